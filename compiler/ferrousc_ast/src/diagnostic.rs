@@ -0,0 +1,109 @@
+use std::fmt;
+
+use ferrousc_diagnostics::{codes, Diagnostics, Span};
+use ferrousc_lexer::{Token, TokenKind};
+
+use crate::nodes::{Diagnostic, ErrorKind};
+use crate::source::SyntaxNode;
+
+impl ErrorKind<'_> {
+    pub fn code(&self) -> codes::Code {
+        match self {
+            ErrorKind::MissingToken{..} => codes::MISSING_TOKEN,
+            ErrorKind::ExpectedStatement{..} => codes::EXPECTED_STATEMENT,
+            ErrorKind::ExpectedExpression{..} => codes::EXPECTED_EXPRESSION,
+            ErrorKind::MisplacedAttribute => codes::MISPLACED_ATTRIBUTE,
+            ErrorKind::ExpectedFunction{..} => codes::EXPECTED_FUNCTION,
+            ErrorKind::LexerError{ diagnostic } => diagnostic.kind.code(),
+            ErrorKind::UnknownAttribute{..} => codes::UNKNOWN_ATTRIBUTE,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::MissingToken{ expected, actual } => write!(f, "expected {}, found {}", Found(Some(*expected)), Found(*actual)),
+            ErrorKind::ExpectedStatement{ actual } => write!(f, "expected a statement, found {}", Found(*actual)),
+            ErrorKind::ExpectedExpression{ actual } => write!(f, "expected an expression, found {}", Found(*actual)),
+            ErrorKind::MisplacedAttribute => write!(f, "attributes can only be put on fn, let, struct and enum declarations"),
+            ErrorKind::ExpectedFunction{ actual } => write!(f, "expected a fn declaration, found {}", Found(*actual)),
+            ErrorKind::LexerError{ diagnostic } => write!(f, "{}", diagnostic.kind),
+            ErrorKind::UnknownAttribute{ name } => write!(f, "unknown attribute `{}`", name),
+        }
+    }
+}
+
+/// how a token is named in a message, None is the end of the file
+struct Found<'src>(Option<Token<'src>>);
+
+impl fmt::Display for Found<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = match self.0 {
+            Some(token) if token.kind != TokenKind::EndOfFile => token,
+            _ => return write!(f, "end of file"),
+        };
+
+        if let Some(text) = token.kind.text() {
+            return write!(f, "`{}`", text);
+        }
+        match token.kind {
+            TokenKind::Identifier if token.value.is_empty() => write!(f, "identifier"),
+            TokenKind::Identifier => write!(f, "identifier `{}`", token.value),
+            TokenKind::NumberLiteral{..} => write!(f, "number literal"),
+            TokenKind::CharLiteral{..} => write!(f, "char literal"),
+            TokenKind::StringLiteral{..}
+            | TokenKind::RawStringLiteral{..}
+            | TokenKind::MultilineStringLiteral{..}
+            | TokenKind::InterpolatedStringLiteral{..} => write!(f, "string literal"),
+            TokenKind::InterpolatedStringEnd => write!(f, "`\"`"),
+            kind => write!(f, "{:?}", kind),
+        }
+    }
+}
+
+impl Diagnostic<'_> {
+    /// the diagnostic as it is shown to the user. token is the token or trivia the diagnostic is attached to
+    pub fn to_diagnostic(&self, token: &Token<'_>) -> ferrousc_diagnostics::Diagnostic {
+        let span = Span::new(token.offset, token.len);
+        let diagnostic = match &self.kind {
+            ErrorKind::LexerError{ diagnostic } => return diagnostic.to_diagnostic(),
+            kind if kind.is_warning() => ferrousc_diagnostics::Diagnostic::warning(kind.code(), kind.to_string()),
+            kind => ferrousc_diagnostics::Diagnostic::error(kind.code(), kind.to_string()),
+        };
+
+        match &self.kind {
+            ErrorKind::MissingToken{ expected, .. } => {
+                let label = format!("expected {}", Found(Some(*expected)));
+                let diagnostic = diagnostic.with_primary_label(span, label);
+                match expected.kind.text() {
+                    Some(text) => diagnostic.with_suggestion(span, text, format!("insert `{}`", text)),
+                    None => diagnostic,
+                }
+            },
+            ErrorKind::MisplacedAttribute => diagnostic
+                .with_primary_label(span, "")
+                .with_help("move the attributes in front of a fn, let, struct or enum"),
+            ErrorKind::ExpectedFunction{..} => diagnostic
+                .with_primary_label(span, "")
+                .with_note("impl blocks and traits can only contain functions"),
+            ErrorKind::UnknownAttribute{..} => diagnostic
+                .with_primary_label(span, "")
+                .with_note("unknown attributes are ignored"),
+            _ => diagnostic.with_primary_label(span, ""),
+        }
+    }
+}
+
+/// Emits the diagnostics of every token and trivia in the node, in the order they are in the source.
+pub fn emit_diagnostics(node: &impl SyntaxNode, diagnostics: &mut Diagnostics) {
+    node.visit_tokens(&mut |token| {
+        for trivia in &token.leading_trivia {
+            diagnostics.extend(trivia.diagnostics.iter().map(|diagnostic| diagnostic.to_diagnostic(&trivia.trivia_token)));
+        }
+        diagnostics.extend(token.diagnostics.iter().map(|diagnostic| diagnostic.to_diagnostic(&token.token)));
+        for trivia in &token.trailing_trivia {
+            diagnostics.extend(trivia.diagnostics.iter().map(|diagnostic| diagnostic.to_diagnostic(&trivia.trivia_token)));
+        }
+    });
+}
@@ -1,2 +1,3 @@
+pub mod diagnostic;
 pub mod nodes;
-
+pub mod source;
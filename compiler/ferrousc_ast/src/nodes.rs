@@ -1,246 +1,648 @@
-use ferrousc_lexer::Token;
+use ferrousc_lexer::{cook_doc_comment, LexerDiagnostic, Token, TokenKind};
 
 #[derive(Debug)]
-pub enum Expr {
+pub enum Expr<'src> {
     Literal { 
-        kind: LiteralKind,  
-        type_kind: Option<TypeKind>,
+        kind: LiteralKind<'src>,  
+        type_kind: Option<TypeKind<'src>>,
     },
     Decorated {
-        l: SyntaxToken,
-        expr: Box<Expr>,
-        r: SyntaxToken,
+        l: SyntaxToken<'src>,
+        expr: Box<Expr<'src>>,
+        r: SyntaxToken<'src>,
     },
     Index {
-        lhs: Box<Expr>,
-        lbracket: SyntaxToken,
-        expr: Box<Expr>,
-        rbracket: SyntaxToken,
+        lhs: Box<Expr<'src>>,
+        lbracket: SyntaxToken<'src>,
+        expr: Box<Expr<'src>>,
+        rbracket: SyntaxToken<'src>,
     },
     ArrayInitializer {
-        lbracket: SyntaxToken,
-        items: Vec<InitializerItem>,
-        rbracket: SyntaxToken,
+        lbracket: SyntaxToken<'src>,
+        items: Vec<InitializerItem<'src>>,
+        rbracket: SyntaxToken<'src>,
     },
+    /// $"Hello {name}!". Later phases lower this to concatenation or formatting calls
+    Interpolated {
+        start: SyntaxToken<'src>,
+        parts: Vec<InterpolatedPart<'src>>,
+        end: SyntaxToken<'src>,
+    },
+    /// a variable, or self inside of a method
     IdentifierUsage {
-        identifier: Identifier,
+        identifier: Identifier<'src>,
     },
     Match {
-        match_token: SyntaxToken,
-        expr: Box<Expr>,
-        body: MatchBody,
+        match_token: SyntaxToken<'src>,
+        expr: Box<Expr<'src>>,
+        body: MatchBody<'src>,
     },
+    /// f(a), point.distance(other) or functions[0](a)
     Call {
-        identifier: Identifier,
-        argument_list: ArgumentList,
+        callee: Box<Expr<'src>>,
+        argument_list: ArgumentList<'src>,
+    },
+    /// max::<int>, the type arguments of a generic function. Without the :: the < would be a comparison
+    Turbofish {
+        expr: Box<Expr<'src>>,
+        colon_colon_token: SyntaxToken<'src>,
+        generic_arguments: GenericArgumentList<'src>,
+    },
+    /// Point { x: 1.0, y: 2.0 }
+    StructLiteral {
+        identifier: Identifier<'src>,
+        l_brace: SyntaxToken<'src>,
+        fields: Vec<FieldInitializer<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+    /// point.x, or point?.x where dot_token is the ?. The field of tuple.0 is the number token
+    FieldAccess {
+        expr: Box<Expr<'src>>,
+        dot_token: SyntaxToken<'src>,
+        field: Identifier<'src>,
     },
     Unary {
-        op: SyntaxToken,
-        operand: Box<Expr>,
+        op: SyntaxToken<'src>,
+        operand: Box<Expr<'src>>,
     },
     Binary {
-        lhs: Box<Expr>,
-        op: SyntaxToken,
-        rhs: Box<Expr>,
+        lhs: Box<Expr<'src>>,
+        op: SyntaxToken<'src>,
+        rhs: Box<Expr<'src>>,
     },
     Ternary {
-        lhs: Box<Expr>,
-        op1: SyntaxToken,
-        mhs: Box<Expr>,
-        op2: SyntaxToken,
-        rhs: Box<Expr>,
+        lhs: Box<Expr<'src>>,
+        op1: SyntaxToken<'src>,
+        mhs: Box<Expr<'src>>,
+        op2: SyntaxToken<'src>,
+        rhs: Box<Expr<'src>>,
+    },
+    /// Where an expression was expected but none could be parsed. The first token holds the diagnostic.
+    /// When there was nothing to skip, it is a zero length Unknown token.
+    Error {
+        tokens: Vec<SyntaxToken<'src>>,
     },
 }
 
 #[derive(Debug)]
-pub enum Stat {
+pub enum Stat<'src> {
     VarDefinition {     
-        let_token: SyntaxToken,
-        mut_token: Option<SyntaxToken>,
-        identifier: Identifier,
-        type_id: Option<TypeId>,
-        initial_value: Option<EqualsValue>,
-        semicolon_token: SyntaxToken,
+        attributes: Vec<Attribute<'src>>,
+        let_token: SyntaxToken<'src>,
+        mut_token: Option<SyntaxToken<'src>>,
+        identifier: Identifier<'src>,
+        type_id: Option<TypeId<'src>>,
+        initial_value: Option<EqualsValue<'src>>,
+        semicolon_token: SyntaxToken<'src>,
     },
     Expr {
-        expr: Expr,
-        semicolon_token: SyntaxToken,
+        expr: Expr<'src>,
+        semicolon_token: SyntaxToken<'src>,
     },
     Block {        
-        l_brace: SyntaxToken,
-        statements: Vec<Stat>,
-        r_brace: SyntaxToken,
+        l_brace: SyntaxToken<'src>,
+        statements: Vec<Stat<'src>>,
+        r_brace: SyntaxToken<'src>,
     },
     For {        
-        for_token: SyntaxToken,
-        identifier: Identifier,
-        in_token: SyntaxToken,
-        range: Expr,
-        statement: Box<Stat>,
+        for_token: SyntaxToken<'src>,
+        identifier: Identifier<'src>,
+        in_token: SyntaxToken<'src>,
+        range: Expr<'src>,
+        statement: Box<Stat<'src>>,
     },
     While {        
-        while_token: SyntaxToken,
-        expression: Expr,
-        statement: Box<Stat>,
+        while_token: SyntaxToken<'src>,
+        expression: Expr<'src>,
+        statement: Box<Stat<'src>>,
     },
     FunctionDefinition {        
-        fn_token: SyntaxToken,
-        identifier: Identifier,
-        parameter_list: ParameterList,
-        return_type: Option<ReturnType>,
-        body: Box<FunctionBody>,
+        attributes: Vec<Attribute<'src>>,
+        fn_token: SyntaxToken<'src>,
+        identifier: Identifier<'src>,
+        generic_parameters: Option<GenericParameterList<'src>>,
+        parameter_list: ParameterList<'src>,
+        return_type: Option<ReturnType<'src>>,
+        body: Box<FunctionBody<'src>>,
+    },
+    StructDefinition {
+        attributes: Vec<Attribute<'src>>,
+        struct_token: SyntaxToken<'src>,
+        identifier: Identifier<'src>,
+        generic_parameters: Option<GenericParameterList<'src>>,
+        l_brace: SyntaxToken<'src>,
+        fields: Vec<FieldDefinition<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+    /// enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }
+    EnumDefinition {
+        attributes: Vec<Attribute<'src>>,
+        enum_token: SyntaxToken<'src>,
+        identifier: Identifier<'src>,
+        generic_parameters: Option<GenericParameterList<'src>>,
+        l_brace: SyntaxToken<'src>,
+        variants: Vec<EnumVariant<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+    /// trait Shape { fn area(self) -> f64; }, functions only holds function definitions and errors.
+    /// The functions without a body have to be implemented by every impl of the trait
+    TraitDefinition {
+        trait_token: SyntaxToken<'src>,
+        identifier: Identifier<'src>,
+        l_brace: SyntaxToken<'src>,
+        functions: Vec<Stat<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+    /// impl Point { fn distance(self, other: Point) -> f64 { ... } }, functions only holds function definitions and errors
+    ImplBlock {
+        impl_token: SyntaxToken<'src>,
+        /// Shape for in impl Shape for Circle
+        trait_for: Option<TraitFor<'src>>,
+        type_kind: TypeKind<'src>,
+        l_brace: SyntaxToken<'src>,
+        functions: Vec<Stat<'src>>,
+        r_brace: SyntaxToken<'src>,
     },
     If {        
-        if_token: SyntaxToken,
-        expression: Expr,
-        statement: Box<Stat>,
-        else_statement: Option<Box<Stat>>,
+        if_token: SyntaxToken<'src>,
+        expression: Expr<'src>,
+        statement: Box<Stat<'src>>,
+        else_statement: Option<Box<Stat<'src>>>,
     },
     Else {        
-        else_token: SyntaxToken,
-        statement: Box<Stat>,
+        else_token: SyntaxToken<'src>,
+        statement: Box<Stat<'src>>,
     },
     Break {        
-        break_token: SyntaxToken,
-        semicolon_token: SyntaxToken,
+        break_token: SyntaxToken<'src>,
+        semicolon_token: SyntaxToken<'src>,
     },
     Return {        
-        return_token: SyntaxToken,
-        expression: Option<Expr>,
-        semicolon_token: SyntaxToken,
+        return_token: SyntaxToken<'src>,
+        expression: Option<Expr<'src>>,
+        semicolon_token: SyntaxToken<'src>,
+    },
+    /// Tokens that are no statement, skipped up to the next point the parser could continue from.
+    /// The first token holds the diagnostic. At the end of the file it is a zero length Unknown token.
+    Error {
+        tokens: Vec<SyntaxToken<'src>>,
+    },
+}
+
+impl<'src> Stat<'src> {
+    /// The text of the doc comments in the leading trivia of a declaration, one line per line of the comments.
+    /// The comments are in front of the attributes, if there are any.
+    /// None when the declaration is undocumented or the statement is no declaration.
+    pub fn docs(&self) -> Option<String> {
+        let first_token = match self {
+            Stat::VarDefinition{ attributes, let_token, .. } => attributes.first().map_or(let_token, |attribute| &attribute.hash_token),
+            Stat::FunctionDefinition{ attributes, fn_token, .. } => attributes.first().map_or(fn_token, |attribute| &attribute.hash_token),
+            Stat::StructDefinition{ attributes, struct_token, .. } => attributes.first().map_or(struct_token, |attribute| &attribute.hash_token),
+            Stat::EnumDefinition{ attributes, enum_token, .. } => attributes.first().map_or(enum_token, |attribute| &attribute.hash_token),
+            Stat::TraitDefinition{ trait_token, .. } => trait_token,
+            _ => return None,
+        };
+
+        let lines: Vec<String> = first_token.leading_trivia.iter()
+            .map(|trivia| &trivia.trivia_token)
+            .filter(|token| matches!(token.kind, TokenKind::DocComment | TokenKind::MultilineDocComment{..}))
+            .map(cook_doc_comment)
+            .collect();
+
+        if lines.is_empty() {
+            None
+        }
+        else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    /// the attributes of a declaration, empty for every other statement
+    pub fn attributes(&self) -> &[Attribute<'src>] {
+        match self {
+            Stat::VarDefinition{ attributes, .. }
+            | Stat::FunctionDefinition{ attributes, .. }
+            | Stat::StructDefinition{ attributes, .. }
+            | Stat::EnumDefinition{ attributes, .. } => attributes,
+            _ => &[],
+        }
+    }
+
+    /// the first attribute with the name, like "inline" for #[inline]
+    pub fn attribute(&self, name: &str) -> Option<&Attribute<'src>> {
+        self.attributes().iter().find(|attribute| attribute.name() == name)
+    }
+}
+
+/// #[name] or #[name(arguments)] in front of a fn, let, struct or enum
+#[derive(Debug)]
+pub struct Attribute<'src> {
+    pub hash_token: SyntaxToken<'src>,
+    pub l_bracket: SyntaxToken<'src>,
+    pub identifier: Identifier<'src>,
+    pub arguments: Option<AttributeArgumentList<'src>>,
+    pub r_bracket: SyntaxToken<'src>,
+}
+
+impl<'src> Attribute<'src> {
+    pub fn name(&self) -> &'src str {
+        self.identifier.identifier.token.value
+    }
+}
+
+#[derive(Debug)]
+pub struct AttributeArgumentList<'src> {
+    pub l_paran: SyntaxToken<'src>,
+    pub arguments: Vec<AttributeArgument<'src>>,
+    pub r_paran: SyntaxToken<'src>,
+}
+
+#[derive(Debug)]
+pub struct AttributeArgument<'src> {
+    pub kind: AttributeArgumentKind<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub enum AttributeArgumentKind<'src> {
+    /// "C" in #[extern("C")]
+    Literal { expr: Box<Expr<'src>> },
+    /// note = "msg" in #[deprecated(note = "msg")]
+    KeyValue {
+        key: Identifier<'src>,
+        equals_token: SyntaxToken<'src>,
+        value: Box<Expr<'src>>,
     },
+    /// a name with its own arguments, like any(a, b) in #[cfg(any(a, b))]
+    Nested {
+        identifier: Identifier<'src>,
+        arguments: Option<AttributeArgumentList<'src>>,
+    },
+}
+
+#[derive(Debug)]
+pub enum TypeKind<'src> {
+    /// Point, or List<int> with type arguments
+    UserDefined { identifier: Identifier<'src>, generic_arguments: Option<Box<GenericArgumentList<'src>>> },
+    Internal { identifier: Identifier<'src> },
+    /// impl Shape + Named, any type that implements all of the traits. Calls are dispatched statically
+    ImplTrait { impl_token: SyntaxToken<'src>, bounds: Vec<TraitBound<'src>> },
+}
+
+/// a trait in a list of bounds, plus_token is the + behind it
+#[derive(Debug)]
+pub struct TraitBound<'src> {
+    pub identifier: Identifier<'src>,
+    pub plus_token: Option<SyntaxToken<'src>>,
 }
 
+/// <T: Shape, U> behind the name of a generic fn, struct or enum
 #[derive(Debug)]
-pub enum TypeKind {
-    UserDefined { identifier: Identifier },
-    Internal { identifier: Identifier },
+pub struct GenericParameterList<'src> {
+    pub less_token: SyntaxToken<'src>,
+    pub parameters: Vec<GenericParameter<'src>>,
+    pub greater_token: SyntaxToken<'src>,
 }
 
 #[derive(Debug)]
-pub enum FunctionBody {
-    BlockStatement { block: Stat },
-    ExpressionBody { fat_arrow_token: SyntaxToken, statement: Stat },
+pub struct GenericParameter<'src> {
+    pub identifier: Identifier<'src>,
+    /// : Shape + Named, the traits every type argument has to implement
+    pub bounds: Option<GenericBounds<'src>>,
+    pub comma_token: Option<SyntaxToken<'src>>,
 }
 
 #[derive(Debug)]
-pub enum LiteralKind {
-    Number { number_literal: SyntaxToken },
-    String { string_literal: SyntaxToken },
-    Char { char_literal: SyntaxToken },
-    Bool { bool_literal: SyntaxToken },
+pub struct GenericBounds<'src> {
+    pub colon_token: SyntaxToken<'src>,
+    pub bounds: Vec<TraitBound<'src>>,
 }
 
+/// <int, List<T>> behind a type or behind the :: of a turbofish
 #[derive(Debug)]
-pub struct InitializerItem {
-    pub expr: Expr,
-    pub comma_token: Option<SyntaxToken>,
+pub struct GenericArgumentList<'src> {
+    pub less_token: SyntaxToken<'src>,
+    pub arguments: Vec<GenericArgument<'src>>,
+    pub greater_token: SyntaxToken<'src>,
 }
 
 #[derive(Debug)]
-pub struct ArgumentList {
-    pub l_paran: SyntaxToken,
-    pub r_paran: SyntaxToken,
-    pub arguments: Vec<Argument>,
+pub struct GenericArgument<'src> {
+    pub type_kind: TypeKind<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
 }
 
 #[derive(Debug)]
-pub struct MatchBody {
-    pub l_brace: SyntaxToken,
-    pub r_brace: SyntaxToken,
-    pub arms: Vec<MatchArm>,
+pub struct TraitFor<'src> {
+    pub identifier: Identifier<'src>,
+    pub for_token: SyntaxToken<'src>,
 }
 
 #[derive(Debug)]
-pub struct MatchArm {
-    pub pattern: MatchPattern,
-    pub fat_arrow: SyntaxToken,
-    pub expr: Expr,
-    pub comma_token: Option<SyntaxToken>,
+pub enum FunctionBody<'src> {
+    BlockStatement { block: Stat<'src> },
+    ExpressionBody { fat_arrow_token: SyntaxToken<'src>, statement: Stat<'src> },
+    /// fn area(self) -> f64; in a trait, a function that has to be implemented
+    Signature { semicolon_token: SyntaxToken<'src> },
 }
 
 #[derive(Debug)]
-pub enum MatchPattern {
-    Literal(SyntaxToken),
-    Identifier(Identifier),
+pub enum LiteralKind<'src> {
+    Number { number_literal: SyntaxToken<'src> },
+    String { string_literal: SyntaxToken<'src> },
+    Char { char_literal: SyntaxToken<'src> },
+    Bool { bool_literal: SyntaxToken<'src> },
 }
 
 #[derive(Debug)]
-pub struct Argument {
-    pub expr: Expr,
-    pub comma_token: Option<SyntaxToken>,
+pub enum InterpolatedPart<'src> {
+    /// use ferrousc_lexer::cook_string to get the text with {{ and }} unescaped
+    Text { text: SyntaxToken<'src> },
+    Hole { 
+        l_brace: SyntaxToken<'src>,
+        expr: Box<Expr<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+}
+
+#[derive(Debug)]
+pub struct InitializerItem<'src> {
+    pub expr: Expr<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
 }
 
 #[derive(Debug)]
-pub struct ReturnType {
-    pub small_arrow_token: SyntaxToken,
-    pub type_kind: TypeKind,
+pub struct ArgumentList<'src> {
+    pub l_paran: SyntaxToken<'src>,
+    pub r_paran: SyntaxToken<'src>,
+    pub arguments: Vec<Argument<'src>>,
 }
 
 #[derive(Debug)]
-pub struct ParameterList {
-    pub l_paran: SyntaxToken,
-    pub r_paran: SyntaxToken,
-    pub parameters: Vec<Parameter>,
+pub struct MatchBody<'src> {
+    pub l_brace: SyntaxToken<'src>,
+    pub r_brace: SyntaxToken<'src>,
+    pub arms: Vec<MatchArm<'src>>,
 }
 
 #[derive(Debug)]
-pub struct Parameter {
-    pub identifier: Identifier,
-    pub type_id: TypeId,
-    pub comma_token: Option<SyntaxToken>,
+pub struct MatchArm<'src> {
+    pub pattern: MatchPattern<'src>,
+    pub guard: Option<MatchGuard<'src>>,
+    pub fat_arrow: SyntaxToken<'src>,
+    pub expr: Expr<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
 }
 
+/// if x > 0 between the pattern and the => of an arm
 #[derive(Debug)]
-pub struct SyntaxToken {
-    pub token: Token,
-    pub trivia: Vec<Trivia>,
-    pub diagnostics: Vec<Diagnostic>,
+pub struct MatchGuard<'src> {
+    pub if_token: SyntaxToken<'src>,
+    pub expr: Expr<'src>,
+}
+
+#[derive(Debug)]
+pub enum MatchPattern<'src> {
+    Literal(SyntaxToken<'src>),
+    /// binds the value to a new name. Whether it names a unit variant instead is decided by the semantic phase
+    Identifier(Identifier<'src>),
+    /// _, or default from Edition2026 on
+    Wildcard(SyntaxToken<'src>),
+    /// Shape::Empty, Shape::Circle(r), Shape::Rect { w, h: 0 } or Some(x)
+    Variant {
+        path: Path<'src>,
+        payload: Option<PatternPayload<'src>>,
+    },
+    /// 1..=5 or 'a'..'z', op is the .. or ..=
+    Range {
+        start: SyntaxToken<'src>,
+        op: SyntaxToken<'src>,
+        end: SyntaxToken<'src>,
+    },
+    /// a | b, matches when either side matches
+    Or {
+        lhs: Box<MatchPattern<'src>>,
+        bar_token: SyntaxToken<'src>,
+        rhs: Box<MatchPattern<'src>>,
+    },
+}
+
+#[derive(Debug)]
+pub enum PatternPayload<'src> {
+    Tuple {
+        l_paran: SyntaxToken<'src>,
+        patterns: Vec<PatternItem<'src>>,
+        r_paran: SyntaxToken<'src>,
+    },
+    Struct {
+        l_brace: SyntaxToken<'src>,
+        fields: Vec<FieldPattern<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
 }
 
 #[derive(Debug)]
-pub struct Diagnostic {
-    pub kind: ErrorKind,
+pub struct PatternItem<'src> {
+    pub pattern: MatchPattern<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
 }
 
+/// w: 0 in Rect { w: 0 }, or just w, which binds the field to a variable of the same name
 #[derive(Debug)]
-pub enum ErrorKind {
+pub struct FieldPattern<'src> {
+    pub identifier: Identifier<'src>,
+    pub value: Option<FieldPatternValue<'src>>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub struct FieldPatternValue<'src> {
+    pub colon_token: SyntaxToken<'src>,
+    pub pattern: MatchPattern<'src>,
+}
+
+/// Shape::Circle, the segments are separated by ::
+#[derive(Debug)]
+pub struct Path<'src> {
+    pub segments: Vec<PathSegment<'src>>,
+}
+
+#[derive(Debug)]
+pub struct PathSegment<'src> {
+    pub identifier: Identifier<'src>,
+    /// the :: behind the identifier, None for the last segment
+    pub colon_colon_token: Option<SyntaxToken<'src>>,
+}
+
+/// Circle(f64) or Rect { w: f64, h: f64 } in an enum declaration
+#[derive(Debug)]
+pub struct EnumVariant<'src> {
+    pub identifier: Identifier<'src>,
+    pub payload: Option<VariantPayload<'src>>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub enum VariantPayload<'src> {
+    Tuple {
+        l_paran: SyntaxToken<'src>,
+        fields: Vec<TupleField<'src>>,
+        r_paran: SyntaxToken<'src>,
+    },
+    Struct {
+        l_brace: SyntaxToken<'src>,
+        fields: Vec<FieldDefinition<'src>>,
+        r_brace: SyntaxToken<'src>,
+    },
+}
+
+#[derive(Debug)]
+pub struct TupleField<'src> {
+    pub type_kind: TypeKind<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+/// x: f64 in struct Point { x: f64 }
+#[derive(Debug)]
+pub struct FieldDefinition<'src> {
+    pub identifier: Identifier<'src>,
+    pub type_id: TypeId<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+/// x: 1.0 in Point { x: 1.0 }
+#[derive(Debug)]
+pub struct FieldInitializer<'src> {
+    pub identifier: Identifier<'src>,
+    pub colon_token: SyntaxToken<'src>,
+    pub expr: Expr<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub struct Argument<'src> {
+    pub expr: Expr<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub struct ReturnType<'src> {
+    pub small_arrow_token: SyntaxToken<'src>,
+    pub type_kind: TypeKind<'src>,
+}
+
+#[derive(Debug)]
+pub struct ParameterList<'src> {
+    pub l_paran: SyntaxToken<'src>,
+    pub r_paran: SyntaxToken<'src>,
+    /// self or mut self in front of the parameters of a method
+    pub receiver: Option<SelfParameter<'src>>,
+    pub parameters: Vec<Parameter<'src>>,
+}
+
+#[derive(Debug)]
+pub struct SelfParameter<'src> {
+    pub mut_token: Option<SyntaxToken<'src>>,
+    pub self_token: SyntaxToken<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+#[derive(Debug)]
+pub struct Parameter<'src> {
+    pub identifier: Identifier<'src>,
+    pub type_id: TypeId<'src>,
+    pub comma_token: Option<SyntaxToken<'src>>,
+}
+
+/// the trivia follows the same rules as ferrousc_lexer::TokenWithTrivia
+#[derive(Debug)]
+pub struct SyntaxToken<'src> {
+    pub leading_trivia: Vec<Trivia<'src>>,
+    pub token: Token<'src>,
+    pub trailing_trivia: Vec<Trivia<'src>>,
+    pub diagnostics: Vec<Diagnostic<'src>>,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic<'src> {
+    pub kind: ErrorKind<'src>,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind<'src> {
     MissingToken{ 
-        expected: Token,
-        actual: Option<Token>,
+        expected: Token<'src>,
+        actual: Option<Token<'src>>,
+    },
+    ExpectedStatement {
+        actual: Option<Token<'src>>,
+    },
+    ExpectedExpression {
+        actual: Option<Token<'src>>,
     },
+    /// attributes in front of something that is not a fn, let, struct or enum
+    MisplacedAttribute,
+    /// anything but a fn declaration in an impl block or trait
+    ExpectedFunction {
+        actual: Option<Token<'src>>,
+    },
+    /// the token or trivia was malformed, see ferrousc_lexer::diagnose
+    LexerError {
+        diagnostic: LexerDiagnostic,
+    },
+    /// warning: an attribute the compiler does not know, it is ignored
+    UnknownAttribute {
+        name: &'src str,
+    },
+}
+
+impl ErrorKind<'_> {
+    /// warnings don't stop the compilation
+    pub fn is_warning(&self) -> bool {
+        match self {
+            ErrorKind::MissingToken{..}
+            | ErrorKind::ExpectedStatement{..}
+            | ErrorKind::ExpectedExpression{..}
+            | ErrorKind::MisplacedAttribute
+            | ErrorKind::ExpectedFunction{..} => false,
+            ErrorKind::LexerError{ diagnostic } => diagnostic.kind.is_warning(),
+            ErrorKind::UnknownAttribute{..} => true,
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct Trivia {
-    pub trivia_token: Token,
+pub struct Trivia<'src> {
+    pub trivia_token: Token<'src>,
+    pub diagnostics: Vec<Diagnostic<'src>>,
 }
 
 #[derive(Debug)]
-pub struct Identifier {
-    pub identifier: SyntaxToken,
+pub struct Identifier<'src> {
+    pub identifier: SyntaxToken<'src>,
 }
 
 #[derive(Debug)]
-pub struct TypeId {
-    pub colon_token: SyntaxToken,
-    pub type_kind: TypeKind,
+pub struct TypeId<'src> {
+    pub colon_token: SyntaxToken<'src>,
+    pub type_kind: TypeKind<'src>,
 }
 
 #[derive(Debug)]
-pub struct EqualsValue {
-    pub equals_token: SyntaxToken,
-    pub expression: Expr,
+pub struct EqualsValue<'src> {
+    pub equals_token: SyntaxToken<'src>,
+    pub expression: Expr<'src>,
 }
 
 #[derive(Debug)]
-pub struct CompilationUnit {
-    pub leading_trivia: Vec<Trivia>,
-    pub statements: Vec<Stat>,
+pub struct CompilationUnit<'src> {
+    pub statements: Vec<Stat<'src>>,
+    /// holds the trivia after the last statement
+    pub end_of_file: SyntaxToken<'src>,
 }
 
-impl CompilationUnit {
-    pub fn walk(&self, it: impl Fn(&Stat)) {
+impl<'src> CompilationUnit<'src> {
+    pub fn walk(&self, mut it: impl FnMut(&Stat<'src>)) {
         for st in &self.statements {
             it(st);
         }
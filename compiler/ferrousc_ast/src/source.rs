@@ -0,0 +1,663 @@
+use ferrousc_diagnostics::Span;
+use ferrousc_lexer::Token;
+
+use crate::nodes::*;
+
+/// Rebuilds the source of a node byte for byte, including all of its trivia.
+/// The source of a CompilationUnit is always exactly the source it was parsed from.
+pub trait ToSource {
+    fn write_source(&self, source: &mut String);
+
+    fn to_source(&self) -> String {
+        let mut source = String::new();
+        self.write_source(&mut source);
+        source
+    }
+}
+
+/// Anything in the syntax tree. Its tokens, with their trivia, are all of its source.
+pub trait SyntaxNode {
+    /// calls visit with every token of the node, in the order they are in the source
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>));
+}
+
+/// from the first to the end of the last token of the node
+pub fn node_span(node: &impl SyntaxNode) -> Span {
+    let mut start = None;
+    let mut end = 0;
+    node.visit_tokens(&mut |token| {
+        start.get_or_insert(token.token.offset);
+        end = token.token.offset + token.token.len;
+    });
+    let start = start.unwrap_or(end);
+    Span::new(start, end - start)
+}
+
+impl<T: SyntaxNode + ?Sized> SyntaxNode for Box<T> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        (**self).visit_tokens(visit);
+    }
+}
+
+impl<T: SyntaxNode> SyntaxNode for Option<T> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        if let Some(node) = self {
+            node.visit_tokens(visit);
+        }
+    }
+}
+
+impl<T: SyntaxNode> SyntaxNode for Vec<T> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        for node in self {
+            node.visit_tokens(visit);
+        }
+    }
+}
+
+impl<T: SyntaxNode> ToSource for T {
+    fn write_source(&self, source: &mut String) {
+        self.visit_tokens(&mut |token| {
+            token.leading_trivia.iter().for_each(|trivia| trivia.write_source(source));
+            token.token.write_source(source);
+            token.trailing_trivia.iter().for_each(|trivia| trivia.write_source(source));
+        });
+    }
+}
+
+impl ToSource for Token<'_> {
+    fn write_source(&self, source: &mut String) {
+        source.push_str(self.value);
+    }
+}
+
+impl ToSource for Trivia<'_> {
+    fn write_source(&self, source: &mut String) {
+        self.trivia_token.write_source(source);
+    }
+}
+
+impl SyntaxNode for SyntaxToken<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        visit(self);
+    }
+}
+
+impl SyntaxNode for CompilationUnit<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.statements.visit_tokens(visit);
+        self.end_of_file.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Stat<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            Stat::VarDefinition{ attributes, let_token, mut_token, identifier, type_id, initial_value, semicolon_token } => {
+                attributes.visit_tokens(visit);
+                let_token.visit_tokens(visit);
+                mut_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                type_id.visit_tokens(visit);
+                initial_value.visit_tokens(visit);
+                semicolon_token.visit_tokens(visit);
+            },
+            Stat::Expr{ expr, semicolon_token } => {
+                expr.visit_tokens(visit);
+                semicolon_token.visit_tokens(visit);
+            },
+            Stat::Block{ l_brace, statements, r_brace } => {
+                l_brace.visit_tokens(visit);
+                statements.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Stat::For{ for_token, identifier, in_token, range, statement } => {
+                for_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                in_token.visit_tokens(visit);
+                range.visit_tokens(visit);
+                statement.visit_tokens(visit);
+            },
+            Stat::While{ while_token, expression, statement } => {
+                while_token.visit_tokens(visit);
+                expression.visit_tokens(visit);
+                statement.visit_tokens(visit);
+            },
+            Stat::FunctionDefinition{ attributes, fn_token, identifier, generic_parameters, parameter_list, return_type, body } => {
+                attributes.visit_tokens(visit);
+                fn_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                generic_parameters.visit_tokens(visit);
+                parameter_list.visit_tokens(visit);
+                return_type.visit_tokens(visit);
+                body.visit_tokens(visit);
+            },
+            Stat::StructDefinition{ attributes, struct_token, identifier, generic_parameters, l_brace, fields, r_brace } => {
+                attributes.visit_tokens(visit);
+                struct_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                generic_parameters.visit_tokens(visit);
+                l_brace.visit_tokens(visit);
+                fields.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Stat::EnumDefinition{ attributes, enum_token, identifier, generic_parameters, l_brace, variants, r_brace } => {
+                attributes.visit_tokens(visit);
+                enum_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                generic_parameters.visit_tokens(visit);
+                l_brace.visit_tokens(visit);
+                variants.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Stat::TraitDefinition{ trait_token, identifier, l_brace, functions, r_brace } => {
+                trait_token.visit_tokens(visit);
+                identifier.visit_tokens(visit);
+                l_brace.visit_tokens(visit);
+                functions.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Stat::ImplBlock{ impl_token, trait_for, type_kind, l_brace, functions, r_brace } => {
+                impl_token.visit_tokens(visit);
+                trait_for.visit_tokens(visit);
+                type_kind.visit_tokens(visit);
+                l_brace.visit_tokens(visit);
+                functions.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Stat::If{ if_token, expression, statement, else_statement } => {
+                if_token.visit_tokens(visit);
+                expression.visit_tokens(visit);
+                statement.visit_tokens(visit);
+                else_statement.visit_tokens(visit);
+            },
+            Stat::Else{ else_token, statement } => {
+                else_token.visit_tokens(visit);
+                statement.visit_tokens(visit);
+            },
+            Stat::Break{ break_token, semicolon_token } => {
+                break_token.visit_tokens(visit);
+                semicolon_token.visit_tokens(visit);
+            },
+            Stat::Return{ return_token, expression, semicolon_token } => {
+                return_token.visit_tokens(visit);
+                expression.visit_tokens(visit);
+                semicolon_token.visit_tokens(visit);
+            },
+            Stat::Error{ tokens } => tokens.visit_tokens(visit),
+        }
+    }
+}
+
+impl SyntaxNode for Expr<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            Expr::Literal{ kind, type_kind } => {
+                kind.visit_tokens(visit);
+                type_kind.visit_tokens(visit);
+            },
+            Expr::Decorated{ l, expr, r } => {
+                l.visit_tokens(visit);
+                expr.visit_tokens(visit);
+                r.visit_tokens(visit);
+            },
+            Expr::Index{ lhs, lbracket, expr, rbracket } => {
+                lhs.visit_tokens(visit);
+                lbracket.visit_tokens(visit);
+                expr.visit_tokens(visit);
+                rbracket.visit_tokens(visit);
+            },
+            Expr::ArrayInitializer{ lbracket, items, rbracket } => {
+                lbracket.visit_tokens(visit);
+                items.visit_tokens(visit);
+                rbracket.visit_tokens(visit);
+            },
+            Expr::Interpolated{ start, parts, end } => {
+                start.visit_tokens(visit);
+                parts.visit_tokens(visit);
+                end.visit_tokens(visit);
+            },
+            Expr::IdentifierUsage{ identifier } => identifier.visit_tokens(visit),
+            Expr::Match{ match_token, expr, body } => {
+                match_token.visit_tokens(visit);
+                expr.visit_tokens(visit);
+                body.visit_tokens(visit);
+            },
+            Expr::Call{ callee, argument_list } => {
+                callee.visit_tokens(visit);
+                argument_list.visit_tokens(visit);
+            },
+            Expr::Turbofish{ expr, colon_colon_token, generic_arguments } => {
+                expr.visit_tokens(visit);
+                colon_colon_token.visit_tokens(visit);
+                generic_arguments.visit_tokens(visit);
+            },
+            Expr::StructLiteral{ identifier, l_brace, fields, r_brace } => {
+                identifier.visit_tokens(visit);
+                l_brace.visit_tokens(visit);
+                fields.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+            Expr::FieldAccess{ expr, dot_token, field } => {
+                expr.visit_tokens(visit);
+                dot_token.visit_tokens(visit);
+                field.visit_tokens(visit);
+            },
+            Expr::Unary{ op, operand } => {
+                // postfix operators like x++ come after their operand
+                if op.token.offset <= first_token(operand).token.offset {
+                    op.visit_tokens(visit);
+                    operand.visit_tokens(visit);
+                }
+                else {
+                    operand.visit_tokens(visit);
+                    op.visit_tokens(visit);
+                }
+            },
+            Expr::Binary{ lhs, op, rhs } => {
+                lhs.visit_tokens(visit);
+                op.visit_tokens(visit);
+                rhs.visit_tokens(visit);
+            },
+            Expr::Ternary{ lhs, op1, mhs, op2, rhs } => {
+                lhs.visit_tokens(visit);
+                op1.visit_tokens(visit);
+                mhs.visit_tokens(visit);
+                op2.visit_tokens(visit);
+                rhs.visit_tokens(visit);
+            },
+            Expr::Error{ tokens } => tokens.visit_tokens(visit),
+        }
+    }
+}
+
+/// the token an expression starts with
+fn first_token<'a, 'src>(expr: &'a Expr<'src>) -> &'a SyntaxToken<'src> {
+    match expr {
+        Expr::Literal{ kind, .. } => match kind {
+            LiteralKind::Number{ number_literal: token }
+            | LiteralKind::String{ string_literal: token }
+            | LiteralKind::Char{ char_literal: token }
+            | LiteralKind::Bool{ bool_literal: token } => token,
+        },
+        Expr::Decorated{ l, .. } => l,
+        Expr::Index{ lhs, .. } | Expr::Binary{ lhs, .. } | Expr::Ternary{ lhs, .. } => first_token(lhs),
+        Expr::ArrayInitializer{ lbracket, .. } => lbracket,
+        Expr::Interpolated{ start, .. } => start,
+        Expr::IdentifierUsage{ identifier }
+        | Expr::StructLiteral{ identifier, .. } => &identifier.identifier,
+        Expr::FieldAccess{ expr, .. }
+        | Expr::Call{ callee: expr, .. }
+        | Expr::Turbofish{ expr, .. } => first_token(expr),
+        Expr::Match{ match_token, .. } => match_token,
+        Expr::Error{ tokens } => &tokens[0],
+        Expr::Unary{ op, operand } => {
+            let operand = first_token(operand);
+            if op.token.offset <= operand.token.offset { op } else { operand }
+        },
+    }
+}
+
+impl SyntaxNode for LiteralKind<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            LiteralKind::Number{ number_literal: token }
+            | LiteralKind::String{ string_literal: token }
+            | LiteralKind::Char{ char_literal: token }
+            | LiteralKind::Bool{ bool_literal: token } => token.visit_tokens(visit),
+        }
+    }
+}
+
+impl SyntaxNode for TypeKind<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            TypeKind::UserDefined{ identifier, generic_arguments } => {
+                identifier.visit_tokens(visit);
+                generic_arguments.visit_tokens(visit);
+            },
+            TypeKind::Internal{ identifier } => identifier.visit_tokens(visit),
+            TypeKind::ImplTrait{ impl_token, bounds } => {
+                impl_token.visit_tokens(visit);
+                bounds.visit_tokens(visit);
+            },
+        }
+    }
+}
+
+impl SyntaxNode for TraitBound<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.plus_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for GenericParameterList<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.less_token.visit_tokens(visit);
+        self.parameters.visit_tokens(visit);
+        self.greater_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for GenericParameter<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.bounds.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for GenericBounds<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.colon_token.visit_tokens(visit);
+        self.bounds.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for GenericArgumentList<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.less_token.visit_tokens(visit);
+        self.arguments.visit_tokens(visit);
+        self.greater_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for GenericArgument<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.type_kind.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for TraitFor<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.for_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for FunctionBody<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            FunctionBody::BlockStatement{ block } => block.visit_tokens(visit),
+            FunctionBody::ExpressionBody{ fat_arrow_token, statement } => {
+                fat_arrow_token.visit_tokens(visit);
+                statement.visit_tokens(visit);
+            },
+            FunctionBody::Signature{ semicolon_token } => semicolon_token.visit_tokens(visit),
+        }
+    }
+}
+
+impl SyntaxNode for InterpolatedPart<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            InterpolatedPart::Text{ text } => text.visit_tokens(visit),
+            InterpolatedPart::Hole{ l_brace, expr, r_brace } => {
+                l_brace.visit_tokens(visit);
+                expr.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+        }
+    }
+}
+
+impl SyntaxNode for InitializerItem<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.expr.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for ArgumentList<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.l_paran.visit_tokens(visit);
+        self.arguments.visit_tokens(visit);
+        self.r_paran.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for FieldDefinition<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.type_id.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for FieldInitializer<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.colon_token.visit_tokens(visit);
+        self.expr.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Argument<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.expr.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for MatchBody<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.l_brace.visit_tokens(visit);
+        self.arms.visit_tokens(visit);
+        self.r_brace.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for MatchArm<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.pattern.visit_tokens(visit);
+        self.guard.visit_tokens(visit);
+        self.fat_arrow.visit_tokens(visit);
+        self.expr.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for MatchGuard<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.if_token.visit_tokens(visit);
+        self.expr.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for MatchPattern<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            MatchPattern::Literal(token) | MatchPattern::Wildcard(token) => token.visit_tokens(visit),
+            MatchPattern::Identifier(identifier) => identifier.visit_tokens(visit),
+            MatchPattern::Variant{ path, payload } => {
+                path.visit_tokens(visit);
+                payload.visit_tokens(visit);
+            },
+            MatchPattern::Range{ start, op, end } => {
+                start.visit_tokens(visit);
+                op.visit_tokens(visit);
+                end.visit_tokens(visit);
+            },
+            MatchPattern::Or{ lhs, bar_token, rhs } => {
+                lhs.visit_tokens(visit);
+                bar_token.visit_tokens(visit);
+                rhs.visit_tokens(visit);
+            },
+        }
+    }
+}
+
+impl SyntaxNode for PatternPayload<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            PatternPayload::Tuple{ l_paran, patterns, r_paran } => {
+                l_paran.visit_tokens(visit);
+                patterns.visit_tokens(visit);
+                r_paran.visit_tokens(visit);
+            },
+            PatternPayload::Struct{ l_brace, fields, r_brace } => {
+                l_brace.visit_tokens(visit);
+                fields.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+        }
+    }
+}
+
+impl SyntaxNode for PatternItem<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.pattern.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for FieldPattern<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.value.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for FieldPatternValue<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.colon_token.visit_tokens(visit);
+        self.pattern.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Path<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.segments.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for PathSegment<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.colon_colon_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for EnumVariant<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.payload.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for VariantPayload<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match self {
+            VariantPayload::Tuple{ l_paran, fields, r_paran } => {
+                l_paran.visit_tokens(visit);
+                fields.visit_tokens(visit);
+                r_paran.visit_tokens(visit);
+            },
+            VariantPayload::Struct{ l_brace, fields, r_brace } => {
+                l_brace.visit_tokens(visit);
+                fields.visit_tokens(visit);
+                r_brace.visit_tokens(visit);
+            },
+        }
+    }
+}
+
+impl SyntaxNode for TupleField<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.type_kind.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for ReturnType<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.small_arrow_token.visit_tokens(visit);
+        self.type_kind.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for ParameterList<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.l_paran.visit_tokens(visit);
+        self.receiver.visit_tokens(visit);
+        self.parameters.visit_tokens(visit);
+        self.r_paran.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for SelfParameter<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.mut_token.visit_tokens(visit);
+        self.self_token.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Parameter<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+        self.type_id.visit_tokens(visit);
+        self.comma_token.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Identifier<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.identifier.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for TypeId<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.colon_token.visit_tokens(visit);
+        self.type_kind.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for EqualsValue<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.equals_token.visit_tokens(visit);
+        self.expression.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for Attribute<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.hash_token.visit_tokens(visit);
+        self.l_bracket.visit_tokens(visit);
+        self.identifier.visit_tokens(visit);
+        self.arguments.visit_tokens(visit);
+        self.r_bracket.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for AttributeArgumentList<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        self.l_paran.visit_tokens(visit);
+        self.arguments.visit_tokens(visit);
+        self.r_paran.visit_tokens(visit);
+    }
+}
+
+impl SyntaxNode for AttributeArgument<'_> {
+    fn visit_tokens(&self, visit: &mut dyn FnMut(&SyntaxToken<'_>)) {
+        match &self.kind {
+            AttributeArgumentKind::Literal{ expr } => expr.visit_tokens(visit),
+            AttributeArgumentKind::KeyValue{ key, equals_token, value } => {
+                key.visit_tokens(visit);
+                equals_token.visit_tokens(visit);
+                value.visit_tokens(visit);
+            },
+            AttributeArgumentKind::Nested{ identifier, arguments } => {
+                identifier.visit_tokens(visit);
+                arguments.visit_tokens(visit);
+            },
+        }
+        self.comma_token.visit_tokens(visit);
+    }
+}
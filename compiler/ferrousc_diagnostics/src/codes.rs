@@ -0,0 +1,100 @@
+use std::fmt;
+
+/// A stable identifier of a kind of diagnostic, shown as E0001. Codes are never reused,
+/// so they can be searched for and explained. All of them are declared in this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Code(u16);
+
+impl Code {
+    pub fn number(&self) -> u16 {
+        self.0
+    }
+
+    /// a longer explanation of the diagnostic than its message
+    pub fn description(&self) -> &'static str {
+        ALL.iter()
+            .find(|(code, _)| code == self)
+            .map_or("", |(_, description)| description)
+    }
+
+    /// looks up a code by the way it is shown, like "E0001"
+    pub fn from_name(name: &str) -> Option<Code> {
+        ALL.iter().map(|(code, _)| *code).find(|code| code.to_string() == name)
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{:04}", self.0)
+    }
+}
+
+macro_rules! codes {
+    ($($name:ident = $number:literal, $description:literal;)*) => {
+        $(
+            #[doc = $description]
+            pub const $name: Code = Code($number);
+        )*
+
+        /// every code with its description
+        pub const ALL: &[(Code, &str)] = &[$(($name, $description)),*];
+    };
+}
+
+// E00xx are reported by the lexer, E01xx by the parser, E02xx by the semantic checks, E03xx by the LLVM backend
+codes! {
+    UNKNOWN_CHAR = 1, "A char that can not start any token, like a stray '`'.";
+    UNTERMINATED_STRING_LITERAL = 2, "A string literal without its closing quote. It continues up to the end of the file.";
+    UNTERMINATED_CHAR_LITERAL = 3, "A char literal without its closing quote.";
+    UNTERMINATED_MULTILINE_COMMENT = 4, "A '/*' without a matching '*/'. Block comments nest, so every '/*' needs its own '*/'.";
+    UNTERMINATED_INTERPOLATION_HOLE = 5, "A '{' in an interpolated string without a matching '}'. Use '{{' for a literal brace.";
+    MISSING_DIGITS = 6, "A number literal like 0x or 0b_ without any digits.";
+    INVALID_DIGIT = 7, "A digit that does not exist in the base of the number literal, like the 2 in 0b102.";
+    INVALID_NUMBER_SUFFIX = 8, "A number literal followed by something that is no type suffix, like 5_abc or 5i128.";
+    FLOAT_SUFFIX_ON_NON_DECIMAL = 9, "A float suffix on a binary, octal or hexadecimal literal. Only decimal literals can be floats.";
+    INTEGER_SUFFIX_ON_FLOAT = 10, "An integer suffix on a literal with a fraction or exponent, like 1.5i32.";
+    INVALID_LITERAL = 11, "An invalid escape sequence, or a char literal that does not contain exactly one char.";
+    RESERVED_KEYWORD = 12, "A word that is reserved for an upcoming feature in the edition of the source was used as an identifier.";
+    MISPLACED_BYTE_ORDER_MARK = 13, "A byte order mark (U+FEFF) anywhere but at the very start of the file.";
+    MIXED_SCRIPT_IDENTIFIER = 14, "An identifier that mixes chars of different scripts, like latin and cyrillic.";
+    LOOKALIKE_IDENTIFIER = 15, "An identifier that looks like an ascii identifier, but contains non-ascii chars.";
+    CONFUSABLE_IDENTIFIER = 16, "Two different identifiers that look the same.";
+    MISSING_TOKEN = 100, "The parser expected a specific token, like a ';' at the end of a statement.";
+    EXPECTED_STATEMENT = 101, "Tokens that can not start a statement. They are skipped up to the next statement.";
+    EXPECTED_EXPRESSION = 102, "An expression is missing, like the value in 'let a = ;'.";
+    MISPLACED_ATTRIBUTE = 103, "Attributes can only be put on fn, let, struct and enum declarations.";
+    UNKNOWN_ATTRIBUTE = 104, "An attribute the compiler does not know. It is ignored.";
+    EXPECTED_FUNCTION = 105, "Something other than a fn declaration in an impl block or trait. It is skipped.";
+    MISSING_TRAIT_FUNCTION = 200, "An impl of a trait does not implement a function that the trait declares without a body.";
+    TRAIT_FUNCTION_MISMATCH = 201, "A function in an impl of a trait takes or returns other types than the function in the trait.";
+    NOT_A_TRAIT_FUNCTION = 202, "A function in an impl of a trait that the trait does not declare.";
+    UNKNOWN_TRAIT = 203, "A name in an impl or a bound that no trait is declared with.";
+    MISSING_FUNCTION_BODY = 204, "A function without a body outside of a trait. Only functions of traits leave their body to the impls.";
+    UNSATISFIED_TRAIT_BOUND = 205, "An argument whose type does not implement a trait that the parameter is bounded by.";
+    WRONG_TYPE_ARGUMENT_COUNT = 206, "A generic function or type is given more or fewer type arguments than it has type parameters.";
+    CANNOT_INFER_TYPE_ARGUMENT = 207, "A type parameter of a called function is not the type of any of its parameters. Give the type arguments with a turbofish like max::<int>.";
+    CONFLICTING_TYPE_ARGUMENT = 208, "Two arguments of a call need different types for the same type parameter.";
+    RECURSIVE_INSTANTIATION = 209, "A generic function that needs an instance of itself with ever larger types, so monomorphization would never end.";
+    UNSUPPORTED_CALLEE = 300, "A call of something other than a function by its name, like a method. The LLVM backend can not compile it yet.";
+    UNKNOWN_FUNCTION = 301, "A call of a function that no top level function or instance of a generic function is built for.";
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique() {
+        for (i, (code, _)) in ALL.iter().enumerate() {
+            assert!(ALL[i + 1..].iter().all(|(other, _)| other != code), "{} is used twice", code);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(UNKNOWN_CHAR.to_string(), "E0001");
+        assert_eq!(MISSING_TOKEN.to_string(), "E0100");
+        assert_eq!(Code::from_name("E0100"), Some(MISSING_TOKEN));
+        assert_eq!(Code::from_name("E9999"), None);
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use crate::codes::Code;
+
+/// how bad a diagnostic is, in the order they are shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// the source can not be compiled
+    Error,
+    /// compilation continues, but the source likely does not do what was intended
+    Warning,
+    /// extra information, usually attached to another diagnostic
+    Note,
+    /// how to fix another diagnostic
+    Help,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+            Severity::Help => write!(f, "help"),
+        }
+    }
+}
+
+/// offset and len are in bytes and point into the source, like the ones of a token
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize, len: usize) -> Span {
+        Span { offset, len }
+    }
+
+    /// byte offset one past the last char of the span
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// A message about a part of the source. Every diagnostic has one primary label,
+/// secondary labels point at related code, like an earlier declaration.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Label {
+    pub span: Span,
+    /// can be empty, the span is underlined either way
+    pub message: String,
+    pub primary: bool,
+}
+
+/// A note or help message that is not about a specific part of the source
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Note {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Replacing the span with the replacement fixes the diagnostic. Empty spans insert, empty replacements remove.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub message: String,
+}
+
+/// Everything the compiler has to say about one problem in a source.
+/// Build them with Diagnostic::error or Diagnostic::warning and the with_ methods.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<Code>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<Note>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: vec![],
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    pub fn error(code: Code, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message).with_code(code)
+    }
+
+    pub fn warning(code: Code, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, message).with_code(code)
+    }
+
+    pub fn with_code(mut self, code: Code) -> Diagnostic {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_primary_label(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label { span, message: message.into(), primary: true });
+        self
+    }
+
+    pub fn with_secondary_label(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label { span, message: message.into(), primary: false });
+        self
+    }
+
+    pub fn with_note(mut self, message: impl Into<String>) -> Diagnostic {
+        self.notes.push(Note { severity: Severity::Note, message: message.into() });
+        self
+    }
+
+    pub fn with_help(mut self, message: impl Into<String>) -> Diagnostic {
+        self.notes.push(Note { severity: Severity::Help, message: message.into() });
+        self
+    }
+
+    pub fn with_suggestion(mut self, span: Span, replacement: impl Into<String>, message: impl Into<String>) -> Diagnostic {
+        self.suggestions.push(Suggestion { span, replacement: replacement.into(), message: message.into() });
+        self
+    }
+
+    /// the span of the first primary label, diagnostics are sorted and shown by it
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels.iter().find(|label| label.primary).map(|label| label.span)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
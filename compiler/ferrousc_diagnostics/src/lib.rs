@@ -0,0 +1,9 @@
+pub use diagnostic::{Diagnostic, Label, Note, Severity, Span, Suggestion};
+pub use line_index::{LineCol, LineIndex};
+pub use sink::Diagnostics;
+
+pub mod codes;
+mod diagnostic;
+mod line_index;
+mod render;
+mod sink;
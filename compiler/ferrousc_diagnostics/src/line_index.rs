@@ -0,0 +1,115 @@
+use std::fmt;
+
+/// zero based line and column. The column is counted in bytes from the start of the line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for LineCol {
+    /// one based, the way editors and error messages show it
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.col + 1)
+    }
+}
+
+/// Maps byte offsets (like Token::offset) to lines and columns.
+/// \r\n, \r, and \n all end a line, the same way the lexer treats them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    /// byte offset of the first char of every line
+    line_starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        let bytes = src.as_bytes();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                    i += 2;
+                    line_starts.push(i);
+                },
+                b'\r' | b'\n' => {
+                    i += 1;
+                    line_starts.push(i);
+                },
+                _ => i += 1,
+            }
+        }
+
+        LineIndex { line_starts, len: src.len() }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// offsets past the end of the source are clamped to the end
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+
+        LineCol { line, col: offset - self.line_starts[line] }
+    }
+
+    /// the inverse of line_col. Returns None when the line does not exist.
+    pub fn offset(&self, line_col: LineCol) -> Option<usize> {
+        self.line_starts.get(line_col.line).map(|start| start + line_col.col)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lc(line: usize, col: usize) -> LineCol {
+        LineCol { line, col }
+    }
+
+    #[test]
+    fn test_line_col() {
+        let index = LineIndex::new("ab\ncd\r\nef\rg");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), lc(0, 0));
+        assert_eq!(index.line_col(2), lc(0, 2));
+        assert_eq!(index.line_col(3), lc(1, 0));
+        assert_eq!(index.line_col(5), lc(1, 2));
+        assert_eq!(index.line_col(6), lc(1, 3));
+        assert_eq!(index.line_col(7), lc(2, 0));
+        assert_eq!(index.line_col(10), lc(3, 0));
+        assert_eq!(index.line_col(11), lc(3, 1));
+        assert_eq!(index.line_col(100), lc(3, 1));
+    }
+
+    #[test]
+    fn test_offset() {
+        let index = LineIndex::new("ab\ncd\r\nef\rg");
+        assert_eq!(index.offset(lc(0, 1)), Some(1));
+        assert_eq!(index.offset(lc(1, 1)), Some(4));
+        assert_eq!(index.offset(lc(2, 0)), Some(7));
+        assert_eq!(index.offset(lc(3, 0)), Some(10));
+        assert_eq!(index.offset(lc(4, 0)), None);
+    }
+
+    #[test]
+    fn test_empty() {
+        let index = LineIndex::new("");
+        assert_eq!(index.line_count(), 1);
+        assert_eq!(index.line_col(0), lc(0, 0));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(lc(0, 0).to_string(), "1:1");
+        assert_eq!(lc(4, 11).to_string(), "5:12");
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::Write;
+
+use crate::{Diagnostic, Diagnostics, Label, LineCol, LineIndex, Span, Suggestion};
+
+impl Diagnostic {
+    /// Shows the diagnostic the way the command line compiler prints it, with the labelled lines of the source
+    /// and the suggestions applied to them. Columns are counted in chars, tabs are shown as four spaces.
+    pub fn render(&self, src: &str, file_name: &str) -> String {
+        let index = LineIndex::new(src);
+        let mut out = String::new();
+
+        match self.code {
+            Some(code) => writeln!(out, "{}[{}]: {}", self.severity, code, self.message).unwrap(),
+            None => writeln!(out, "{}: {}", self.severity, self.message).unwrap(),
+        }
+
+        let spans = self.labels.iter().map(|label| label.span).chain(self.suggestions.iter().map(|suggestion| suggestion.span));
+        let gutter = spans.map(|span| (index.line_col(span.offset).line + 1).to_string().len()).max().unwrap_or(0);
+        let pad = " ".repeat(gutter);
+
+        if let Some(span) = self.primary_span() {
+            writeln!(out, "{}--> {}:{}", pad, file_name, index.line_col(span.offset)).unwrap();
+        }
+
+        if !self.labels.is_empty() {
+            writeln!(out, "{} |", pad).unwrap();
+            let mut labels: Vec<&Label> = self.labels.iter().collect();
+            labels.sort_by_key(|label| label.span.offset);
+
+            let mut previous_line = None;
+            for label in labels {
+                let start = index.line_col(label.span.offset);
+                if previous_line != Some(start.line) {
+                    writeln!(out, "{:>width$} | {}", start.line + 1, expand_tabs(line_text(src, &index, start.line)), width = gutter).unwrap();
+                    previous_line = Some(start.line);
+                }
+
+                let marker = if label.primary { "^" } else { "-" };
+                let (column, width) = underline(src, &index, start, label.span);
+                let underline = format!("{}{}", " ".repeat(column), marker.repeat(width));
+                if label.message.is_empty() {
+                    writeln!(out, "{} | {}", pad, underline).unwrap();
+                }
+                else {
+                    writeln!(out, "{} | {} {}", pad, underline, label.message).unwrap();
+                }
+            }
+        }
+
+        for note in &self.notes {
+            writeln!(out, "{} = {}: {}", pad, note.severity, note.message).unwrap();
+        }
+
+        for suggestion in &self.suggestions {
+            writeln!(out, "help: {}", suggestion.message).unwrap();
+            if let Some((line, patched)) = apply_on_line(src, &index, suggestion) {
+                writeln!(out, "{} |", pad).unwrap();
+                writeln!(out, "{:>width$} | {}", line + 1, expand_tabs(&patched), width = gutter).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+impl Diagnostics {
+    /// every diagnostic rendered, separated by an empty line
+    pub fn render(&self, src: &str, file_name: &str) -> String {
+        self.iter()
+            .map(|diagnostic| diagnostic.render(src, file_name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// the text of a line without its line break
+fn line_text<'src>(src: &'src str, index: &LineIndex, line: usize) -> &'src str {
+    let start = index.offset(LineCol { line, col: 0 }).unwrap_or(src.len());
+    let end = index.offset(LineCol { line: line + 1, col: 0 }).unwrap_or(src.len());
+    src[start..end].trim_end_matches(['\r', '\n'])
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
+
+/// Where the underline of a span starts and how long it is, in displayed chars.
+/// Spans over multiple lines are underlined up to the end of their first line, empty spans get a single marker.
+fn underline(src: &str, index: &LineIndex, start: LineCol, span: Span) -> (usize, usize) {
+    let line = line_text(src, index, start.line);
+    let col = start.col.min(line.len());
+    let end = (col + span.len).min(line.len());
+
+    let column = expand_tabs(&line[..col]).chars().count();
+    let width = expand_tabs(&line[col..end]).chars().count();
+    (column, width.max(1))
+}
+
+/// the line of the suggestion with the replacement applied, if the suggestion only changes a single line
+fn apply_on_line(src: &str, index: &LineIndex, suggestion: &Suggestion) -> Option<(usize, String)> {
+    let start = index.line_col(suggestion.span.offset);
+    let line = line_text(src, index, start.line);
+    let end = start.col + suggestion.span.len;
+    if end > line.len() || suggestion.replacement.contains('\n') {
+        return None;
+    }
+
+    Some((start.line, format!("{}{}{}", &line[..start.col], suggestion.replacement, &line[end..])))
+}
+
+#[cfg(test)]
+mod test {
+    use expect_test::{expect, Expect};
+
+    use crate::{codes, Diagnostic, Diagnostics, Severity, Span};
+
+    fn check(diagnostics: Vec<Diagnostic>, src: &str, expect: Expect) {
+        let mut sink = Diagnostics::new();
+        sink.extend(diagnostics);
+        expect.assert_eq(&sink.render(src, "main.fe"));
+    }
+
+    #[test]
+    fn test_render_labels() {
+        check(
+            vec![
+                Diagnostic::error(codes::MISSING_TOKEN, "expected `;`, found `let`")
+                    .with_primary_label(Span::new(9, 0), "expected `;`")
+                    .with_secondary_label(Span::new(0, 3), "in this statement")
+                    .with_suggestion(Span::new(9, 0), ";", "insert `;`"),
+                Diagnostic::warning(codes::CONFUSABLE_IDENTIFIER, "identifier can be confused")
+                    .with_primary_label(Span::new(34, 1), "")
+                    .with_note("the other identifier looks the same")
+                    .with_help("rename one of them"),
+                Diagnostic::new(Severity::Note, "no label at all"),
+            ],
+            "let a = 1 let b = 2;\n\n\n\n\n\n\n\n\n\tlet c = b;",
+            expect![[r#"
+                error[E0100]: expected `;`, found `let`
+                 --> main.fe:1:10
+                  |
+                1 | let a = 1 let b = 2;
+                  | --- in this statement
+                  |          ^ expected `;`
+                help: insert `;`
+                  |
+                1 | let a = 1; let b = 2;
+
+                warning[E0016]: identifier can be confused
+                  --> main.fe:10:6
+                   |
+                10 |     let c = b;
+                   |         ^
+                   = note: the other identifier looks the same
+                   = help: rename one of them
+
+                note: no label at all
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_render_multiline_span() {
+        check(
+            vec![
+                Diagnostic::error(codes::UNTERMINATED_MULTILINE_COMMENT, "unterminated multiline comment")
+                    .with_primary_label(Span::new(2, 12), "")
+                    .with_suggestion(Span::new(2, 2), "", "remove the comment"),
+            ],
+            "a /* b\r\n c",
+            expect![[r#"
+                error[E0004]: unterminated multiline comment
+                 --> main.fe:1:3
+                  |
+                1 | a /* b
+                  |   ^^^^
+                help: remove the comment
+                  |
+                1 | a  b
+            "#]],
+        );
+    }
+}
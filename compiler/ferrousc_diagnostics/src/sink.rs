@@ -0,0 +1,107 @@
+use crate::{Diagnostic, Severity};
+
+/// Collects the diagnostics of every pass over a source. The lexer and the parser both see
+/// malformed tokens, so a diagnostic that is already in the sink is not added a second time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        if !self.diagnostics.contains(&diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.severity == severity).count()
+    }
+
+    /// errors stop the compilation, everything else does not
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// in the order they were emitted
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// Orders the diagnostics by where they start in the source. Diagnostics without a label go last,
+    /// the order of diagnostics at the same position is kept.
+    pub fn sort(&mut self) {
+        self.diagnostics.sort_by_key(|diagnostic| diagnostic.primary_span().map_or(usize::MAX, |span| span.offset));
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, diagnostics: T) {
+        for diagnostic in diagnostics {
+            self.push(diagnostic);
+        }
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{codes, Span};
+
+    #[test]
+    fn test_duplicates_are_dropped() {
+        let mut diagnostics = Diagnostics::new();
+        let unknown_char = Diagnostic::error(codes::UNKNOWN_CHAR, "unknown character").with_primary_label(Span::new(3, 1), "");
+        diagnostics.push(unknown_char.clone());
+        diagnostics.push(unknown_char.clone().with_primary_label(Span::new(5, 1), ""));
+        diagnostics.push(unknown_char);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_sort_and_count() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.extend(vec![
+            Diagnostic::new(Severity::Note, "no label"),
+            Diagnostic::warning(codes::UNKNOWN_ATTRIBUTE, "second").with_primary_label(Span::new(10, 2), ""),
+            Diagnostic::error(codes::MISSING_TOKEN, "first").with_primary_label(Span::new(2, 0), ""),
+        ]);
+        diagnostics.sort();
+
+        let messages: Vec<_> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, ["first", "second", "no label"]);
+        assert_eq!(diagnostics.count(Severity::Warning), 1);
+        assert!(diagnostics.has_errors());
+    }
+}
@@ -0,0 +1,26 @@
+//! Lexer throughput on test_code.fe, repeated until it is about a megabyte.
+//! Run with `cargo bench -p ferrousc_lexer`, criterion reports the throughput in MB/s.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use ferrousc_lexer::{lex, tokenize};
+
+const TEST_CODE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../test_code.fe"));
+const TARGET_SIZE: usize = 1024 * 1024;
+
+fn scaled_test_code() -> String {
+    TEST_CODE.repeat(TARGET_SIZE / TEST_CODE.len() + 1)
+}
+
+fn lexer(c: &mut Criterion) {
+    let src = scaled_test_code();
+    let mut group = c.benchmark_group("lexer");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+
+    group.bench_function("tokenize", |b| b.iter(|| tokenize(black_box(&src)).count()));
+    group.bench_function("lex", |b| b.iter(|| lex(black_box(&src))));
+
+    group.finish();
+}
+
+criterion_group!(benches, lexer);
+criterion_main!(benches);
@@ -0,0 +1,135 @@
+use unicode_xid::UnicodeXID;
+
+const WHITESPACE_CHARS: [char; 22] = [
+    ' ',        // space
+    '\t',       // tab
+    '\u{000B}', // vertical tab
+    '\u{000C}', // form feed
+    '\u{00A0}', // no break space
+    '\u{1680}', // ogham space mark
+    '\u{180E}', // mongolian vowel seperator
+    '\u{2000}', // en quad
+    '\u{2001}', // em quad
+    '\u{2002}', // en space
+    '\u{2003}', // em space
+    '\u{2004}', // three-per-em space
+    '\u{2005}', // four-per-em space
+    '\u{2006}', // six-per-em space
+    '\u{2007}', // figure space
+    '\u{2008}', // punctuation space
+    '\u{2009}', // thin space
+    '\u{200A}', // hair space
+    '\u{200B}', // zero-width space
+    '\u{202F}', // narrow no-break space
+    '\u{205F}', // medium mathmatical space
+    '\u{3000}', // idiographic space
+];
+
+/// U+FEFF zero width no-break space, which is only a byte order mark at the start of a file
+pub(crate) const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+const NEWLINE_CHARS: [char; 2] = [
+    '\n',
+    '\r',
+];
+
+const WHITESPACE: u8 = 1 << 0;
+const NEWLINE: u8 = 1 << 1;
+const IDENTIFIER_START: u8 = 1 << 2;
+const IDENTIFIER_CONTINUE: u8 = 1 << 3;
+
+/// the classes of every ascii char, so the common case is a single lookup
+static ASCII_CLASSES: [u8; 128] = ascii_classes();
+
+const fn ascii_classes() -> [u8; 128] {
+    let mut classes = [0; 128];
+
+    let mut i = 0;
+    while i < WHITESPACE_CHARS.len() {
+        if WHITESPACE_CHARS[i].is_ascii() {
+            classes[WHITESPACE_CHARS[i] as usize] |= WHITESPACE;
+        }
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < NEWLINE_CHARS.len() {
+        classes[NEWLINE_CHARS[i] as usize] |= NEWLINE;
+        i += 1;
+    }
+
+    let mut c = 0;
+    while c < 128 {
+        let char = c as u8 as char;
+        if char.is_ascii_alphabetic() || char == '_' {
+            classes[c] |= IDENTIFIER_START | IDENTIFIER_CONTINUE;
+        }
+        if char.is_ascii_digit() {
+            classes[c] |= IDENTIFIER_CONTINUE;
+        }
+        c += 1;
+    }
+
+    classes
+}
+
+fn has_class(c: char, class: u8) -> bool {
+    ASCII_CLASSES[c as usize] & class != 0
+}
+
+pub(crate) fn is_whitespace(c: char) -> bool {
+    if c.is_ascii() {
+        has_class(c, WHITESPACE)
+    }
+    else {
+        WHITESPACE_CHARS.contains(&c)
+    }
+}
+
+pub(crate) fn is_newline(c: char) -> bool {
+    c.is_ascii() && has_class(c, NEWLINE)
+}
+
+/// XID_Start from UAX #31 and the underscore
+pub(crate) fn is_identifier_start(c: char) -> bool {
+    if c.is_ascii() {
+        has_class(c, IDENTIFIER_START)
+    }
+    else {
+        c.is_xid_start()
+    }
+}
+
+/// XID_Continue from UAX #31, which includes digits of all scripts
+pub(crate) fn is_identifier_continue(c: char) -> bool {
+    if c.is_ascii() {
+        has_class(c, IDENTIFIER_CONTINUE)
+    }
+    else {
+        c.is_xid_continue()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ascii_classes_match_unicode_fallback() {
+        for c in (0..128u8).map(char::from) {
+            assert_eq!(is_whitespace(c), WHITESPACE_CHARS.contains(&c), "{:?}", c);
+            assert_eq!(is_newline(c), NEWLINE_CHARS.contains(&c), "{:?}", c);
+            assert_eq!(is_identifier_start(c), c == '_' || c.is_xid_start(), "{:?}", c);
+            assert_eq!(is_identifier_continue(c), c.is_xid_continue(), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_whitespace() {
+        for &c in WHITESPACE_CHARS.iter() {
+            assert!(is_whitespace(c), "{:?}", c);
+        }
+        assert!(!is_whitespace('ä'));
+        assert!(!is_whitespace(BYTE_ORDER_MARK));
+    }
+}
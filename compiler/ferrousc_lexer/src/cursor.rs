@@ -1,34 +1,152 @@
-use std::str::Chars;
+use std::cell::Cell;
+
+use crate::char_class::BYTE_ORDER_MARK;
+use crate::Edition;
 
 pub const EOF_CHAR: char = '\0';
 
+/// Walks the source byte by byte. Ascii chars are decoded from a single byte, everything else falls back to str::chars.
 pub(crate) struct Cursor<'a> {
-    chars: Chars<'a>
+    src: &'a str,
+    /// byte position of the next char in src
+    pos: usize,
+    /// byte position in src where the current token starts
+    token_start: usize,
+    /// one past the last byte the cursor has looked at, src.len() + 1 once it has seen the end
+    reach: Cell<usize>,
+    /// only the start of a file can have a shebang
+    at_file_start: bool,
+    /// decides which words are keywords
+    edition: Edition,
 }
 
-impl Cursor<'_> {
-    pub(crate) fn new(src: &'_ str) -> Cursor<'_> {
-        Cursor { chars: src.chars() }
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(src: &'a str) -> Cursor<'a> {
+        Cursor { src, pos: 0, token_start: 0, reach: Cell::new(0), at_file_start: false, edition: Edition::default() }
+    }
+
+    /// a cursor over src[offset..]. The file starts at offset 0 and again right behind a leading byte order mark
+    pub(crate) fn at(src: &'a str, offset: usize, edition: Edition) -> Cursor<'a> {
+        let bom_len = BYTE_ORDER_MARK.len_utf8();
+        let at_file_start = offset == 0 || (offset == bom_len && src.starts_with(BYTE_ORDER_MARK));
+        Cursor { at_file_start, edition, ..Cursor::new(&src[offset..]) }
+    }
+
+    pub(crate) fn edition(&self) -> Edition {
+        self.edition
+    }
+
+    pub(crate) fn at_file_start(&self) -> bool {
+        self.at_file_start
+    }
+
+    pub(crate) fn set_at_file_start(&mut self, at_file_start: bool) {
+        self.at_file_start = at_file_start;
+    }
+
+    pub(crate) fn start_token(&mut self) {
+        self.token_start = self.pos;
+    }
+
+    pub(crate) fn token_start(&self) -> usize {
+        self.token_start
+    }
+
+    pub(crate) fn set_token_start(&mut self, token_start: usize) {
+        self.token_start = token_start;
+    }
+
+    /// everything that was eaten since the start of the current token
+    pub(crate) fn consumed(&self) -> &'a str {
+        &self.src[self.token_start..self.pos]
+    }
+
+    /// Everything the cursor returned so far only depends on src[..reach].
+    /// Edits behind the reach can't change what was lexed.
+    pub(crate) fn reach(&self) -> usize {
+        self.reach.get()
     }
 
     pub(crate) fn is_eof(&self) -> bool {
-        self.chars.as_str().is_empty()
+        let is_eof = self.pos >= self.src.len();
+        self.look_until(if is_eof { self.src.len() + 1 } else { self.pos + 1 });
+        is_eof
     }
 
     pub(crate) fn peek(&self) -> char {
-        self.chars().nth(0).unwrap_or(EOF_CHAR)
+        self.char_at(self.pos).0
     }
 
     pub(crate) fn peek_n(&self, n: usize) -> char {
-        self.chars().nth(n).unwrap_or(EOF_CHAR)
+        let mut pos = self.pos;
+        for _ in 0..n {
+            if pos >= self.src.len() {
+                break;
+            }
+            pos += utf8_len(self.src.as_bytes()[pos]);
+        }
+        self.char_at(pos).0
     }
 
     pub(crate) fn eat(&mut self) -> char {
-        self.chars.next().unwrap_or(EOF_CHAR)
+        let (c, len) = self.char_at(self.pos);
+        self.pos += len;
+        c
+    }
+
+    /// Eats chars as long as the predicate holds. Faster than peek and eat in a loop, because every char is only decoded once.
+    pub(crate) fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) {
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() {
+            let byte = bytes[self.pos];
+            let (c, len) = if byte < 0x80 {
+                (byte as char, 1)
+            }
+            else {
+                (self.src[self.pos..].chars().next().unwrap_or(EOF_CHAR), utf8_len(byte))
+            };
+
+            if !predicate(c) {
+                self.look_until(self.pos + len);
+                return;
+            }
+            self.pos += len;
+        }
+        self.look_until(bytes.len() + 1);
+    }
+
+    /// the char at the byte position and its length in bytes. EOF_CHAR with length 0 at the end.
+    fn char_at(&self, pos: usize) -> (char, usize) {
+        let bytes = self.src.as_bytes();
+        if pos >= bytes.len() {
+            self.look_until(bytes.len() + 1);
+            return (EOF_CHAR, 0);
+        }
+
+        let len = utf8_len(bytes[pos]);
+        self.look_until(pos + len);
+        if len == 1 {
+            (bytes[pos] as char, 1)
+        }
+        else {
+            (self.src[pos..].chars().next().unwrap_or(EOF_CHAR), len)
+        }
+    }
+
+    fn look_until(&self, reach: usize) {
+        if reach > self.reach.get() {
+            self.reach.set(reach);
+        }
     }
+}
 
-    fn chars(&self) -> Chars<'_> {
-        self.chars.clone()
+/// the length of the char from its first byte, src is always valid utf-8
+fn utf8_len(first_byte: u8) -> usize {
+    match first_byte {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
     }
 }
 
@@ -88,6 +206,32 @@ mod test {
         assert_eq!(c.peek_n(3), EOF_CHAR);
     }
 
+    #[test]
+    fn test_eat_while() {
+        let mut c = Cursor::new("aäb1 c");
+        c.eat_while(char::is_alphabetic);
+        assert_eq!(c.consumed(), "aäb");
+        assert_eq!(c.reach(), 5);
+        c.eat_while(|_| true);
+        assert_eq!(c.consumed(), "aäb1 c");
+        assert_eq!(c.reach(), 8);
+        assert!(c.is_eof());
+    }
+
+    #[test]
+    fn test_reach() {
+        let mut c = Cursor::new("1ä34");
+        assert_eq!(c.reach(), 0);
+        c.eat();
+        assert_eq!(c.reach(), 1);
+        c.peek_n(1);
+        assert_eq!(c.reach(), 4);
+        c.eat();
+        assert_eq!(c.reach(), 4);
+        c.peek_n(5);
+        assert_eq!(c.reach(), 6);
+    }
+
     #[test]
     fn test_peek_end() {
         let mut c = Cursor::new("1234");
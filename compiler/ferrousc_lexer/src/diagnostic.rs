@@ -0,0 +1,268 @@
+use std::fmt;
+use std::ops::Range;
+
+use ferrousc_diagnostics::{codes, Diagnostic, Span};
+
+use crate::identifier::{confusable_identifiers, diagnose_identifier};
+use crate::keyword::reserved_keywords;
+use crate::{cook_char, cook_string, split_interpolated_string, split_number_literal, tokenize_with_edition, Base, Edition, InterpolatedPart, LiteralErrorKind, NumberSuffix, Token, TokenKind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LexerErrorKind {
+    /// a char that can not start any token
+    UnknownChar,
+    UnterminatedStringLiteral,
+    UnterminatedCharLiteral,
+    UnterminatedMultilineComment,
+    /// $"{name" the '{' of the hole is never closed
+    UnterminatedInterpolationHole,
+    /// 0x, 0b_ and so on, or the exponent of 1e_5
+    MissingDigits,
+    /// 0b102, the 2 is not a binary digit
+    InvalidDigit,
+    /// 5_abc, 5i128
+    InvalidNumberSuffix,
+    /// 0b1f32. Only decimal literals can be floats
+    FloatSuffixOnNonDecimal,
+    /// 1.5i32, 1e3u8
+    IntegerSuffixOnFloat,
+    /// an invalid escape sequence or a char literal without exactly one char
+    InvalidLiteral(LiteralErrorKind),
+    /// an identifier that is reserved for an upcoming feature in the edition of the source
+    ReservedKeyword,
+    /// a U+FEFF anywhere but at the start of the file
+    MisplacedByteOrderMark,
+    /// warning: "paramеter" with a cyrillic е mixes latin and cyrillic
+    MixedScriptIdentifier,
+    /// warning: "рор" is all cyrillic, but looks like the ascii "pop"
+    LookalikeIdentifier,
+    /// warning: the identifier looks like, but is not, the earlier identifier at the byte offset other
+    ConfusableIdentifier { other: usize },
+}
+
+impl LexerErrorKind {
+    /// warnings don't stop the compilation
+    pub fn is_warning(&self) -> bool {
+        matches!(self, LexerErrorKind::MixedScriptIdentifier | LexerErrorKind::LookalikeIdentifier | LexerErrorKind::ConfusableIdentifier{..})
+    }
+
+    pub fn code(&self) -> codes::Code {
+        match self {
+            LexerErrorKind::UnknownChar => codes::UNKNOWN_CHAR,
+            LexerErrorKind::UnterminatedStringLiteral => codes::UNTERMINATED_STRING_LITERAL,
+            LexerErrorKind::UnterminatedCharLiteral => codes::UNTERMINATED_CHAR_LITERAL,
+            LexerErrorKind::UnterminatedMultilineComment => codes::UNTERMINATED_MULTILINE_COMMENT,
+            LexerErrorKind::UnterminatedInterpolationHole => codes::UNTERMINATED_INTERPOLATION_HOLE,
+            LexerErrorKind::MissingDigits => codes::MISSING_DIGITS,
+            LexerErrorKind::InvalidDigit => codes::INVALID_DIGIT,
+            LexerErrorKind::InvalidNumberSuffix => codes::INVALID_NUMBER_SUFFIX,
+            LexerErrorKind::FloatSuffixOnNonDecimal => codes::FLOAT_SUFFIX_ON_NON_DECIMAL,
+            LexerErrorKind::IntegerSuffixOnFloat => codes::INTEGER_SUFFIX_ON_FLOAT,
+            LexerErrorKind::InvalidLiteral(_) => codes::INVALID_LITERAL,
+            LexerErrorKind::ReservedKeyword => codes::RESERVED_KEYWORD,
+            LexerErrorKind::MisplacedByteOrderMark => codes::MISPLACED_BYTE_ORDER_MARK,
+            LexerErrorKind::MixedScriptIdentifier => codes::MIXED_SCRIPT_IDENTIFIER,
+            LexerErrorKind::LookalikeIdentifier => codes::LOOKALIKE_IDENTIFIER,
+            LexerErrorKind::ConfusableIdentifier{..} => codes::CONFUSABLE_IDENTIFIER,
+        }
+    }
+}
+
+/// offset and len are in bytes and point into the lexed source
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LexerDiagnostic {
+    pub kind: LexerErrorKind,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl LexerDiagnostic {
+    /// the diagnostic as it is shown to the user, ready for a ferrousc_diagnostics::Diagnostics sink
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let span = Span::new(self.offset, self.len);
+        let diagnostic = if self.kind.is_warning() {
+            Diagnostic::warning(self.kind.code(), self.kind.to_string())
+        }
+        else {
+            Diagnostic::error(self.kind.code(), self.kind.to_string())
+        };
+        let diagnostic = diagnostic.with_primary_label(span, "");
+
+        match self.kind {
+            LexerErrorKind::UnterminatedInterpolationHole => diagnostic.with_help("use '{{' for a '{' that does not start a hole"),
+            LexerErrorKind::ReservedKeyword => diagnostic.with_help("rename the identifier, the word is reserved for an upcoming feature"),
+            LexerErrorKind::MisplacedByteOrderMark => diagnostic.with_suggestion(span, "", "remove the byte order mark"),
+            LexerErrorKind::ConfusableIdentifier{ other } => diagnostic.with_secondary_label(Span::new(other, 0), "the other identifier starts here"),
+            _ => diagnostic,
+        }
+    }
+}
+
+/// the tokens of a source together with everything that is wrong with them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lexed<'src> {
+    pub tokens: Vec<Token<'src>>,
+    pub diagnostics: Vec<LexerDiagnostic>,
+}
+
+/// lexes a source of the latest edition
+pub fn lex(src: &str) -> Lexed<'_> {
+    lex_with_edition(src, Edition::default())
+}
+
+pub fn lex_with_edition(src: &str, edition: Edition) -> Lexed<'_> {
+    let mut diagnostics = vec![];
+    let tokens = tokenize_with_edition(src, edition)
+        .inspect(|token| diagnostics.extend(diagnose(token)))
+        .collect::<Vec<_>>();
+
+    // confusables need all identifiers of the source, not just a single token
+    diagnostics.extend(confusable_identifiers(&tokens));
+    diagnostics.extend(reserved_keywords(&tokens, edition));
+    diagnostics.sort_by_key(|diagnostic| diagnostic.offset);
+
+    Lexed{ tokens, diagnostics }
+}
+
+/// Everything that is wrong with a single token. The lexer itself never fails,
+/// so malformed input is only visible through the token kind and this function.
+/// Interpolated strings also report everything that is wrong with the tokens in their holes.
+pub fn diagnose(token: &Token) -> Vec<LexerDiagnostic> {
+    let whole_token = |kind| LexerDiagnostic{ kind, offset: token.offset, len: token.len };
+    let mut diagnostics = vec![];
+
+    match token.kind {
+        TokenKind::Unknown => diagnostics.push(whole_token(LexerErrorKind::UnknownChar)),
+        TokenKind::ByteOrderMark if token.offset != 0 => diagnostics.push(whole_token(LexerErrorKind::MisplacedByteOrderMark)),
+        TokenKind::Identifier => diagnostics.extend(diagnose_identifier(token)),
+        TokenKind::MultilineComment{ terminated: false } | TokenKind::MultilineDocComment{ terminated: false } => {
+            diagnostics.extend(unmatched_comment_openers(token.value)
+                .into_iter()
+                .map(|opener| LexerDiagnostic{ kind: LexerErrorKind::UnterminatedMultilineComment, offset: token.offset + opener, len: "/*".len() }));
+        },
+        TokenKind::NumberLiteral{ base, has_digits, has_exponent, suffix } => {
+            if !has_digits {
+                diagnostics.push(whole_token(LexerErrorKind::MissingDigits));
+            }
+            if has_exponent {
+                if let Some(exponent) = missing_exponent_digits(token.value) {
+                    diagnostics.push(LexerDiagnostic{ kind: LexerErrorKind::MissingDigits, offset: token.offset + exponent.start, len: exponent.len() });
+                }
+            }
+            if let Some(suffix) = suffix {
+                let (number, suffix_text) = split_number_literal(token);
+                let is_float = has_exponent || number.contains('.');
+                let kind = match suffix {
+                    _ if suffix_text.starts_with(|c: char| c.is_ascii_digit()) => Some(LexerErrorKind::InvalidDigit),
+                    NumberSuffix::Invalid => Some(LexerErrorKind::InvalidNumberSuffix),
+                    suffix if suffix.is_float() && base != Base::Decimal => Some(LexerErrorKind::FloatSuffixOnNonDecimal),
+                    suffix if !suffix.is_float() && is_float => Some(LexerErrorKind::IntegerSuffixOnFloat),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    diagnostics.push(LexerDiagnostic{ kind, offset: token.offset + number.len(), len: suffix_text.len() });
+                }
+            }
+        },
+        TokenKind::StringLiteral{ terminated }
+        | TokenKind::RawStringLiteral{ terminated }
+        | TokenKind::MultilineStringLiteral{ terminated } => {
+            if !terminated {
+                diagnostics.push(whole_token(LexerErrorKind::UnterminatedStringLiteral));
+            }
+            if let Err(errors) = cook_string(token) {
+                diagnostics.extend(errors.iter().map(|e| LexerDiagnostic{ kind: LexerErrorKind::InvalidLiteral(e.kind), offset: e.offset, len: e.len }));
+            }
+        },
+        TokenKind::InterpolatedStringText => {
+            if let Err(errors) = cook_string(token) {
+                diagnostics.extend(errors.iter().map(|e| LexerDiagnostic{ kind: LexerErrorKind::InvalidLiteral(e.kind), offset: e.offset, len: e.len }));
+            }
+        },
+        TokenKind::InterpolatedStringLiteral{ terminated } => {
+            if !terminated {
+                diagnostics.push(whole_token(LexerErrorKind::UnterminatedStringLiteral));
+            }
+            for part in split_interpolated_string(token).parts {
+                match part {
+                    InterpolatedPart::Text{ text } => diagnostics.extend(diagnose(&text)),
+                    InterpolatedPart::Hole{ l_brace, tokens, r_brace } => {
+                        if r_brace.is_none() {
+                            diagnostics.push(LexerDiagnostic{ kind: LexerErrorKind::UnterminatedInterpolationHole, offset: l_brace.offset, len: l_brace.len });
+                        }
+                        diagnostics.extend(tokens.iter().flat_map(diagnose));
+                    },
+                }
+            }
+        },
+        TokenKind::CharLiteral{ terminated } => {
+            if !terminated {
+                diagnostics.push(whole_token(LexerErrorKind::UnterminatedCharLiteral));
+            }
+            if let Err(errors) = cook_char(token) {
+                diagnostics.extend(errors.iter().map(|e| LexerDiagnostic{ kind: LexerErrorKind::InvalidLiteral(e.kind), offset: e.offset, len: e.len }));
+            }
+        },
+        _ => {},
+    }
+
+    diagnostics
+}
+
+/// the e, sign and underscores of an exponent that does not start with a digit, like the e_ of 1e_5
+fn missing_exponent_digits(number: &str) -> Option<Range<usize>> {
+    let start = number.find(['e', 'E'])?;
+    let exponent = &number[start + 1..];
+    let sign = if exponent.starts_with(['+', '-']) { 1 } else { 0 };
+    let underscores = exponent[sign..].len() - exponent[sign..].trim_start_matches('_').len();
+    if underscores == 0 {
+        return None;
+    }
+    Some(start..start + 1 + sign + underscores)
+}
+
+/// the positions of the "/*" in a block comment that have no matching "*/"
+fn unmatched_comment_openers(comment: &str) -> Vec<usize> {
+    let mut openers = vec![];
+    let mut pos = 0;
+
+    while pos < comment.len() {
+        let rest = &comment[pos..];
+        if rest.starts_with("/*") {
+            openers.push(pos);
+            pos += 2;
+        }
+        else if rest.starts_with("*/") {
+            openers.pop();
+            pos += 2;
+        }
+        else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    openers
+}
+
+impl fmt::Display for LexerErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexerErrorKind::UnknownChar => write!(f, "unknown character"),
+            LexerErrorKind::UnterminatedStringLiteral => write!(f, "unterminated string literal, missing '\"'"),
+            LexerErrorKind::UnterminatedCharLiteral => write!(f, "unterminated char literal, missing '''"),
+            LexerErrorKind::UnterminatedMultilineComment => write!(f, "unterminated multiline comment, missing '*/'"),
+            LexerErrorKind::UnterminatedInterpolationHole => write!(f, "unterminated interpolation hole, missing '}}'"),
+            LexerErrorKind::MissingDigits => write!(f, "number literal is missing digits"),
+            LexerErrorKind::InvalidDigit => write!(f, "invalid digit for the base of the number literal"),
+            LexerErrorKind::InvalidNumberSuffix => write!(f, "invalid number suffix, expected one of i8, i16, i32, i64, u8, u16, u32, u64, f32, f64 or their aliases"),
+            LexerErrorKind::FloatSuffixOnNonDecimal => write!(f, "only decimal number literals can have a float suffix"),
+            LexerErrorKind::IntegerSuffixOnFloat => write!(f, "float number literals can not have an integer suffix"),
+            LexerErrorKind::InvalidLiteral(kind) => write!(f, "{}", kind),
+            LexerErrorKind::ReservedKeyword => write!(f, "reserved keyword can not be used as an identifier"),
+            LexerErrorKind::MisplacedByteOrderMark => write!(f, "byte order mark (U+FEFF) is only allowed at the start of the file"),
+            LexerErrorKind::MixedScriptIdentifier => write!(f, "identifier mixes characters of different scripts"),
+            LexerErrorKind::LookalikeIdentifier => write!(f, "identifier looks like an ascii identifier, but contains non-ascii characters"),
+            LexerErrorKind::ConfusableIdentifier{ other } => write!(f, "identifier can be confused with the identifier at byte {}", other),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+use unicode_security::{skeleton, MixedScript};
+
+use crate::{LexerDiagnostic, LexerErrorKind, Token, TokenKind};
+
+/// The NFC form of an identifier. Identifiers that only differ in their normalization are the same identifier.
+/// Only allocates when the identifier is not already normalized.
+pub fn normalize_identifier(identifier: &str) -> Cow<'_, str> {
+    if identifier.is_ascii() {
+        return Cow::Borrowed(identifier);
+    }
+
+    match is_nfc_quick(identifier.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(identifier),
+        _ => Cow::Owned(identifier.nfc().collect()),
+    }
+}
+
+/// warnings for identifiers that look like something they are not
+pub(crate) fn diagnose_identifier(token: &Token) -> Vec<LexerDiagnostic> {
+    let mut diagnostics = vec![];
+    if token.value.is_ascii() {
+        return diagnostics;
+    }
+
+    let whole_token = |kind| LexerDiagnostic{ kind, offset: token.offset, len: token.len };
+    if !token.value.is_single_script() {
+        diagnostics.push(whole_token(LexerErrorKind::MixedScriptIdentifier));
+    }
+    if skeleton(token.value).all(|c| c.is_ascii()) {
+        diagnostics.push(whole_token(LexerErrorKind::LookalikeIdentifier));
+    }
+
+    diagnostics
+}
+
+/// Different identifiers with the same skeleton (UTS #39) look the same, like "apple" and "аpple" with a cyrillic а.
+/// Every identifier that can be confused with an earlier one gets a warning. Identifiers that are both ascii are never reported.
+pub(crate) fn confusable_identifiers(tokens: &[Token]) -> Vec<LexerDiagnostic> {
+    let identifiers: Vec<&Token> = tokens.iter().filter(|token| token.kind == TokenKind::Identifier).collect();
+    if identifiers.iter().all(|token| token.value.is_ascii()) {
+        return vec![];
+    }
+
+    let mut diagnostics = vec![];
+    let mut skeletons: HashMap<String, (Cow<str>, &Token)> = HashMap::new();
+    for token in identifiers {
+        let normalized = normalize_identifier(token.value);
+        let earlier = skeletons.entry(skeleton(token.value).collect()).or_insert((normalized.clone(), token));
+        if earlier.0 != normalized && !(earlier.1.value.is_ascii() && token.value.is_ascii()) {
+            diagnostics.push(LexerDiagnostic{ kind: LexerErrorKind::ConfusableIdentifier{ other: earlier.1.offset }, offset: token.offset, len: token.len });
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,112 @@
+use std::ops::Range;
+
+use crate::char_class::BYTE_ORDER_MARK;
+use crate::{current_token, Cursor, Edition, Token, MAX_LOOKAHEAD};
+
+/// replaces the bytes in range of the old source with text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit<'a> {
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+#[derive(Debug)]
+pub struct Relexed<'src> {
+    /// the tokens of the whole new source
+    pub tokens: Vec<Token<'src>>,
+    /// the previous tokens that were thrown away
+    pub removed: Range<usize>,
+    /// the tokens that replaced them. Only these were lexed again, the others are the previous tokens moved to the new source.
+    pub inserted: Range<usize>,
+}
+
+/// Re-lexes the part of src that was touched by the edit.
+/// previous must be the tokens of the whole source before the edit and src the source after it.
+pub fn relex<'src>(previous: &[Token<'_>], edit: &TextEdit<'_>, src: &'src str) -> Relexed<'src> {
+    relex_with_edition(previous, edit, src, Edition::default())
+}
+
+/// relex for a source of an older edition, previous must be lexed with the same edition
+pub fn relex_with_edition<'src>(previous: &[Token<'_>], edit: &TextEdit<'_>, src: &'src str, edition: Edition) -> Relexed<'src> {
+    let old_len = previous.last().map_or(0, |token| token.end());
+    debug_assert_eq!(src.len() + edit.range.len(), old_len + edit.text.len(), "src is not the edited source");
+
+    let first = first_changed_token(previous, edit, src);
+    let mut tokens = Vec::with_capacity(previous.len());
+    tokens.extend(previous[..first].iter().map(|token| move_token(token, token.offset, src)));
+
+    let moved_offset = |token: &Token| token.offset - edit.range.end + edit.range.start + edit.text.len();
+
+    let mut offset = previous.get(first).map_or(old_len, |token| token.offset);
+    let mut old = first;
+    loop {
+        // the first old token behind the edit that could start at offset
+        while old < previous.len() && (previous[old].offset < edit.range.end || moved_offset(&previous[old]) < offset) {
+            old += 1;
+        }
+
+        // the lexer only looks forward and everything from here on is unchanged
+        if old == previous.len() && offset >= src.len() {
+            break;
+        }
+        if old < previous.len() && moved_offset(&previous[old]) == offset && !is_near_file_start(&previous[old], offset) {
+            break;
+        }
+
+        let token = current_token(src, offset, edition);
+        offset += token.len;
+        tokens.push(token);
+    }
+
+    let inserted = first..tokens.len();
+    tokens.extend(previous[old..].iter().map(|token| move_token(token, moved_offset(token), src)));
+
+    Relexed { tokens, removed: first..old, inserted }
+}
+
+/// The index of the first previous token that has to be lexed again.
+/// A token in front of the edit is only kept when neither the lexer looked at the edited text while lexing it,
+/// nor while lexing any token in front of it.
+fn first_changed_token(previous: &[Token<'_>], edit: &TextEdit<'_>, src: &str) -> usize {
+    let mut first = previous.partition_point(|token| token.end() <= edit.range.start);
+
+    // Tokens further in front than MAX_LOOKAHEAD unchanged tokens can't have looked at the edit.
+    // The exception is the raw string check, which looks through any run of #.
+    let mut unchanged = 0;
+    while first > 0 && unchanged < MAX_LOOKAHEAD {
+        let token = &previous[first - 1];
+        if token.value != "#" && reach(src, token.offset) <= edit.range.start {
+            unchanged += 1;
+        }
+        else {
+            unchanged = 0;
+        }
+        first -= 1;
+    }
+
+    first + unchanged
+}
+
+/// Tokens at the start of the file lex differently, a shebang is only a shebang there.
+/// Old tokens are never reused when they move to or away from it.
+fn is_near_file_start(token: &Token<'_>, offset: usize) -> bool {
+    let file_start = BYTE_ORDER_MARK.len_utf8();
+    token.offset <= file_start || offset <= file_start
+}
+
+/// one past the last byte of src the lexer looked at to lex the token at offset
+fn reach(src: &str, offset: usize) -> usize {
+    // keywords and identifiers are lexed the same way up to the end, so the edition does not matter here
+    let mut cursor = Cursor::at(src, offset, Edition::default());
+    cursor.advance_token();
+    offset + cursor.reach()
+}
+
+fn move_token<'src>(token: &Token<'_>, offset: usize, src: &'src str) -> Token<'src> {
+    Token {
+        kind: token.kind,
+        value: &src[offset..offset + token.len],
+        len: token.len,
+        offset,
+    }
+}
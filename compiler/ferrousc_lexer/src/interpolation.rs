@@ -0,0 +1,100 @@
+use crate::{current_token, Edition, Token, TokenKind};
+
+/// the tokens of an interpolated string literal. Offsets point into the lexed source, like the offset of the literal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterpolatedString<'src> {
+    /// $"
+    pub start: Token<'src>,
+    pub parts: Vec<InterpolatedPart<'src>>,
+    /// None when the literal is not terminated
+    pub end: Option<Token<'src>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InterpolatedPart<'src> {
+    /// an InterpolatedStringText token, never empty. Use cook_string to decode escapes and {{ }}
+    Text { text: Token<'src> },
+    /// { tokens }. r_brace is None when the hole is not closed before the end of the source
+    Hole { l_brace: Token<'src>, tokens: Vec<Token<'src>>, r_brace: Option<Token<'src>> },
+}
+
+/// Splits an InterpolatedStringLiteral token into its text parts and the token streams of its holes.
+/// Concatenating the values of all returned tokens gives back the value of the literal.
+pub fn split_interpolated_string<'src>(token: &Token<'src>) -> InterpolatedString<'src> {
+    split_interpolated_string_with_edition(token, Edition::default())
+}
+
+/// split_interpolated_string for a literal of an older edition, its holes are lexed with the keywords of the edition
+pub fn split_interpolated_string_with_edition<'src>(token: &Token<'src>, edition: Edition) -> InterpolatedString<'src> {
+    debug_assert!(matches!(token.kind, TokenKind::InterpolatedStringLiteral{..}));
+
+    let src = token.value;
+    let sub_token = |kind, start: usize, end: usize| -> Token<'src> { Token{ kind, value: &src[start..end], len: end - start, offset: token.offset + start } };
+
+    let start = sub_token(TokenKind::InterpolatedStringStart, 0, 2);
+    let mut parts = vec![];
+    let mut text_start = 2;
+    let mut pos = 2;
+
+    let push_text = |parts: &mut Vec<InterpolatedPart<'src>>, text_start: usize, text_end: usize| {
+        if text_end > text_start {
+            parts.push(InterpolatedPart::Text{ text: sub_token(TokenKind::InterpolatedStringText, text_start, text_end) });
+        }
+    };
+
+    while pos < src.len() {
+        let rest = &src[pos..];
+        if rest.starts_with('"') {
+            push_text(&mut parts, text_start, pos);
+            let end = Some(sub_token(TokenKind::InterpolatedStringEnd, pos, pos + 1));
+            return InterpolatedString{ start, parts, end };
+        }
+        else if rest.starts_with("{{") || rest.starts_with("}}") {
+            pos += 2;
+        }
+        else if rest.starts_with('{') {
+            push_text(&mut parts, text_start, pos);
+            let l_brace = sub_token(TokenKind::LBrace, pos, pos + 1);
+            let (tokens, r_brace) = split_hole(token, pos + 1, edition);
+            pos = r_brace.as_ref().or(tokens.last()).map_or(l_brace.end(), Token::end) - token.offset;
+            text_start = pos;
+            parts.push(InterpolatedPart::Hole{ l_brace, tokens, r_brace });
+        }
+        else {
+            let mut chars = rest.chars();
+            let c = chars.next().unwrap();
+            pos += c.len_utf8();
+            // the escaped char can never end the text, the escape itself is checked by cook_string
+            if c == '\\' {
+                pos += chars.next().map_or(0, char::len_utf8);
+            }
+        }
+    }
+    push_text(&mut parts, text_start, pos);
+
+    InterpolatedString{ start, parts, end: None }
+}
+
+/// lexes the tokens of a hole the same way the lexer did, up to the '}' that closes it
+fn split_hole<'src>(token: &Token<'src>, hole_start: usize, edition: Edition) -> (Vec<Token<'src>>, Option<Token<'src>>) {
+    let src = token.value;
+    let mut tokens = vec![];
+    let mut depth = 0;
+    let mut pos = hole_start;
+
+    while pos < src.len() {
+        let mut hole_token = current_token(src, pos, edition);
+        pos += hole_token.len;
+        hole_token.offset += token.offset;
+
+        match hole_token.kind {
+            TokenKind::LBrace => depth += 1,
+            TokenKind::RBrace if depth == 0 => return (tokens, Some(hole_token)),
+            TokenKind::RBrace => depth -= 1,
+            _ => {},
+        }
+        tokens.push(hole_token);
+    }
+
+    (tokens, None)
+}
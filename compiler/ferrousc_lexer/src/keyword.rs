@@ -0,0 +1,135 @@
+use crate::{normalize_identifier, split_interpolated_string_with_edition, InterpolatedPart, LexerDiagnostic, LexerErrorKind, Token, TokenKind};
+
+/// The version of the language a source is written in. Newer editions reserve the words of upcoming features,
+/// older editions keep accepting them as identifiers, so existing code does not break.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Edition {
+    /// only the keywords that every edition has
+    Edition2025,
+    /// adds the struct, enum, impl, self and trait keywords, reserves RESERVED_KEYWORDS and adds the contextual keywords
+    #[default]
+    Edition2026,
+}
+
+/// not keywords yet, but can not be used as identifiers from Edition2026 on
+const RESERVED_KEYWORDS: [&str; 11] = [
+    "as", "const", "continue", "loop", "mod", "null",
+    "pub", "Self", "type", "use", "where",
+];
+
+/// keywords from Edition2026 on that older editions lex as identifiers, so they can not be used as identifiers either
+const NEW_KEYWORDS: [&str; 4] = ["struct", "enum", "impl", "trait"];
+
+/// Words that are only keywords in some positions of the parser and identifiers everywhere else
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContextualKeyword {
+    /// get in a property accessor
+    Get,
+    /// set in a property accessor
+    Set,
+    /// union in front of a declaration
+    Union,
+    /// default as the pattern of a match arm, the same as _
+    Default,
+}
+
+impl ContextualKeyword {
+    /// The contextual keyword the identifier can stand for. Whether it is one depends on where the parser finds it.
+    pub fn from_identifier(identifier: &str, edition: Edition) -> Option<ContextualKeyword> {
+        if edition < Edition::Edition2026 {
+            return None;
+        }
+
+        match normalize_identifier(identifier).as_ref() {
+            "get"       => Some(ContextualKeyword::Get),
+            "set"       => Some(ContextualKeyword::Set),
+            "union"     => Some(ContextualKeyword::Union),
+            "default"   => Some(ContextualKeyword::Default),
+            _ => None,
+        }
+    }
+}
+
+/// the keywords of the edition, the others are identifiers
+pub(crate) fn bake_keyword(identifier: &str, edition: Edition) -> Option<TokenKind> {
+    use TokenKind::*;
+    match normalize_identifier(identifier).as_ref() {
+        "let"       => Some(LetKeyword),
+        "mut"       => Some(MutKeyword),
+        "match"     => Some(MatchKeyword),
+        "for"       => Some(ForKeyword),
+        "in"        => Some(InKeyword),
+        "while"     => Some(WhileKeyword),
+        "if"        => Some(IfKeyword),
+        "else"      => Some(ElseKeyword),
+        "fn"        => Some(FunctionKeyword),
+        "return"    => Some(ReturnKeyword),
+        "break"     => Some(BreakKeyword),
+        "struct"    if edition >= Edition::Edition2026 => Some(StructKeyword),
+        "enum"      if edition >= Edition::Edition2026 => Some(EnumKeyword),
+        "impl"      if edition >= Edition::Edition2026 => Some(ImplKeyword),
+        "self"      if edition >= Edition::Edition2026 => Some(SelfKeyword),
+        "trait"     if edition >= Edition::Edition2026 => Some(TraitKeyword),
+        "true"      => Some(TrueKeyword),
+        "false"     => Some(FalseKeyword),
+        _ => None,
+    }
+}
+
+/// Whether the word can not be used as an identifier in the edition. Reserved keywords are lexed as identifiers,
+/// the new keywords as keywords, the parser reports both where it expects an identifier
+pub fn is_reserved_keyword(identifier: &str, edition: Edition) -> bool {
+    let identifier = normalize_identifier(identifier);
+    edition >= Edition::Edition2026 && (RESERVED_KEYWORDS.contains(&identifier.as_ref()) || NEW_KEYWORDS.contains(&identifier.as_ref()))
+}
+
+/// an error for every identifier that is reserved in the edition, including the ones in interpolation holes
+pub(crate) fn reserved_keywords(tokens: &[Token], edition: Edition) -> Vec<LexerDiagnostic> {
+    let mut diagnostics = vec![];
+    for token in tokens {
+        match token.kind {
+            TokenKind::Identifier if is_reserved_keyword(token.value, edition) => {
+                diagnostics.push(LexerDiagnostic{ kind: LexerErrorKind::ReservedKeyword, offset: token.offset, len: token.len });
+            },
+            TokenKind::InterpolatedStringLiteral{..} => {
+                for part in split_interpolated_string_with_edition(token, edition).parts {
+                    if let InterpolatedPart::Hole{ tokens, .. } = part {
+                        diagnostics.extend(reserved_keywords(&tokens, edition));
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reserved_keywords_depend_on_edition() {
+        for &keyword in RESERVED_KEYWORDS.iter() {
+            assert!(is_reserved_keyword(keyword, Edition::Edition2026), "{}", keyword);
+            assert!(!is_reserved_keyword(keyword, Edition::Edition2025), "{}", keyword);
+            assert_eq!(bake_keyword(keyword, Edition::Edition2026), None, "{}", keyword);
+        }
+        for &keyword in NEW_KEYWORDS.iter() {
+            assert!(is_reserved_keyword(keyword, Edition::Edition2026), "{}", keyword);
+            assert!(!is_reserved_keyword(keyword, Edition::Edition2025), "{}", keyword);
+            assert_eq!(bake_keyword(keyword, Edition::Edition2025), None, "{}", keyword);
+        }
+        assert!(!is_reserved_keyword("structure", Edition::default()));
+    }
+
+    #[test]
+    fn test_contextual_keywords_are_not_reserved() {
+        for keyword in ["get", "set", "union", "default"].iter() {
+            assert!(ContextualKeyword::from_identifier(keyword, Edition::Edition2026).is_some(), "{}", keyword);
+            assert_eq!(ContextualKeyword::from_identifier(keyword, Edition::Edition2025), None, "{}", keyword);
+            assert!(!is_reserved_keyword(keyword, Edition::Edition2026), "{}", keyword);
+        }
+    }
+}
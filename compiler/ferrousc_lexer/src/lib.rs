@@ -1,71 +1,29 @@
-use cursor::Cursor;
+use char_class::{is_identifier_continue, is_identifier_start, is_newline, is_whitespace, BYTE_ORDER_MARK};
+use cursor::{Cursor, EOF_CHAR};
+use keyword::bake_keyword;
 
+pub use diagnostic::{diagnose, lex, lex_with_edition, Lexed, LexerDiagnostic, LexerErrorKind};
+pub use ferrousc_diagnostics::{LineCol, LineIndex};
+pub use identifier::normalize_identifier;
+pub use incremental::{relex, relex_with_edition, Relexed, TextEdit};
+pub use interpolation::{split_interpolated_string, split_interpolated_string_with_edition, InterpolatedPart, InterpolatedString};
+pub use keyword::{is_reserved_keyword, ContextualKeyword, Edition};
+pub use literal::{cook_char, cook_doc_comment, cook_string, evaluate_negated_number, evaluate_number, LiteralError, LiteralErrorKind, Number, NumberValue};
+pub use trivia::{attach_trivia, tokenize_with_trivia, TokenWithTrivia};
+
+mod char_class;
 mod cursor;
+mod diagnostic;
+mod identifier;
+mod incremental;
+mod interpolation;
+mod keyword;
+mod literal;
+mod trivia;
 
 #[cfg(test)]
 mod tests;
 
-const WHITESPACE_CHARS: [char; 23] = [
-    ' ',        // space
-    '\t',       // tab
-    '\u{000B}', // vertical tab
-    '\u{000C}', // form feed
-    '\u{00A0}', // no break space
-    '\u{1680}', // ogham space mark
-    '\u{180E}', // mongolian vowel seperator
-    '\u{2000}', // en quad
-    '\u{2001}', // em quad
-    '\u{2002}', // en space
-    '\u{2003}', // em space
-    '\u{2004}', // three-per-em space
-    '\u{2005}', // four-per-em space
-    '\u{2006}', // six-per-em space
-    '\u{2007}', // figure space
-    '\u{2008}', // punctuation space
-    '\u{2009}', // thin space
-    '\u{200A}', // hair space
-    '\u{200B}', // zero-width space
-    '\u{202F}', // narrow no-break space
-    '\u{205F}', // medium mathmatical space
-    '\u{3000}', // idiographic space
-    '\u{FEFF}', // zero with no-break space
-];
-
-const NEWLINE_CHARS: [char; 2] = [
-    '\n',
-    '\r',
-];
-
-/// does not include whitespace or newline characters!
-const NON_LITERAL_CHARS: [char; 26] = [
-    '+',
-    '-',
-    '*',
-    '/',
-    '%',
-    '&',
-    '|',
-    '!',
-    '=',
-    '^',
-    '<',
-    '>',
-    ';',
-    ':',
-    ',',
-    '.',
-    '(',
-    ')',
-    '[',
-    ']',
-    '{',
-    '}',
-    '~',
-    '?',
-    '\'',
-    '"',
-];
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TokenKind {
     /// any whitespace char
@@ -75,8 +33,19 @@ pub enum TokenKind {
 
     /// from // to eol
     LineComment,
-    /// from "/*" to "*/". When "*/" is missing terminated will be false
+    /// from "/*" to the matching "*/". Block comments nest, so every "/*" inside needs its own "*/".
+    /// When one of them is missing terminated will be false
     MultilineComment { terminated: bool },
+    /// from /// to eol, documents the following declaration. //// is a LineComment
+    DocComment,
+    /// from "/**" to "*/", documents the following declaration. /**/ and /*** are MultilineComments.
+    /// When "*/" is missing terminated will be false
+    MultilineDocComment { terminated: bool },
+    /// U+FEFF, the utf-8 byte order mark. Only valid as the first char of a file, diagnose reports it anywhere else
+    ByteOrderMark,
+    /// #!/usr/bin/env ferrous run, up to the end of the line. Only lexed at the start of a file, behind an optional byte order mark.
+    /// #![ is not a shebang
+    Shebang,
 
     /// /
     Slash,
@@ -183,6 +152,8 @@ pub enum TokenKind {
     Comma,
     /// ;
     Semicolon,
+    /// # starts an attribute
+    Hash,
     /// :
     Colon,
     /// ::
@@ -197,13 +168,29 @@ pub enum TokenKind {
 
     /// "some text or \"escaped characters\" \n". When the second '"' is missing terminated will be false
     StringLiteral { terminated: bool },
+    /// r"C:\path" or r#"contains "quotes""#, no escape sequences. When the closing '"' or one of its '#' is missing terminated will be false
+    RawStringLiteral { terminated: bool },
+    /// three or more '"' around text that can span multiple lines. The indentation of the closing quotes is stripped from every line.
+    /// When the closing quotes are missing terminated will be false
+    MultilineStringLiteral { terminated: bool },
+    /// $"Hello {name}!". Holes are lexed as regular tokens, so strings and braces inside of them can not end the literal.
+    /// {{ and }} are escaped braces. Use split_interpolated_string to get the parts. When the closing '"' is missing terminated will be false
+    InterpolatedStringLiteral { terminated: bool },
+    /// the $" of an interpolated string, only produced by split_interpolated_string
+    InterpolatedStringStart,
+    /// the text between the holes of an interpolated string, only produced by split_interpolated_string
+    InterpolatedStringText,
+    /// the closing " of an interpolated string, only produced by split_interpolated_string
+    InterpolatedStringEnd,
     /// 'c'. When the second "'" is missing terminated will be false
     CharLiteral { terminated: bool },
-    /// 5 25.5 0b1011_0011 0xAFfe 0o3710 '_' can be used between numbers and do not affect the behaviour. 
+    /// 5 25.5 0b1011_0011 0xAFfe 0o3710 1.5e-3 5_i32 '_' can be used between numbers and do not affect the behaviour. 
     /// base for what base the number is in. has_digits will be false if there a no digits in the literal.
-    NumberLiteral { base: Base, has_digits: bool},
+    /// has_exponent is true for decimal literals like 2E10. suffix is anything directly following the digits.
+    NumberLiteral { base: Base, has_digits: bool, has_exponent: bool, suffix: Option<NumberSuffix> },
     
-    /// Any identifier. Must start with _ or any unicode char that is not used anywhere else. May contain Numbers after the first char.
+    /// Any identifier. Follows UAX #31: starts with _ or an XID_Start char, continues with XID_Continue chars.
+    /// Keywords are recognized after NFC normalization, see normalize_identifier.
     Identifier,
     
     /// true
@@ -232,8 +219,119 @@ pub enum TokenKind {
     ReturnKeyword,
     /// break;
     BreakKeyword,
+    /// struct
+    StructKeyword,
+    /// enum
+    EnumKeyword,
+    /// impl
+    ImplKeyword,
+    /// self, the receiver of a method
+    SelfKeyword,
+    /// trait
+    TraitKeyword,
 
+    /// any char that can not start a token. Unknown tokens are always one char long.
     Unknown,
+
+    /// zero length, only produced by attach_trivia. Its leading trivia is everything after the last token
+    EndOfFile,
+}
+
+impl TokenKind {
+    /// Trivia is everything the parser skips. Unknown chars are trivia as well,
+    /// so they can not break the parse, diagnose still reports them.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, TokenKind::Whitespace
+            | TokenKind::Newline
+            | TokenKind::LineComment
+            | TokenKind::MultilineComment{..}
+            | TokenKind::DocComment
+            | TokenKind::MultilineDocComment{..}
+            | TokenKind::ByteOrderMark
+            | TokenKind::Shebang
+            | TokenKind::Unknown)
+    }
+
+    /// the text of tokens that are always spelled the same, like ; or fn
+    pub fn text(&self) -> Option<&'static str> {
+        let text = match self {
+            TokenKind::Slash                  => "/",
+            TokenKind::Star                   => "*",
+            TokenKind::Minus                  => "-",
+            TokenKind::MinusMinus             => "--",
+            TokenKind::Plus                   => "+",
+            TokenKind::PlusPlus               => "++",
+            TokenKind::Caret                  => "^",
+            TokenKind::Percent                => "%",
+            TokenKind::Tilde                  => "~",
+            TokenKind::Amp                    => "&",
+            TokenKind::AmpAmp                 => "&&",
+            TokenKind::Bar                    => "|",
+            TokenKind::BarBar                 => "||",
+            TokenKind::Greater                => ">",
+            TokenKind::GreaterGreater         => ">>",
+            TokenKind::Less                   => "<",
+            TokenKind::LessLess               => "<<",
+            TokenKind::EqualEqual             => "==",
+            TokenKind::EqualsGreater          => "=>",
+            TokenKind::MinusGreater           => "->",
+            TokenKind::Equal                  => "=",
+            TokenKind::PlusEqual              => "+=",
+            TokenKind::MinusEqual             => "-=",
+            TokenKind::PercentEqual           => "%=",
+            TokenKind::StarEqual              => "*=",
+            TokenKind::SlashEqual             => "/=",
+            TokenKind::AmpEqual               => "&=",
+            TokenKind::BarEqual               => "|=",
+            TokenKind::BangEqual              => "!=",
+            TokenKind::GreaterEqual           => ">=",
+            TokenKind::LessEqual              => "<=",
+            TokenKind::CaretEqual             => "^=",
+            TokenKind::TildeEqual             => "~=",
+            TokenKind::LessLessEqual          => "<<=",
+            TokenKind::GreaterGreaterEqual    => ">>=",
+            TokenKind::QuestionQuestionEquals => "??=",
+            TokenKind::QuestionLBracket       => "?[",
+            TokenKind::LBracket               => "[",
+            TokenKind::RBracket               => "]",
+            TokenKind::LBrace                 => "{",
+            TokenKind::RBrace                 => "}",
+            TokenKind::LParen                 => "(",
+            TokenKind::RParen                 => ")",
+            TokenKind::QuestionDot            => "?.",
+            TokenKind::Dot                    => ".",
+            TokenKind::DotDot                 => "..",
+            TokenKind::DotDotEqual            => "..=",
+            TokenKind::Comma                  => ",",
+            TokenKind::Semicolon              => ";",
+            TokenKind::Hash                   => "#",
+            TokenKind::Colon                  => ":",
+            TokenKind::ColonColon             => "::",
+            TokenKind::Bang                   => "!",
+            TokenKind::Question               => "?",
+            TokenKind::QuestionQuestion       => "??",
+            TokenKind::TrueKeyword            => "true",
+            TokenKind::FalseKeyword           => "false",
+            TokenKind::LetKeyword             => "let",
+            TokenKind::MutKeyword             => "mut",
+            TokenKind::MatchKeyword           => "match",
+            TokenKind::IfKeyword              => "if",
+            TokenKind::ElseKeyword            => "else",
+            TokenKind::ForKeyword             => "for",
+            TokenKind::InKeyword              => "in",
+            TokenKind::WhileKeyword           => "while",
+            TokenKind::FunctionKeyword        => "fn",
+            TokenKind::ReturnKeyword          => "return",
+            TokenKind::BreakKeyword           => "break",
+            TokenKind::StructKeyword          => "struct",
+            TokenKind::EnumKeyword            => "enum",
+            TokenKind::ImplKeyword            => "impl",
+            TokenKind::SelfKeyword            => "self",
+            TokenKind::TraitKeyword           => "trait",
+            _ => return None,
+        };
+        Some(text)
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -244,38 +342,94 @@ pub enum Base {
     Decimal,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Token {
+/// the type suffix of a number literal. The C# style aliases (int, ulong, double, ...) map to the same suffixes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NumberSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    /// anything else, like 5_abc or 5i128
+    Invalid,
+}
+
+impl NumberSuffix {
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumberSuffix::F32 | NumberSuffix::F64)
+    }
+}
+
+/// Tokens borrow their text from the source, so lexing does not allocate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Token<'src> {
     pub kind: TokenKind,
-    pub value: String,
+    pub value: &'src str,
     pub len: usize,
+    /// byte offset of the first char of the token in the source. Use a LineIndex to get the line and column.
+    pub offset: usize,
 }
 
-impl Token {
-    pub(crate) fn new(kind: TokenKind, value: String, len: usize) -> Token {
-        Token { kind, value, len }
+impl Token<'_> {
+    /// byte offset one past the last char of the token
+    pub fn end(&self) -> usize {
+        self.offset + self.len
     }
 }
 
-fn current_token(src: &str) -> Token {
-    Cursor::new(src).advance_token()
+/// the lexer never peeks more than this many chars past the end of a token, except for the # of a raw string start
+pub(crate) const MAX_LOOKAHEAD: usize = 3;
+
+/// the cursor only ever sees the rest of the source, so the offset is filled in afterwards
+pub(crate) fn current_token(src: &str, offset: usize, edition: Edition) -> Token<'_> {
+    let mut token = Cursor::at(src, offset, edition).advance_token();
+    token.offset = offset;
+    token
+}
+
+/// tokenizes a source of the latest edition
+pub fn tokenize(src: &str) -> impl Iterator<Item = Token<'_>> {
+    tokenize_with_edition(src, Edition::default())
 }
 
-pub fn tokenize(mut src: &str) -> impl Iterator<Item = Token> + '_ {
+pub fn tokenize_with_edition(src: &str, edition: Edition) -> impl Iterator<Item = Token<'_>> {
+    let mut offset = 0;
     std::iter::from_fn(move || {
-        if src.is_empty() {
+        if offset >= src.len() {
             return None;
         }
     
-        let token = current_token(src);
-        src = &src[token.len..];
+        let token = current_token(src, offset, edition);
+        offset += token.len;
         Some(token)
     })
 }
 
-impl Cursor<'_> {
-    pub(crate) fn advance_token(&mut self) -> Token {
+/// Concatenates the text of the tokens. The tokens of tokenize always give back exactly the source they were lexed from.
+pub fn to_source<'a, 'src: 'a>(tokens: impl IntoIterator<Item = &'a Token<'src>>) -> String {
+    tokens.into_iter().map(|token| token.value).collect()
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn advance_token(&mut self) -> Token<'a> {
+        self.start_token();
+        let at_file_start = self.at_file_start();
+        self.set_at_file_start(false);
+        if at_file_start && self.is_shebang_start() {
+            return self.lex_shebang();
+        }
+
         let c = self.eat();
+        if c == BYTE_ORDER_MARK {
+            // a shebang can still follow a leading byte order mark
+            self.set_at_file_start(at_file_start);
+            return self.token(TokenKind::ByteOrderMark);
+        }
 
         let trivia = self.eat_trivia(c);
         if let Some(t) = trivia {
@@ -286,96 +440,96 @@ impl Cursor<'_> {
             '/' => match self.peek() {
                 '/' => self.lex_line_comment(),
                 '*' => self.lex_multiline_comment(),
-                _ => Token::new(TokenKind::Slash, "/".to_owned(), 1),
+                _ => self.token(TokenKind::Slash),
             },
             '*' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::StarEqual, "*=".to_owned(), 2)
+                    self.token(TokenKind::StarEqual)
                 },
-                _ => Token::new(TokenKind::Star, "*".to_owned(), 1),
+                _ => self.token(TokenKind::Star),
             },
             '+' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::PlusEqual, "+=".to_owned(), 2)
+                    self.token(TokenKind::PlusEqual)
                 },
                 '+' => {
                     self.eat();
-                    Token::new(TokenKind::PlusPlus, "++".to_owned(), 2)
+                    self.token(TokenKind::PlusPlus)
                 },
-                _ => Token::new(TokenKind::Plus, "+".to_owned(), 1),
+                _ => self.token(TokenKind::Plus),
             },
             '-' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::MinusEqual, "-=".to_owned(), 2)
+                    self.token(TokenKind::MinusEqual)
                 },
                 '>' => {
                     self.eat();
-                    Token::new(TokenKind::MinusGreater, "->".to_owned(), 2)
+                    self.token(TokenKind::MinusGreater)
                 },
                 '-' => {
                     self.eat();
-                    Token::new(TokenKind::MinusMinus, "--".to_owned(), 2)
+                    self.token(TokenKind::MinusMinus)
                 },
-                _ => Token::new(TokenKind::Minus, "-".to_owned(), 1),
+                _ => self.token(TokenKind::Minus),
             },
             '&' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::AmpEqual, "&=".to_owned(), 2)
+                    self.token(TokenKind::AmpEqual)
                 },
                 '&' => {
                     self.eat();
-                    Token::new(TokenKind::AmpAmp, "&&".to_owned(), 2)
+                    self.token(TokenKind::AmpAmp)
                 },
-                _ => Token::new(TokenKind::Amp, "&".to_owned(), 1),
+                _ => self.token(TokenKind::Amp),
             },
             '|' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::BarEqual, "|=".to_owned(), 2)
+                    self.token(TokenKind::BarEqual)
                 },
                 '|' => {
                     self.eat();
-                    Token::new(TokenKind::BarBar, "||".to_owned(), 2)
+                    self.token(TokenKind::BarBar)
                 },
-                _ => Token::new(TokenKind::Bar, "|".to_owned(), 1),
+                _ => self.token(TokenKind::Bar),
             },
             '>' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::GreaterEqual, ">=".to_owned(), 2)
+                    self.token(TokenKind::GreaterEqual)
                 },
                 '>' => {
                     self.eat();
                     match self.peek() {
                         '=' => {
                             self.eat();
-                            Token::new(TokenKind::GreaterGreaterEqual, ">>=".to_owned(), 3)
+                            self.token(TokenKind::GreaterGreaterEqual)
                         },
-                        _ => Token::new(TokenKind::GreaterGreater, ">>".to_owned(), 2),
+                        _ => self.token(TokenKind::GreaterGreater),
                     }
                 },
-                _ => Token::new(TokenKind::Greater, ">".to_owned(), 1),
+                _ => self.token(TokenKind::Greater),
             },
             '<' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::LessEqual, "<=".to_owned(), 2)
+                    self.token(TokenKind::LessEqual)
                 },
                 '<' => {
                     self.eat();
                     match self.peek() {
                         '=' => {
                             self.eat();
-                            Token::new(TokenKind::LessLessEqual, "<<=".to_owned(), 3)
+                            self.token(TokenKind::LessLessEqual)
                         },
-                        _ => Token::new(TokenKind::LessLess, "<<".to_owned(), 2),
+                        _ => self.token(TokenKind::LessLess),
                     }
                 },
-                _ => Token::new(TokenKind::Less, "<".to_owned(), 1),
+                _ => self.token(TokenKind::Less),
             },
             '?' => match self.peek() {
                 '?' => {
@@ -383,52 +537,52 @@ impl Cursor<'_> {
                     match self.peek() {
                         '=' => {
                             self.eat();
-                            Token::new(TokenKind::QuestionQuestionEquals, "??=".to_owned(), 3)
+                            self.token(TokenKind::QuestionQuestionEquals)
                         },
-                        _ => Token::new(TokenKind::QuestionQuestion, "??".to_owned(), 2),
+                        _ => self.token(TokenKind::QuestionQuestion),
                     }
                 },
                 '.' => {
                     self.eat();
-                    Token::new(TokenKind::QuestionDot, "?.".to_owned(), 2)
+                    self.token(TokenKind::QuestionDot)
                 },
                 '[' => {
                     self.eat();
-                    Token::new(TokenKind::QuestionLBracket, "?[".to_owned(), 2)
+                    self.token(TokenKind::QuestionLBracket)
                 },
-                _ => Token::new(TokenKind::Question, "<".to_owned(), 1),
+                _ => self.token(TokenKind::Question),
             },
             '=' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::EqualEqual, "==".to_owned(), 2)
+                    self.token(TokenKind::EqualEqual)
                 },
                 '>' => {
                     self.eat();
-                    Token::new(TokenKind::EqualsGreater, "=>".to_owned(), 2)
+                    self.token(TokenKind::EqualsGreater)
                 },
-                _ => Token::new(TokenKind::Equal, "=".to_owned(), 1),
+                _ => self.token(TokenKind::Equal),
             },
             '!' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::BangEqual, "!=".to_owned(), 2)
+                    self.token(TokenKind::BangEqual)
                 },
-                _ => Token::new(TokenKind::Bang, "!".to_owned(), 1),
+                _ => self.token(TokenKind::Bang),
             },
             '%' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::PercentEqual, "%=".to_owned(), 2)
+                    self.token(TokenKind::PercentEqual)
                 },
-                _ => Token::new(TokenKind::Percent, "%".to_owned(), 1),
+                _ => self.token(TokenKind::Percent),
             },
             ':' => match self.peek() {
                 ':' => {
                     self.eat();
-                    Token::new(TokenKind::ColonColon, "::".to_owned(), 2)
+                    self.token(TokenKind::ColonColon)
                 },
-                _ => Token::new(TokenKind::Colon, ":".to_owned(), 1),
+                _ => self.token(TokenKind::Colon),
             },
             '.' => match self.peek() {
                 '.' => {
@@ -436,185 +590,301 @@ impl Cursor<'_> {
                     match self.peek() {
                         '=' => {
                             self.eat();
-                            Token::new(TokenKind::DotDotEqual, "..=".to_owned(), 3)
+                            self.token(TokenKind::DotDotEqual)
                         },
                         _ => {
-                            Token::new(TokenKind::DotDot, "..".to_owned(), 2)
+                            self.token(TokenKind::DotDot)
                         },
                     }
                 },
-                _ => Token::new(TokenKind::Dot, ".".to_owned(), 1),
+                _ => self.token(TokenKind::Dot),
             },
             '~' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::TildeEqual, "~=".to_owned(), 2)
+                    self.token(TokenKind::TildeEqual)
                 },
-                _ => Token::new(TokenKind::Tilde, "~".to_owned(), 1),
+                _ => self.token(TokenKind::Tilde),
             },
             '^' => match self.peek() {
                 '=' => {
                     self.eat();
-                    Token::new(TokenKind::CaretEqual, "^=".to_owned(), 2)
+                    self.token(TokenKind::CaretEqual)
                 },
-                _ => Token::new(TokenKind::Caret, "^".to_owned(), 1),
+                _ => self.token(TokenKind::Caret),
             },
-            ',' => Token::new(TokenKind::Comma, ",".to_owned(), 1),
-            ';' => Token::new(TokenKind::Semicolon, ";".to_owned(), 1),
-            '(' => Token::new(TokenKind::LParen, "(".to_owned(), 1),
-            ')' => Token::new(TokenKind::RParen, ")".to_owned(), 1),
-            '[' => Token::new(TokenKind::LBracket, "[".to_owned(), 1),
-            ']' => Token::new(TokenKind::RBracket, "]".to_owned(), 1),
-            '{' => Token::new(TokenKind::LBrace, "{".to_owned(), 1),
-            '}' => Token::new(TokenKind::RBrace, "}".to_owned(), 1),
-            '"' => self.lex_string_literal(&c),
-            '\'' => self.lex_char_literal(&c),
+            ',' => self.token(TokenKind::Comma),
+            '#' => self.token(TokenKind::Hash),
+            ';' => self.token(TokenKind::Semicolon),
+            '(' => self.token(TokenKind::LParen),
+            ')' => self.token(TokenKind::RParen),
+            '[' => self.token(TokenKind::LBracket),
+            ']' => self.token(TokenKind::RBracket),
+            '{' => self.token(TokenKind::LBrace),
+            '}' => self.token(TokenKind::RBrace),
+            '$' if self.peek() == '"' => self.lex_interpolated_string_literal(),
+            '"' if self.peek() == '"' && self.peek_n(1) == '"' => self.lex_multiline_string_literal(),
+            '"' => self.lex_string_literal(),
+            'r' if self.is_raw_string_start() => self.lex_raw_string_literal(),
+            '\'' => self.lex_char_literal(),
             '0'..='9' => self.lex_number_literal(&c),
-            c if is_literal(&c) => self.lex_identifier(&c),
-            _ => panic!("unknown match encountered when every possible char should be handled!")
+            c if is_identifier_start(c) => self.lex_identifier(),
+            _ => self.token(TokenKind::Unknown),
         }
     }
 
-    fn eat_trivia(&mut self, mut current_char: char) -> Option<Token> {
+    /// everything eaten since the start of the token
+    fn token(&self, kind: TokenKind) -> Token<'a> {
+        let value = self.consumed();
+        Token { kind, value, len: value.len(), offset: 0 }
+    }
+
+    fn eat_trivia(&mut self, current_char: char) -> Option<Token<'a>> {
         let newline = self.lex_newline(&current_char);        
         if let Some(token) = newline {
             return Some(token);
         }
         
-        let mut lexeme: String = String::new();
-        while potential_whitespace(&current_char) {
-            lexeme.push(current_char);
-            current_char = self.eat();
+        if !is_whitespace(current_char) {
+            return None;
         }
 
-        if lexeme.is_empty() {
-            None
-        }
-        else {
-            let len = lexeme.len();
-            Some(Token::new(TokenKind::Whitespace, lexeme, len))
-        }
+        // only peek, so the char after the whitespace is still there for the next token
+        self.eat_while(is_whitespace);
+        Some(self.token(TokenKind::Whitespace))
     }
 
-    fn lex_to_eol(&mut self) -> String {
-        let mut lexeme = String::new();
-        while !self.is_eof() && !potential_eol(&self.peek()) {
-            lexeme.push(self.eat());
-        }
-        return lexeme;
+    fn is_shebang_start(&self) -> bool {
+        self.peek() == '#' && self.peek_n(1) == '!' && self.peek_n(2) != '['
     }
 
-    fn lex_newline(&mut self, char: &char) -> Option<Token> {
+    fn lex_shebang(&mut self) -> Token<'a> {
+        self.eat_to_eol();
+        self.token(TokenKind::Shebang)
+    }
+
+    fn eat_to_eol(&mut self) {
+        self.eat_while(|c| !is_newline(c));
+    }
+
+    fn lex_newline(&mut self, char: &char) -> Option<Token<'a>> {
         match char {
             '\r' => match self.peek() {
                 '\n' => {
                     self.eat();
-                    Some(Token::new(TokenKind::Newline, "\r\n".to_owned(), 2))
+                    Some(self.token(TokenKind::Newline))
                 },
                 _ => {
-                    Some(Token::new(TokenKind::Newline, "\r".to_owned(), 1))
+                    Some(self.token(TokenKind::Newline))
                 }
             },
-            '\n' => Some(Token::new(TokenKind::Newline, "\n".to_owned(), 1)),
+            '\n' => Some(self.token(TokenKind::Newline)),
             _ => None, 
         }
     }
 
-    fn lex_string_literal(&mut self, char: &char) -> Token {
-        let mut lexeme = String::from(*char);
+    fn lex_string_literal(&mut self) -> Token<'a> {
         let mut terminated = false;
 
-        while self.peek() != '"' && !self.is_eof() {
-            if self.peek() == '\\' && self.peek_n(1) == '"' {
-                lexeme.push(self.eat());
+        while !self.is_eof() {
+            self.eat_while(|c| c != '"' && c != '\\');
+            if self.peek() != '\\' {
+                break;
             }
-            lexeme.push(self.eat());
+            // the escaped char can never end the literal, the escape itself is checked by cook_string
+            self.eat();
+            self.eat();
         }
 
         if !self.is_eof() {
-            lexeme.push(self.eat());
+            self.eat();
             terminated = true;
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::StringLiteral{terminated}, lexeme, len)
+        self.token(TokenKind::StringLiteral{terminated})
+    }
+
+    /// the 'r' is already eaten. Any number of '#' may come between the 'r' and the '"'
+    fn is_raw_string_start(&self) -> bool {
+        let mut n = 0;
+        while self.peek_n(n) == '#' {
+            n += 1;
+        }
+        self.peek_n(n) == '"'
+    }
+
+    fn lex_raw_string_literal(&mut self) -> Token<'a> {
+        let mut hashes = 0;
+        while self.peek() == '#' {
+            self.eat();
+            hashes += 1;
+        }
+        self.eat();
+
+        let mut terminated = false;
+        while !self.is_eof() {
+            let c = self.eat();
+            if c == '"' && (0..hashes).all(|n| self.peek_n(n) == '#') {
+                for _ in 0..hashes {
+                    self.eat();
+                }
+                terminated = true;
+                break;
+            }
+        }
+
+        self.token(TokenKind::RawStringLiteral{terminated})
+    }
+
+    /// the first '"' is already eaten and at least two more follow. The literal ends at the first run of as many quotes as it started with.
+    fn lex_multiline_string_literal(&mut self) -> Token<'a> {
+        while self.peek() == '"' {
+            self.eat();
+        }
+        let quotes = self.consumed().len();
+
+        let mut terminated = false;
+        while !self.is_eof() {
+            let c = self.eat();
+            if c == '"' && (0..quotes - 1).all(|n| self.peek_n(n) == '"') {
+                for _ in 0..quotes - 1 {
+                    self.eat();
+                }
+                terminated = true;
+                break;
+            }
+        }
+
+        self.token(TokenKind::MultilineStringLiteral{terminated})
     }
 
-    fn lex_char_literal(&mut self, char: &char) -> Token {
-        let mut lexeme = String::from(*char);
+    /// the '$' is already eaten and the '"' follows
+    fn lex_interpolated_string_literal(&mut self) -> Token<'a> {
+        self.eat();
+        let mut terminated = false;
+
+        while !self.is_eof() {
+            match self.peek() {
+                '"' => {
+                    self.eat();
+                    terminated = true;
+                    break;
+                },
+                '\\' if self.peek_n(1) != EOF_CHAR => {
+                    self.eat();
+                    self.eat();
+                },
+                '{' if self.peek_n(1) == '{' => {
+                    self.eat();
+                    self.eat();
+                },
+                '{' => {
+                    self.eat();
+                    self.lex_interpolation_hole();
+                },
+                _ => {
+                    self.eat();
+                },
+            }
+        }
+
+        self.token(TokenKind::InterpolatedStringLiteral{terminated})
+    }
+
+    /// the '{' is already eaten. Eats tokens until the matching '}' or the end of the source.
+    fn lex_interpolation_hole(&mut self) {
+        // the tokens of the hole move the token start, but they are part of the literal
+        let literal_start = self.token_start();
+        let mut depth = 0;
+        while !self.is_eof() {
+            match self.advance_token().kind {
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace if depth == 0 => break,
+                TokenKind::RBrace => depth -= 1,
+                _ => {},
+            }
+        }
+        self.set_token_start(literal_start);
+    }
+
+    fn lex_char_literal(&mut self) -> Token<'a> {
         let mut terminated = false;
 
         while self.peek() != '\'' && self.peek() != '\n' && self.peek() != '\r' && !self.is_eof() {
-            if self.peek() == '\\' && self.peek_n(1) == '\'' {
-                lexeme.push(self.eat());
+            if self.peek() == '\\' && !matches!(self.peek_n(1), '\n' | '\r' | EOF_CHAR) {
+                self.eat();
             }
-            lexeme.push(self.eat());
+            self.eat();
         }
 
         if !self.is_eof() && self.peek() != '\n' && self.peek() != '\r' {
-            lexeme.push(self.eat());
+            self.eat();
             terminated = true;
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::CharLiteral{terminated}, lexeme, len)
+        self.token(TokenKind::CharLiteral{terminated})
     }
 
-    fn lex_number_literal(&mut self, char: &char) -> Token {
+    fn lex_number_literal(&mut self, char: &char) -> Token<'a> {
         match (char, self.peek()) {
-            ('0', 'b') => self.lex_binary(char),
-            ('0', 'o') => self.lex_octal(char),
-            ('0', 'x') => self.lex_hexadecimal(char),
-            (_, _) => self.lex_decimal(char),
+            ('0', 'b') => self.lex_binary(),
+            ('0', 'o') => self.lex_octal(),
+            ('0', 'x') => self.lex_hexadecimal(),
+            (_, _) => self.lex_decimal(),
         }
     }
 
-    fn lex_binary(&mut self, c: &char) -> Token {
-        let mut lexeme = String::from(*c);
-        lexeme.push(self.eat());
+    fn lex_binary(&mut self) -> Token<'a> {
+        self.eat();
         let mut has_digits = false;
 
         loop {
             match self.peek() {
-                '_' => lexeme.push(self.eat()),
+                '_' => {
+                    self.eat();
+                },
                 '0' | '1' => {
                     has_digits = true;
-                    lexeme.push(self.eat());
+                    self.eat();
                 },
                 _ => break,
             }
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::NumberLiteral{base: Base::Binary, has_digits}, lexeme, len)
+        let suffix = self.lex_number_suffix();
+
+        self.token(TokenKind::NumberLiteral{base: Base::Binary, has_digits, has_exponent: false, suffix})
     }
 
-    fn lex_octal(&mut self, c: &char) -> Token {
-        let mut lexeme = String::from(*c);       
-        lexeme.push(self.eat());
+    fn lex_octal(&mut self) -> Token<'a> {
+        self.eat();
         let mut has_digits = false;
 
         loop {
             match self.peek() {
-                '_' => lexeme.push(self.eat()),
+                '_' => {
+                    self.eat();
+                },
                 '0'..='7' => {
                     has_digits = true;
-                    lexeme.push(self.eat());
+                    self.eat();
                 },
                 _ => break,
             }
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::NumberLiteral{base: Base::Octal, has_digits}, lexeme, len)
+        let suffix = self.lex_number_suffix();
+
+        self.token(TokenKind::NumberLiteral{base: Base::Octal, has_digits, has_exponent: false, suffix})
     }
 
-    fn lex_decimal(&mut self, c: &char) -> Token {
-        let mut lexeme = String::from(*c);
+    fn lex_decimal(&mut self) -> Token<'a> {
         let mut had_dot = false;
         loop {
             match self.peek() {
-                '_' | '0'..='9' => lexeme.push(self.eat()),
+                '_' | '0'..='9' => {
+                    self.eat();
+                },
                 '.' if !had_dot => {
                     if !(matches!(self.peek_n(1), '0'..='9') || (self.peek_n(1) == '_' && matches!(self.peek_n(2), '0'..='9'))) {
                         // "4." must be folled by a number or a _ followed by a number
@@ -625,112 +895,221 @@ impl Cursor<'_> {
                         break;
                     }
                     had_dot = true;
-                    lexeme.push(self.eat());
+                    self.eat();
                 },
                 _ => break,
             }
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::NumberLiteral{base: Base::Decimal, has_digits: true}, lexeme, len)
+        let has_exponent = self.lex_exponent();
+        let suffix = self.lex_number_suffix();
+
+        self.token(TokenKind::NumberLiteral{base: Base::Decimal, has_digits: true, has_exponent, suffix})
     }
 
-    fn lex_hexadecimal(&mut self, c: &char) -> Token {
-        let mut lexeme = String::from(*c);
-        lexeme.push(self.eat());
+    /// e5, E-3, e+10. An e that is not followed by digits is not an exponent, but the start of a suffix.
+    /// e_5 is an exponent that is missing its first digit
+    fn lex_exponent(&mut self) -> bool {
+        if !matches!(self.peek(), 'e' | 'E') {
+            return false;
+        }
+
+        let has_sign = matches!(self.peek_n(1), '+' | '-');
+        let mut first_digit = if has_sign { 2 } else { 1 };
+        while self.peek_n(first_digit) == '_' {
+            first_digit += 1;
+        }
+        if !self.peek_n(first_digit).is_ascii_digit() {
+            return false;
+        }
+
+        self.eat();
+        if has_sign {
+            self.eat();
+        }
+        self.eat_while(|c| matches!(c, '_' | '0'..='9'));
+        true
+    }
+
+    /// everything that could continue an identifier is part of the suffix, so 0b102 has the invalid suffix "2"
+    fn lex_number_suffix(&mut self) -> Option<NumberSuffix> {
+        let start = self.consumed().len();
+        self.eat_while(is_identifier_continue);
+
+        let suffix = &self.consumed()[start..];
+        if suffix.is_empty() {
+            return None;
+        }
+
+        Some(bake_number_suffix(suffix))
+    }
+
+    fn lex_hexadecimal(&mut self) -> Token<'a> {
+        self.eat();
         let mut has_digits = false;
 
         loop {
             match self.peek() {
-                '_' => lexeme.push(self.eat()),
+                '_' => {
+                    self.eat();
+                },
+                'a'..='f' | 'A'..='F' if self.at_hex_suffix() => break,
                 '0'..='9' | 'a'..='f' | 'A'..='F' => {
                     has_digits = true;
-                    lexeme.push(self.eat());
+                    self.eat();
                 },
                 _ => break,
             }
         }
 
-        let len = lexeme.len();
-        Token::new(TokenKind::NumberLiteral{base: Base::Hexadecimal, has_digits}, lexeme, len)
+        let suffix = self.lex_number_suffix();
+
+        self.token(TokenKind::NumberLiteral{base: Base::Hexadecimal, has_digits, has_exponent: false, suffix})
+    }
+
+    fn at_hex_suffix(&self) -> bool {
+        // no suffix is longer than ushort, so the rest fits on the stack and lexing does not allocate
+        let mut rest = [0u8; 6];
+        let mut len = 0;
+        while is_identifier_continue(self.peek_n(len)) {
+            let c = self.peek_n(len);
+            if len == rest.len() || !c.is_ascii() {
+                return false;
+            }
+            rest[len] = c as u8;
+            len += 1;
+        }
+        std::str::from_utf8(&rest[..len]).is_ok_and(is_hex_suffix)
     }
 
-    fn lex_multiline_comment(&mut self) -> Token {
+    fn lex_multiline_comment(&mut self) -> Token<'a> {
         self.eat();
-        let mut lexeme = "/*".to_owned();
-        let mut terminated = false;
+        let mut depth = 1;
         loop {
             match (self.peek(), self.peek_n(1)) {
                 ('*', '/') => {
-                    terminated = true;
-                    lexeme.push(self.eat());
-                    lexeme.push(self.eat());
-                    break;
+                    self.eat();
+                    self.eat();
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                ('/', '*') => {
+                    self.eat();
+                    self.eat();
+                    depth += 1;
                 },
                 (_, _) if self.is_eof() => break,
-                (_, _) => lexeme.push(self.eat()),
+                (_, _) => {
+                    self.eat();
+                },
             }
         }
+        let terminated = depth == 0;
         
-        let len = lexeme.len();
-        Token::new(TokenKind::MultilineComment{terminated}, lexeme, len)
+        let comment = self.consumed();
+        let kind = if comment.starts_with("/**") && !comment.starts_with("/***") && comment != "/**/" {
+            TokenKind::MultilineDocComment{terminated}
+        }
+        else {
+            TokenKind::MultilineComment{terminated}
+        };
+
+        self.token(kind)
     }
 
-    fn lex_line_comment(&mut self) -> Token {
+    fn lex_line_comment(&mut self) -> Token<'a> {
         self.eat();
-        let mut lexeme = "//".to_owned();
-        lexeme.push_str(&self.lex_to_eol());
-        let len = lexeme.len();
-        Token::new(TokenKind::LineComment, lexeme, len)
-    }
+        self.eat_to_eol();
+        let comment = self.consumed();
+        let kind = if comment.starts_with("///") && !comment.starts_with("////") {
+            TokenKind::DocComment
+        }
+        else {
+            TokenKind::LineComment
+        };
 
-    fn lex_identifier(&mut self, char: &char) -> Token {
-        let mut lexeme = String::from(*char);
+        self.token(kind)
+    }
 
-        while is_literal(&self.peek()) {
-            lexeme.push(self.eat());
-        }
+    fn lex_identifier(&mut self) -> Token<'a> {
+        self.eat_while(is_identifier_continue);
 
         let mut kind = TokenKind::Identifier;
-        if let Some(keyword) = bake_keyword(&lexeme) {
+        if let Some(keyword) = bake_keyword(self.consumed(), self.edition()) {
             kind = keyword;
         }
 
-        let len = lexeme.len();
-        Token::new(kind, lexeme, len)
+        self.token(kind)
     }
 }
 
-fn bake_keyword(identifier: &str) -> Option<TokenKind> {
-    use TokenKind::*;
-    match identifier {
-        "let"       => Some(LetKeyword),
-        "mut"       => Some(MutKeyword),
-        "match"     => Some(MatchKeyword),
-        "for"       => Some(ForKeyword),
-        "in"        => Some(InKeyword),
-        "while"     => Some(WhileKeyword),
-        "if"        => Some(IfKeyword),
-        "else"      => Some(ElseKeyword),
-        "fn"        => Some(FunctionKeyword),
-        "return"    => Some(ReturnKeyword),
-        "break"     => Some(BreakKeyword),
-        "true"      => Some(TrueKeyword),
-        "false"     => Some(FalseKeyword),
-        _ => None,
+fn bake_number_suffix(suffix: &str) -> NumberSuffix {
+    use NumberSuffix::*;
+    match suffix {
+        "i8"  | "sbyte"     => I8,
+        "i16" | "short"     => I16,
+        "i32" | "int"       => I32,
+        "i64" | "long"      => I64,
+        "u8"  | "byte"      => U8,
+        "u16" | "ushort"    => U16,
+        "u32" | "uint"      => U32,
+        "u64" | "ulong"     => U64,
+        "f32" | "float"     => F32,
+        "f64" | "double"    => F64,
+        _ => Invalid,
     }
 }
 
-fn is_literal(char: &char) -> bool {
-    !NON_LITERAL_CHARS.contains(char) 
-    && !NEWLINE_CHARS.contains(char) 
-    && !WHITESPACE_CHARS.contains(char)
+/// A suffix like double or byte that starts with a hex digit, so 0x1_double is 0x1 with the suffix double and not 0x1d with the suffix ouble.
+/// Suffixes that are all hex digits like f32 stay digits
+fn is_hex_suffix(rest: &str) -> bool {
+    bake_number_suffix(rest) != NumberSuffix::Invalid && !rest.chars().all(|c| c.is_ascii_hexdigit())
 }
 
-fn potential_eol(char: &char) -> bool {
-    NEWLINE_CHARS.contains(char)
-}
+/// Splits a number literal into the number itself (with its 0x, 0b or 0o prefix) and the suffix.
+pub(crate) fn split_number_literal<'src>(token: &Token<'src>) -> (&'src str, &'src str) {
+    let (base, has_exponent) = match token.kind {
+        TokenKind::NumberLiteral{ base, has_exponent, .. } => (base, has_exponent),
+        _ => return (token.value, ""),
+    };
 
-fn potential_whitespace(char: &char) -> bool {
-    WHITESPACE_CHARS.contains(char)
-}
\ No newline at end of file
+    let value = token.value;
+    let mut chars = value.char_indices().peekable();
+    if base != Base::Decimal {
+        chars.next();
+        chars.next();
+    }
+
+    let is_digit = |c: char| match base {
+        Base::Binary => matches!(c, '0' | '1'),
+        Base::Octal => matches!(c, '0'..='7'),
+        Base::Hexadecimal => c.is_ascii_hexdigit(),
+        // a dot only ever ends up in the lexeme when it is followed by digits
+        Base::Decimal => c.is_ascii_digit() || c == '.',
+    };
+
+    let mut end = value.len();
+    let mut in_exponent = false;
+    while let Some((i, c)) = chars.next() {
+        if base == Base::Hexadecimal && is_hex_suffix(&value[i..]) {
+            end = i;
+            break;
+        }
+        if c == '_' || is_digit(c) {
+            continue;
+        }
+        if has_exponent && !in_exponent && matches!(c, 'e' | 'E') {
+            in_exponent = true;
+            if matches!(chars.peek(), Some((_, '+')) | Some((_, '-'))) {
+                chars.next();
+            }
+            continue;
+        }
+        end = i;
+        break;
+    }
+
+    value.split_at(end)
+}
@@ -0,0 +1,480 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::{split_number_literal, Base, NumberSuffix, Token, TokenKind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LiteralErrorKind {
+    /// \q or any other char that has no escape meaning
+    UnknownEscape,
+    /// a \ right before the end of the literal
+    LoneBackslash,
+    /// \x must be followed by exactly two hex digits
+    InvalidHexEscape,
+    /// \x can only express ascii, so \x80 and above are invalid
+    OutOfRangeHexEscape,
+    /// \u must be followed by a {
+    MissingUnicodeBrace,
+    /// \u{ without a closing }
+    UnterminatedUnicodeEscape,
+    /// \u{}
+    EmptyUnicodeEscape,
+    /// more than six hex digits in \u{...}
+    OverlongUnicodeEscape,
+    /// \u{...} above 10FFFF
+    OutOfRangeUnicodeEscape,
+    /// \u{D800} to \u{DFFF} are surrogates and not unicode scalar values
+    SurrogateUnicodeEscape,
+    /// ''
+    EmptyCharLiteral,
+    /// 'ab'. A char literal has to hold exactly one unicode scalar value
+    MoreThanOneChar,
+    /// """text on the opening line
+    ContentAfterOpeningQuotes,
+    /// text on the line of the closing quotes"""
+    ContentBeforeClosingQuotes,
+    /// a line of a multiline string that does not start with the indentation of the closing quotes
+    InsufficientIndentation,
+    /// a '}' in the text of an interpolated string has to be escaped as }}
+    UnescapedClosingBrace,
+    /// a number literal without digits or with an invalid suffix, see ferrousc_lexer::diagnose
+    MalformedNumber,
+    /// 256u8, 1e400, or an integer above u64::MAX without a suffix
+    NumberOutOfRange,
+}
+
+/// offset and len are in bytes and point into the source the token was lexed from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LiteralError {
+    pub kind: LiteralErrorKind,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// Turns a StringLiteral, RawStringLiteral, MultilineStringLiteral or InterpolatedStringText token into the string it represents.
+/// Escape sequences are decoded, and multiline strings lose their indentation.
+/// Every error is reported, not just the first one.
+pub fn cook_string(token: &Token) -> Result<String, Vec<LiteralError>> {
+    debug_assert!(matches!(token.kind, TokenKind::StringLiteral{..} | TokenKind::InterpolatedStringText | TokenKind::RawStringLiteral{..} | TokenKind::MultilineStringLiteral{..}));
+
+    let (cooked, errors) = match token.kind {
+        TokenKind::RawStringLiteral{ terminated } => (cook_raw_string(token, terminated), vec![]),
+        TokenKind::MultilineStringLiteral{ terminated } => cook_multiline_string(token, terminated),
+        _ => cook_content(token),
+    };
+    if errors.is_empty() {
+        Ok(cooked)
+    }
+    else {
+        Err(errors)
+    }
+}
+
+/// Turns a CharLiteral token into the char it represents, with escape sequences decoded.
+pub fn cook_char(token: &Token) -> Result<char, Vec<LiteralError>> {
+    debug_assert!(matches!(token.kind, TokenKind::CharLiteral{..}));
+
+    let (cooked, mut errors) = cook_content(token);
+    let mut chars = cooked.chars();
+    let c = chars.next();
+
+    if c.is_none() && errors.is_empty() {
+        errors.push(LiteralError{ kind: LiteralErrorKind::EmptyCharLiteral, offset: token.offset, len: token.len });
+    }
+    if chars.next().is_some() {
+        errors.push(LiteralError{ kind: LiteralErrorKind::MoreThanOneChar, offset: token.offset, len: token.len });
+    }
+
+    match c {
+        Some(c) if errors.is_empty() => Ok(c),
+        _ => Err(errors),
+    }
+}
+
+/// The text of a DocComment or MultilineDocComment token without the comment markers.
+/// One space after /// is stripped, and so is the leading whitespace and '*' of every line in /** */.
+pub fn cook_doc_comment(token: &Token) -> String {
+    debug_assert!(matches!(token.kind, TokenKind::DocComment | TokenKind::MultilineDocComment{..}));
+
+    match token.kind {
+        TokenKind::DocComment => {
+            let text = &token.value["///".len()..];
+            text.strip_prefix(' ').unwrap_or(text).to_owned()
+        },
+        TokenKind::MultilineDocComment{ terminated } => {
+            let end = if terminated { token.value.len() - "*/".len() } else { token.value.len() };
+            let lines: Vec<&str> = token.value["/**".len()..end]
+                .lines()
+                .map(|line| {
+                    let line = line.trim_start();
+                    let line = line.strip_prefix('*').unwrap_or(line);
+                    line.strip_prefix(' ').unwrap_or(line).trim_end()
+                })
+                .collect();
+
+            // /** and */ usually sit on their own lines
+            let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+            let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |last| last + 1);
+            lines[first..last].join("\n")
+        },
+        _ => token.value.to_owned(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum NumberValue {
+    Integer(u128),
+    /// f32 literals are rounded to f32 first and then widened, so they are exact
+    Float(f64),
+}
+
+/// the value of a number literal and its type. The type comes from the suffix or is
+/// inferred the way C# does it: the first of i32, u32, i64, u64 that fits, or f64 for floats.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Number {
+    pub value: NumberValue,
+    pub number_type: NumberSuffix,
+}
+
+/// Evaluates a NumberLiteral token. '_' separators are ignored and floats are correctly rounded.
+/// The sign is not part of the literal, so 128i8 is out of range. Use evaluate_negated_number for -128i8.
+pub fn evaluate_number(token: &Token) -> Result<Number, LiteralError> {
+    evaluate(token, false)
+}
+
+/// Evaluates a NumberLiteral token that is the operand of a unary minus.
+/// The returned value is the magnitude, but the range check and type inference account for the sign.
+pub fn evaluate_negated_number(token: &Token) -> Result<Number, LiteralError> {
+    evaluate(token, true)
+}
+
+fn evaluate(token: &Token, negated: bool) -> Result<Number, LiteralError> {
+    let error = |kind| LiteralError{ kind, offset: token.offset, len: token.len };
+    let (base, has_digits, has_exponent, suffix) = match token.kind {
+        TokenKind::NumberLiteral{ base, has_digits, has_exponent, suffix } => (base, has_digits, has_exponent, suffix),
+        _ => return Err(error(LiteralErrorKind::MalformedNumber)),
+    };
+
+    let (number, _) = split_number_literal(token);
+    let is_float = has_exponent || number.contains('.');
+    let malformed = !has_digits
+        || suffix == Some(NumberSuffix::Invalid)
+        || (is_float && matches!(suffix, Some(suffix) if !suffix.is_float()))
+        || (base != Base::Decimal && matches!(suffix, Some(suffix) if suffix.is_float()));
+    if malformed {
+        return Err(error(LiteralErrorKind::MalformedNumber));
+    }
+
+    let digits: String = match base {
+        Base::Decimal => number,
+        _ => &number[2..],
+    }.chars().filter(|c| *c != '_').collect();
+
+    if is_float || matches!(suffix, Some(suffix) if suffix.is_float()) {
+        let number_type = suffix.unwrap_or(NumberSuffix::F64);
+        let value = if number_type == NumberSuffix::F32 {
+            digits.parse::<f32>().map(f64::from)
+        }
+        else {
+            digits.parse::<f64>()
+        };
+
+        return match value {
+            Ok(value) if value.is_finite() => Ok(Number{ value: NumberValue::Float(value), number_type }),
+            _ => Err(error(LiteralErrorKind::NumberOutOfRange)),
+        };
+    }
+
+    let radix = match base {
+        Base::Binary => 2,
+        Base::Octal => 8,
+        Base::Decimal => 10,
+        Base::Hexadecimal => 16,
+    };
+    let value = u128::from_str_radix(&digits, radix).map_err(|_| error(LiteralErrorKind::NumberOutOfRange))?;
+
+    let number_type = match suffix {
+        Some(suffix) => suffix,
+        None => {
+            let candidates: &[NumberSuffix] = if negated {
+                &[NumberSuffix::I32, NumberSuffix::I64]
+            }
+            else {
+                &[NumberSuffix::I32, NumberSuffix::U32, NumberSuffix::I64, NumberSuffix::U64]
+            };
+            match candidates.iter().find(|candidate| value <= integer_max(**candidate, negated)) {
+                Some(candidate) => *candidate,
+                None => return Err(error(LiteralErrorKind::NumberOutOfRange)),
+            }
+        },
+    };
+
+    if value > integer_max(number_type, negated) {
+        return Err(error(LiteralErrorKind::NumberOutOfRange));
+    }
+    Ok(Number{ value: NumberValue::Integer(value), number_type })
+}
+
+/// the biggest magnitude an integer type can hold. Negated unsigned integers can only be 0.
+fn integer_max(number_type: NumberSuffix, negated: bool) -> u128 {
+    let (bits, signed) = match number_type {
+        NumberSuffix::I8 => (8, true),
+        NumberSuffix::I16 => (16, true),
+        NumberSuffix::I32 => (32, true),
+        NumberSuffix::I64 => (64, true),
+        NumberSuffix::U8 => (8, false),
+        NumberSuffix::U16 => (16, false),
+        NumberSuffix::U32 => (32, false),
+        NumberSuffix::U64 => (64, false),
+        _ => return 0,
+    };
+
+    match (signed, negated) {
+        (true, true) => 1 << (bits - 1),
+        (true, false) => (1 << (bits - 1)) - 1,
+        (false, true) => 0,
+        (false, false) => (1 << bits) - 1,
+    }
+}
+
+fn cook_raw_string(token: &Token, terminated: bool) -> String {
+    let hashes = token.value[1..].chars().take_while(|c| *c == '#').count();
+    let start = 1 + hashes + 1;
+    let end = if terminated { token.value.len() - 1 - hashes } else { token.value.len() };
+    token.value[start..end].to_owned()
+}
+
+/// The first and the last line only hold the line breaks around the text. The whitespace in front
+/// of the closing quotes is the indentation that is stripped from every other line.
+fn cook_multiline_string(token: &Token, terminated: bool) -> (String, Vec<LiteralError>) {
+    let quotes = token.value.chars().take_while(|c| *c == '"').count();
+    let end = if terminated { token.value.len() - quotes } else { token.value.len() };
+    let content = &token.value[quotes..end];
+    let content_offset = token.offset + quotes;
+
+    let lines = split_lines(content);
+    if lines.len() == 1 || !terminated {
+        return (content.to_owned(), vec![]);
+    }
+
+    let mut errors = vec![];
+    let error = |kind, start: usize, line: &str| LiteralError{ kind, offset: content_offset + start, len: line.len() };
+    let is_blank = |line: &str| line.chars().all(|c| c.is_whitespace());
+
+    let (first_start, first, _) = lines[0];
+    if !is_blank(first) {
+        errors.push(error(LiteralErrorKind::ContentAfterOpeningQuotes, first_start, first));
+    }
+
+    let (last_start, last, _) = lines[lines.len() - 1];
+    let indentation = if is_blank(last) {
+        last
+    }
+    else {
+        errors.push(error(LiteralErrorKind::ContentBeforeClosingQuotes, last_start, last));
+        ""
+    };
+
+    let mut cooked = String::with_capacity(content.len());
+    let middle = &lines[1..lines.len() - 1];
+    for (i, (start, line, line_break)) in middle.iter().enumerate() {
+        if let Some(unindented) = line.strip_prefix(indentation) {
+            cooked.push_str(unindented);
+        }
+        else if is_blank(line) {
+            // blank lines do not need to be indented
+        }
+        else {
+            errors.push(error(LiteralErrorKind::InsufficientIndentation, *start, line));
+            cooked.push_str(line);
+        }
+
+        // the line break before the closing quotes is not part of the string
+        if i + 1 < middle.len() {
+            cooked.push_str(line_break);
+        }
+    }
+
+    (cooked, errors)
+}
+
+/// (start, line, line break) for every line. \r\n, \r, and \n all end a line.
+fn split_lines(text: &str) -> Vec<(usize, &str, &str)> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let line_break_len = match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => 2,
+            b'\r' | b'\n' => 1,
+            _ => {
+                i += 1;
+                continue;
+            },
+        };
+        lines.push((start, &text[start..i], &text[i..i + line_break_len]));
+        i += line_break_len;
+        start = i;
+    }
+    lines.push((start, &text[start..], ""));
+
+    lines
+}
+
+/// strips the quotes and decodes the escape sequences in between.
+/// The text of interpolated strings has no quotes, but {{ and }} are escaped braces.
+fn cook_content(token: &Token) -> (String, Vec<LiteralError>) {
+    let is_interpolated_text = token.kind == TokenKind::InterpolatedStringText;
+    let terminated = matches!(token.kind, TokenKind::StringLiteral{ terminated: true } | TokenKind::CharLiteral{ terminated: true });
+    let start = if is_interpolated_text { 0 } else { 1 };
+    let end = if terminated { token.value.len() - 1 } else { token.value.len() };
+    let content = &token.value[start..end];
+    let content_offset = token.offset + start;
+
+    let mut unescaper = Unescaper{ content, chars: content.char_indices().peekable() };
+    let mut cooked = String::with_capacity(content.len());
+    let mut errors = vec![];
+
+    while let Some((start, c)) = unescaper.chars.next() {
+        match c {
+            '\\' => match unescaper.unescape() {
+                Ok(c) => cooked.push(c),
+                Err(kind) => errors.push(LiteralError{ kind, offset: content_offset + start, len: unescaper.pos() - start }),
+            },
+            '{' | '}' if is_interpolated_text => {
+                // a single '{' starts a hole, so only a single '}' can end up here
+                if unescaper.peek() == Some(c) {
+                    unescaper.eat();
+                }
+                else {
+                    errors.push(LiteralError{ kind: LiteralErrorKind::UnescapedClosingBrace, offset: content_offset + start, len: 1 });
+                }
+                cooked.push(c);
+            },
+            c => cooked.push(c),
+        }
+    }
+
+    (cooked, errors)
+}
+
+struct Unescaper<'a> {
+    content: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Unescaper<'_> {
+    /// byte position of the next char in the content
+    fn pos(&mut self) -> usize {
+        let len = self.content.len();
+        self.chars.peek().map_or(len, |(i, _)| *i)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn eat(&mut self) -> Option<char> {
+        self.chars.next().map(|(_, c)| c)
+    }
+
+    /// the \ is already eaten
+    fn unescape(&mut self) -> Result<char, LiteralErrorKind> {
+        match self.eat() {
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('0') => Ok('\0'),
+            Some('\\') => Ok('\\'),
+            Some('"') => Ok('"'),
+            Some('\'') => Ok('\''),
+            Some('x') => self.unescape_hex(),
+            Some('u') => self.unescape_unicode(),
+            Some(_) => Err(LiteralErrorKind::UnknownEscape),
+            None => Err(LiteralErrorKind::LoneBackslash),
+        }
+    }
+
+    fn unescape_hex(&mut self) -> Result<char, LiteralErrorKind> {
+        let mut value = 0;
+        for _ in 0..2 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    self.eat();
+                    value = value * 16 + digit;
+                },
+                None => return Err(LiteralErrorKind::InvalidHexEscape),
+            }
+        }
+
+        if value > 0x7F {
+            return Err(LiteralErrorKind::OutOfRangeHexEscape);
+        }
+        Ok(value as u8 as char)
+    }
+
+    fn unescape_unicode(&mut self) -> Result<char, LiteralErrorKind> {
+        if self.peek() != Some('{') {
+            return Err(LiteralErrorKind::MissingUnicodeBrace);
+        }
+        self.eat();
+
+        let mut value: u32 = 0;
+        let mut digits = 0;
+        loop {
+            match self.peek() {
+                Some('}') => {
+                    self.eat();
+                    break;
+                },
+                Some('_') => {
+                    self.eat();
+                },
+                Some(c) if c.is_ascii_hexdigit() => {
+                    self.eat();
+                    digits += 1;
+                    if digits <= 6 {
+                        value = value * 16 + c.to_digit(16).unwrap();
+                    }
+                },
+                // the rest of the literal is not part of the escape
+                _ => return Err(LiteralErrorKind::UnterminatedUnicodeEscape),
+            }
+        }
+
+        match (digits, value) {
+            (0, _) => Err(LiteralErrorKind::EmptyUnicodeEscape),
+            (7..=usize::MAX, _) => Err(LiteralErrorKind::OverlongUnicodeEscape),
+            (_, 0xD800..=0xDFFF) => Err(LiteralErrorKind::SurrogateUnicodeEscape),
+            (_, value) => std::char::from_u32(value).ok_or(LiteralErrorKind::OutOfRangeUnicodeEscape),
+        }
+    }
+}
+
+impl fmt::Display for LiteralErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralErrorKind::UnknownEscape => write!(f, "unknown escape sequence"),
+            LiteralErrorKind::LoneBackslash => write!(f, "'\\' at the end of a literal has to be escaped"),
+            LiteralErrorKind::InvalidHexEscape => write!(f, "'\\x' must be followed by two hex digits"),
+            LiteralErrorKind::OutOfRangeHexEscape => write!(f, "'\\x' escapes must be in the range [\\x00-\\x7F]"),
+            LiteralErrorKind::MissingUnicodeBrace => write!(f, "'\\u' must be followed by '{{'"),
+            LiteralErrorKind::UnterminatedUnicodeEscape => write!(f, "unterminated unicode escape, missing '}}'"),
+            LiteralErrorKind::EmptyUnicodeEscape => write!(f, "empty unicode escape"),
+            LiteralErrorKind::OverlongUnicodeEscape => write!(f, "unicode escapes have at most six hex digits"),
+            LiteralErrorKind::OutOfRangeUnicodeEscape => write!(f, "unicode escapes must be at most 10FFFF"),
+            LiteralErrorKind::SurrogateUnicodeEscape => write!(f, "unicode escapes must not be a surrogate"),
+            LiteralErrorKind::EmptyCharLiteral => write!(f, "empty char literal"),
+            LiteralErrorKind::MoreThanOneChar => write!(f, "char literal with more than one char"),
+            LiteralErrorKind::ContentAfterOpeningQuotes => write!(f, "the text of a multiline string has to start on the line after the opening quotes"),
+            LiteralErrorKind::ContentBeforeClosingQuotes => write!(f, "the closing quotes of a multiline string have to be on their own line"),
+            LiteralErrorKind::InsufficientIndentation => write!(f, "every line of a multiline string has to start with the indentation of the closing quotes"),
+            LiteralErrorKind::UnescapedClosingBrace => write!(f, "'}}' in an interpolated string has to be escaped as '}}}}'"),
+            LiteralErrorKind::MalformedNumber => write!(f, "malformed number literal"),
+            LiteralErrorKind::NumberOutOfRange => write!(f, "number literal out of range for its type"),
+        }
+    }
+}
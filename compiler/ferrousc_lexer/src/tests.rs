@@ -26,65 +26,65 @@ fn maybe_main(i: int) -> bool {
 }
 ",
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "// This is some test code", len: 25 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/*\n    It contains stuff\n*/", len: 27 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: FunctionKeyword, value: "fn", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "maybe_main", len: 10 }
-            Token { kind: LParen, value: "(", len: 1 }
-            Token { kind: Identifier, value: "i", len: 1 }
-            Token { kind: Colon, value: ":", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "int", len: 3 }
-            Token { kind: RParen, value: ")", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: MinusGreater, value: "->", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "bool", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: LBrace, value: "{", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "    ", len: 4 }
-            Token { kind: WhileKeyword, value: "while", len: 5 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: TrueKeyword, value: "true", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "        ", len: 8 }
-            Token { kind: IfKeyword, value: "if", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "is_nice", len: 7 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: LBrace, value: "{", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "            ", len: 12 }
-            Token { kind: ReturnKeyword, value: "return", len: 6 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: TrueKeyword, value: "true", len: 4 }
-            Token { kind: Semicolon, value: ";", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "        ", len: 8 }
-            Token { kind: RBrace, value: "}", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "        ", len: 8 }
-            Token { kind: ElseKeyword, value: "else", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: LBrace, value: "{", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "            ", len: 12 }
-            Token { kind: ReturnKeyword, value: "return", len: 6 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: FalseKeyword, value: "false", len: 5 }
-            Token { kind: Semicolon, value: ";", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Whitespace, value: "        ", len: 8 }
-            Token { kind: RBrace, value: "}", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: RBrace, value: "}", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: LineComment, value: "// This is some test code", len: 25, offset: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 26 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*\n    It contains stuff\n*/", len: 27, offset: 27 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 54 }
+            Token { kind: FunctionKeyword, value: "fn", len: 2, offset: 55 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 57 }
+            Token { kind: Identifier, value: "maybe_main", len: 10, offset: 58 }
+            Token { kind: LParen, value: "(", len: 1, offset: 68 }
+            Token { kind: Identifier, value: "i", len: 1, offset: 69 }
+            Token { kind: Colon, value: ":", len: 1, offset: 70 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 71 }
+            Token { kind: Identifier, value: "int", len: 3, offset: 72 }
+            Token { kind: RParen, value: ")", len: 1, offset: 75 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 76 }
+            Token { kind: MinusGreater, value: "->", len: 2, offset: 77 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 79 }
+            Token { kind: Identifier, value: "bool", len: 4, offset: 80 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 84 }
+            Token { kind: LBrace, value: "{", len: 1, offset: 85 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 86 }
+            Token { kind: Whitespace, value: "    ", len: 4, offset: 87 }
+            Token { kind: WhileKeyword, value: "while", len: 5, offset: 91 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 96 }
+            Token { kind: TrueKeyword, value: "true", len: 4, offset: 97 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 101 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 102 }
+            Token { kind: Whitespace, value: "        ", len: 8, offset: 103 }
+            Token { kind: IfKeyword, value: "if", len: 2, offset: 111 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 113 }
+            Token { kind: Identifier, value: "is_nice", len: 7, offset: 114 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 121 }
+            Token { kind: LBrace, value: "{", len: 1, offset: 122 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 123 }
+            Token { kind: Whitespace, value: "            ", len: 12, offset: 124 }
+            Token { kind: ReturnKeyword, value: "return", len: 6, offset: 136 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 142 }
+            Token { kind: TrueKeyword, value: "true", len: 4, offset: 143 }
+            Token { kind: Semicolon, value: ";", len: 1, offset: 147 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 148 }
+            Token { kind: Whitespace, value: "        ", len: 8, offset: 149 }
+            Token { kind: RBrace, value: "}", len: 1, offset: 157 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 158 }
+            Token { kind: Whitespace, value: "        ", len: 8, offset: 159 }
+            Token { kind: ElseKeyword, value: "else", len: 4, offset: 167 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 171 }
+            Token { kind: LBrace, value: "{", len: 1, offset: 172 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 173 }
+            Token { kind: Whitespace, value: "            ", len: 12, offset: 174 }
+            Token { kind: ReturnKeyword, value: "return", len: 6, offset: 186 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 192 }
+            Token { kind: FalseKeyword, value: "false", len: 5, offset: 193 }
+            Token { kind: Semicolon, value: ";", len: 1, offset: 198 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 199 }
+            Token { kind: Whitespace, value: "        ", len: 8, offset: 200 }
+            Token { kind: RBrace, value: "}", len: 1, offset: 208 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 209 }
+            Token { kind: RBrace, value: "}", len: 1, offset: 210 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 211 }
         "#]],
     )
 }
@@ -94,19 +94,19 @@ fn operators() {
     check_lexing(
         "()[]+++---!%&|<",
         expect![[r#"
-            Token { kind: LParen, value: "(", len: 1 }
-            Token { kind: RParen, value: ")", len: 1 }
-            Token { kind: LBracket, value: "[", len: 1 }
-            Token { kind: RBracket, value: "]", len: 1 }
-            Token { kind: PlusPlus, value: "++", len: 2 }
-            Token { kind: Plus, value: "+", len: 1 }
-            Token { kind: MinusMinus, value: "--", len: 2 }
-            Token { kind: Minus, value: "-", len: 1 }
-            Token { kind: Bang, value: "!", len: 1 }
-            Token { kind: Percent, value: "%", len: 1 }
-            Token { kind: Amp, value: "&", len: 1 }
-            Token { kind: Bar, value: "|", len: 1 }
-            Token { kind: Less, value: "<", len: 1 }
+            Token { kind: LParen, value: "(", len: 1, offset: 0 }
+            Token { kind: RParen, value: ")", len: 1, offset: 1 }
+            Token { kind: LBracket, value: "[", len: 1, offset: 2 }
+            Token { kind: RBracket, value: "]", len: 1, offset: 3 }
+            Token { kind: PlusPlus, value: "++", len: 2, offset: 4 }
+            Token { kind: Plus, value: "+", len: 1, offset: 6 }
+            Token { kind: MinusMinus, value: "--", len: 2, offset: 7 }
+            Token { kind: Minus, value: "-", len: 1, offset: 9 }
+            Token { kind: Bang, value: "!", len: 1, offset: 10 }
+            Token { kind: Percent, value: "%", len: 1, offset: 11 }
+            Token { kind: Amp, value: "&", len: 1, offset: 12 }
+            Token { kind: Bar, value: "|", len: 1, offset: 13 }
+            Token { kind: Less, value: "<", len: 1, offset: 14 }
         "#]],
     )
 }
@@ -118,46 +118,43 @@ fn numbers() {
 123 5. .5 1232.25 0b10_14 0o17_29 0xaf_Fah 0x 0x_ 0b 0b_ 0o 0o_ 0b_1 0b1_ 0..5
 "#,
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "123", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "5", len: 1 }
-            Token { kind: Dot, value: ".", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Dot, value: ".", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "5", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "1232.25", len: 7 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Binary, has_digits: true }, value: "0b10_1", len: 6 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "4", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Octal, has_digits: true }, value: "0o17_2", len: 6 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "9", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: true }, value: "0xaf_Fa", len: 7 }
-            Token { kind: Identifier, value: "h", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: false }, value: "0x", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: false }, value: "0x_", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Binary, has_digits: false }, value: "0b", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Binary, has_digits: false }, value: "0b_", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Octal, has_digits: false }, value: "0o", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Octal, has_digits: false }, value: "0o_", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Binary, has_digits: true }, value: "0b_1", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Binary, has_digits: true }, value: "0b1_", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "0", len: 1 }
-            Token { kind: DotDot, value: "..", len: 2 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "5", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "123", len: 3, offset: 1 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 4 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "5", len: 1, offset: 5 }
+            Token { kind: Dot, value: ".", len: 1, offset: 6 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 7 }
+            Token { kind: Dot, value: ".", len: 1, offset: 8 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "5", len: 1, offset: 9 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 10 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "1232.25", len: 7, offset: 11 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 18 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: true, has_exponent: false, suffix: Some(Invalid) }, value: "0b10_14", len: 7, offset: 19 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 26 }
+            Token { kind: NumberLiteral { base: Octal, has_digits: true, has_exponent: false, suffix: Some(Invalid) }, value: "0o17_29", len: 7, offset: 27 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 34 }
+            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: true, has_exponent: false, suffix: Some(Invalid) }, value: "0xaf_Fah", len: 8, offset: 35 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 43 }
+            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: false, has_exponent: false, suffix: None }, value: "0x", len: 2, offset: 44 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 46 }
+            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: false, has_exponent: false, suffix: None }, value: "0x_", len: 3, offset: 47 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 50 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: false, has_exponent: false, suffix: None }, value: "0b", len: 2, offset: 51 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 53 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: false, has_exponent: false, suffix: None }, value: "0b_", len: 3, offset: 54 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 57 }
+            Token { kind: NumberLiteral { base: Octal, has_digits: false, has_exponent: false, suffix: None }, value: "0o", len: 2, offset: 58 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 60 }
+            Token { kind: NumberLiteral { base: Octal, has_digits: false, has_exponent: false, suffix: None }, value: "0o_", len: 3, offset: 61 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 64 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: true, has_exponent: false, suffix: None }, value: "0b_1", len: 4, offset: 65 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 69 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: true, has_exponent: false, suffix: None }, value: "0b1_", len: 4, offset: 70 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 74 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "0", len: 1, offset: 75 }
+            Token { kind: DotDot, value: "..", len: 2, offset: 76 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "5", len: 1, offset: 78 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 79 }
         "#]],
     )
 }
@@ -170,17 +167,17 @@ fn strings() {
 ends here" "testäù 幸験test"
 "#,
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: StringLiteral { terminated: true }, value: "\"string\"", len: 8 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: StringLiteral { terminated: true }, value: "\"string with spaces\"", len: 20 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: StringLiteral { terminated: true }, value: "\"string with a '\\\"'\"", len: 20 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: StringLiteral { terminated: true }, value: "\"string with a newline\nends here\"", len: 33 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: StringLiteral { terminated: true }, value: "\"testäù 幸験test\"", len: 21 }
-            Token { kind: Newline, value: "\n", len: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: StringLiteral { terminated: true }, value: "\"string\"", len: 8, offset: 1 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 9 }
+            Token { kind: StringLiteral { terminated: true }, value: "\"string with spaces\"", len: 20, offset: 10 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 30 }
+            Token { kind: StringLiteral { terminated: true }, value: "\"string with a '\\\"'\"", len: 20, offset: 31 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 51 }
+            Token { kind: StringLiteral { terminated: true }, value: "\"string with a newline\nends here\"", len: 33, offset: 52 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 85 }
+            Token { kind: StringLiteral { terminated: true }, value: "\"testäù 幸験test\"", len: 21, offset: 86 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 107 }
         "#]],
     )
 }
@@ -192,21 +189,21 @@ fn chars() {
 's' '\n' '\x' 'too long' '\'' '"' '幸'
 "#,
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'s'", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'\\n'", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'\\x'", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'too long'", len: 10 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'\\''", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'\"'", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: CharLiteral { terminated: true }, value: "'幸'", len: 5 }
-            Token { kind: Newline, value: "\n", len: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: CharLiteral { terminated: true }, value: "'s'", len: 3, offset: 1 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 4 }
+            Token { kind: CharLiteral { terminated: true }, value: "'\\n'", len: 4, offset: 5 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 9 }
+            Token { kind: CharLiteral { terminated: true }, value: "'\\x'", len: 4, offset: 10 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 14 }
+            Token { kind: CharLiteral { terminated: true }, value: "'too long'", len: 10, offset: 15 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 25 }
+            Token { kind: CharLiteral { terminated: true }, value: "'\\''", len: 4, offset: 26 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 30 }
+            Token { kind: CharLiteral { terminated: true }, value: "'\"'", len: 3, offset: 31 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 34 }
+            Token { kind: CharLiteral { terminated: true }, value: "'幸'", len: 5, offset: 35 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 40 }
         "#]],
     )
 }
@@ -218,33 +215,33 @@ fn identifier() {
 _test test 1 _1 __1 a-ha a_ha äòtest testäù 幸験test TEST TestTest
 ",
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: Identifier, value: "_test", len: 5 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "test", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: NumberLiteral { base: Decimal, has_digits: true }, value: "1", len: 1 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "_1", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "__1", len: 3 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "a", len: 1 }
-            Token { kind: Minus, value: "-", len: 1 }
-            Token { kind: Identifier, value: "ha", len: 2 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "a_ha", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "äòtest", len: 8 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "testäù", len: 8 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "幸験test", len: 10 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "TEST", len: 4 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Identifier, value: "TestTest", len: 8 }
-            Token { kind: Newline, value: "\n", len: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: Identifier, value: "_test", len: 5, offset: 1 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 6 }
+            Token { kind: Identifier, value: "test", len: 4, offset: 7 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 11 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "1", len: 1, offset: 12 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 13 }
+            Token { kind: Identifier, value: "_1", len: 2, offset: 14 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 16 }
+            Token { kind: Identifier, value: "__1", len: 3, offset: 17 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 20 }
+            Token { kind: Identifier, value: "a", len: 1, offset: 21 }
+            Token { kind: Minus, value: "-", len: 1, offset: 22 }
+            Token { kind: Identifier, value: "ha", len: 2, offset: 23 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 25 }
+            Token { kind: Identifier, value: "a_ha", len: 4, offset: 26 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 30 }
+            Token { kind: Identifier, value: "äòtest", len: 8, offset: 31 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 39 }
+            Token { kind: Identifier, value: "testäù", len: 8, offset: 40 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 48 }
+            Token { kind: Identifier, value: "幸験test", len: 10, offset: 49 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 59 }
+            Token { kind: Identifier, value: "TEST", len: 4, offset: 60 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 64 }
+            Token { kind: Identifier, value: "TestTest", len: 8, offset: 65 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 73 }
         "#]],
     )
 }
@@ -275,45 +272,880 @@ fn comments() {
 /* not terminated! // is a line comment
 ",
         expect![[r#"
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "// line", len: 7 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "/// line too", len: 12 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "//// line also", len: 14 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "//! still a line", len: 16 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "//* also a line", len: 15 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "//* not affected by */", len: 22 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "// also not affected by */", len: 26 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/* block */", len: 11 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/** also block */", len: 17 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/*** also also block */", len: 23 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/*** also also also block ***/", len: 30 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/*! also also also block */", len: 27 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/**/", len: 4 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/***/", len: 5 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/* /* can also be nested */", len: 27 }
-            Token { kind: Whitespace, value: " ", len: 1 }
-            Token { kind: Star, value: "*", len: 1 }
-            Token { kind: Slash, value: "/", len: 1 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: true }, value: "/* \n    block multiline\n*/", len: 26 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: LineComment, value: "// unicode 幸", len: 14 }
-            Token { kind: Newline, value: "\n", len: 1 }
-            Token { kind: MultilineComment { terminated: false }, value: "/* not terminated! // is a line comment\n", len: 40 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 0 }
+            Token { kind: LineComment, value: "// line", len: 7, offset: 1 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 8 }
+            Token { kind: DocComment, value: "/// line too", len: 12, offset: 9 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 21 }
+            Token { kind: LineComment, value: "//// line also", len: 14, offset: 22 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 36 }
+            Token { kind: LineComment, value: "//! still a line", len: 16, offset: 37 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 53 }
+            Token { kind: LineComment, value: "//* also a line", len: 15, offset: 54 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 69 }
+            Token { kind: LineComment, value: "//* not affected by */", len: 22, offset: 70 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 92 }
+            Token { kind: LineComment, value: "// also not affected by */", len: 26, offset: 93 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 119 }
+            Token { kind: MultilineComment { terminated: true }, value: "/* block */", len: 11, offset: 120 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 131 }
+            Token { kind: MultilineDocComment { terminated: true }, value: "/** also block */", len: 17, offset: 132 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 149 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*** also also block */", len: 23, offset: 150 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 173 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*** also also also block ***/", len: 30, offset: 174 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 204 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*! also also also block */", len: 27, offset: 205 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 232 }
+            Token { kind: MultilineComment { terminated: true }, value: "/**/", len: 4, offset: 233 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 237 }
+            Token { kind: MultilineComment { terminated: true }, value: "/***/", len: 5, offset: 238 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 243 }
+            Token { kind: MultilineComment { terminated: true }, value: "/* /* can also be nested */ */", len: 30, offset: 244 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 274 }
+            Token { kind: MultilineComment { terminated: true }, value: "/* \n    block multiline\n*/", len: 26, offset: 275 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 301 }
+            Token { kind: LineComment, value: "// unicode 幸", len: 14, offset: 302 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 316 }
+            Token { kind: MultilineComment { terminated: false }, value: "/* not terminated! // is a line comment\n", len: 40, offset: 317 }
         "#]],
     )
-}
\ No newline at end of file
+}
+
+#[test]
+fn line_columns() {
+    let src = "let a\r\n  b\rc\n\"multi\nline\" d\n";
+    let index = LineIndex::new(src);
+    let actual: String = tokenize(src)
+        .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Newline))
+        .map(|token| format!("{} {:?}\n", index.line_col(token.offset), token.value))
+        .collect();
+
+    expect![[r#"
+        1:1 "let"
+        1:5 "a"
+        2:3 "b"
+        3:1 "c"
+        4:1 "\"multi\nline\""
+        5:7 "d"
+    "#]].assert_eq(&actual);
+}
+
+fn check_cooking(src: &str, expect: Expect) {
+    let actual: String = tokenize(src)
+        .filter_map(|token| match token.kind {
+            TokenKind::StringLiteral{..}
+            | TokenKind::RawStringLiteral{..}
+            | TokenKind::MultilineStringLiteral{..} => Some(format!("{} => {:?}\n", token.value, cook_string(&token))),
+            TokenKind::CharLiteral{..} => Some(format!("{} => {:?}\n", token.value, cook_char(&token))),
+            _ => None,
+        })
+        .collect();
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn string_escapes() {
+    check_cooking(
+        r#"
+"plain" "\n\r\t\0" "\\" "a\\" "\"quoted\"" "\'" "\x41\x7f" "\u{1F600}" "\u{1_F6_00}" "unicode 幸"
+"\q" "\x4" "\xG1" "\x80" "\u41" "\u{41" "\u{}" "\u{1234567}" "\u{110000}" "\u{D800}" "\q\x"
+"#,
+        expect![[r#"
+            "plain" => Ok("plain")
+            "\n\r\t\0" => Ok("\n\r\t\0")
+            "\\" => Ok("\\")
+            "a\\" => Ok("a\\")
+            "\"quoted\"" => Ok("\"quoted\"")
+            "\'" => Ok("'")
+            "\x41\x7f" => Ok("A\u{7f}")
+            "\u{1F600}" => Ok("😀")
+            "\u{1_F6_00}" => Ok("😀")
+            "unicode 幸" => Ok("unicode 幸")
+            "\q" => Err([LiteralError { kind: UnknownEscape, offset: 101, len: 2 }])
+            "\x4" => Err([LiteralError { kind: InvalidHexEscape, offset: 106, len: 3 }])
+            "\xG1" => Err([LiteralError { kind: InvalidHexEscape, offset: 112, len: 2 }])
+            "\x80" => Err([LiteralError { kind: OutOfRangeHexEscape, offset: 119, len: 4 }])
+            "\u41" => Err([LiteralError { kind: MissingUnicodeBrace, offset: 126, len: 2 }])
+            "\u{41" => Err([LiteralError { kind: UnterminatedUnicodeEscape, offset: 133, len: 5 }])
+            "\u{}" => Err([LiteralError { kind: EmptyUnicodeEscape, offset: 141, len: 4 }])
+            "\u{1234567}" => Err([LiteralError { kind: OverlongUnicodeEscape, offset: 148, len: 11 }])
+            "\u{110000}" => Err([LiteralError { kind: OutOfRangeUnicodeEscape, offset: 162, len: 10 }])
+            "\u{D800}" => Err([LiteralError { kind: SurrogateUnicodeEscape, offset: 175, len: 8 }])
+            "\q\x" => Err([LiteralError { kind: UnknownEscape, offset: 186, len: 2 }, LiteralError { kind: InvalidHexEscape, offset: 188, len: 2 }])
+        "#]],
+    )
+}
+
+#[test]
+fn char_escapes() {
+    check_cooking(
+        r#"
+'a' '幸' '\n' '\\' '\'' '"' '\u{1F600}' '' 'ab' '\q' '\u{41' 'ab\q'
+"#,
+        expect![[r#"
+            'a' => Ok('a')
+            '幸' => Ok('幸')
+            '\n' => Ok('\n')
+            '\\' => Ok('\\')
+            '\'' => Ok('\'')
+            '"' => Ok('"')
+            '\u{1F600}' => Ok('😀')
+            '' => Err([LiteralError { kind: EmptyCharLiteral, offset: 42, len: 2 }])
+            'ab' => Err([LiteralError { kind: MoreThanOneChar, offset: 45, len: 4 }])
+            '\q' => Err([LiteralError { kind: UnknownEscape, offset: 51, len: 2 }])
+            '\u{41' => Err([LiteralError { kind: UnterminatedUnicodeEscape, offset: 56, len: 5 }])
+            'ab\q' => Err([LiteralError { kind: UnknownEscape, offset: 66, len: 2 }, LiteralError { kind: MoreThanOneChar, offset: 63, len: 6 }])
+        "#]],
+    )
+}
+
+#[test]
+fn unknown() {
+    check_lexing(
+        "a#b $ @\u{7}\\`ident_at_eof",
+        expect![[r##"
+            Token { kind: Identifier, value: "a", len: 1, offset: 0 }
+            Token { kind: Hash, value: "#", len: 1, offset: 1 }
+            Token { kind: Identifier, value: "b", len: 1, offset: 2 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 3 }
+            Token { kind: Unknown, value: "$", len: 1, offset: 4 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 5 }
+            Token { kind: Unknown, value: "@", len: 1, offset: 6 }
+            Token { kind: Unknown, value: "\u{7}", len: 1, offset: 7 }
+            Token { kind: Unknown, value: "\\", len: 1, offset: 8 }
+            Token { kind: Unknown, value: "`", len: 1, offset: 9 }
+            Token { kind: Identifier, value: "ident_at_eof", len: 12, offset: 10 }
+        "##]],
+    )
+}
+
+fn check_diagnostics(src: &str, expect: Expect) {
+    let actual: String = lex(src).diagnostics
+        .iter()
+        .map(|diagnostic| format!("{:?} {:?}: {}\n", diagnostic, &src[diagnostic.offset..diagnostic.offset + diagnostic.len], diagnostic.kind))
+        .collect();
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn diagnostics() {
+    check_diagnostics(
+        r#"
+@ 0x 0b_ 0o "\q" 'ab' '' 'x
+"unterminated \u{41"#,
+        expect![[r#"
+            LexerDiagnostic { kind: UnknownChar, offset: 1, len: 1 } "@": unknown character
+            LexerDiagnostic { kind: MissingDigits, offset: 3, len: 2 } "0x": number literal is missing digits
+            LexerDiagnostic { kind: MissingDigits, offset: 6, len: 3 } "0b_": number literal is missing digits
+            LexerDiagnostic { kind: MissingDigits, offset: 10, len: 2 } "0o": number literal is missing digits
+            LexerDiagnostic { kind: InvalidLiteral(UnknownEscape), offset: 14, len: 2 } "\\q": unknown escape sequence
+            LexerDiagnostic { kind: InvalidLiteral(MoreThanOneChar), offset: 18, len: 4 } "'ab'": char literal with more than one char
+            LexerDiagnostic { kind: InvalidLiteral(EmptyCharLiteral), offset: 23, len: 2 } "''": empty char literal
+            LexerDiagnostic { kind: UnterminatedCharLiteral, offset: 26, len: 2 } "'x": unterminated char literal, missing '''
+            LexerDiagnostic { kind: UnterminatedStringLiteral, offset: 29, len: 19 } "\"unterminated \\u{41": unterminated string literal, missing '"'
+            LexerDiagnostic { kind: InvalidLiteral(UnterminatedUnicodeEscape), offset: 43, len: 5 } "\\u{41": unterminated unicode escape, missing '}'
+        "#]],
+    );
+    check_diagnostics(
+        "/* unterminated",
+        expect![[r#"
+            LexerDiagnostic { kind: UnterminatedMultilineComment, offset: 0, len: 2 } "/*": unterminated multiline comment, missing '*/'
+        "#]],
+    )
+}
+
+#[test]
+fn number_exponents_and_suffixes() {
+    check_lexing(
+        "1e10 2E10 1.5e-3 1.5E+3 1e_5 1e 5_i32 5i32 5int 5_ulong 2.5f32 1e3double 0xFFu8 0b1_u64 5else 0..5",
+        expect![[r#"
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "1e10", len: 4, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 4 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "2E10", len: 4, offset: 5 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 9 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "1.5e-3", len: 6, offset: 10 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 16 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "1.5E+3", len: 6, offset: 17 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 23 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "1e_5", len: 4, offset: 24 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 28 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(Invalid) }, value: "1e", len: 2, offset: 29 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 31 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(I32) }, value: "5_i32", len: 5, offset: 32 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 37 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(I32) }, value: "5i32", len: 4, offset: 38 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 42 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(I32) }, value: "5int", len: 4, offset: 43 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 47 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(U64) }, value: "5_ulong", len: 7, offset: 48 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 55 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(F32) }, value: "2.5f32", len: 6, offset: 56 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 62 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: Some(F64) }, value: "1e3double", len: 9, offset: 63 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 72 }
+            Token { kind: NumberLiteral { base: Hexadecimal, has_digits: true, has_exponent: false, suffix: Some(U8) }, value: "0xFFu8", len: 6, offset: 73 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 79 }
+            Token { kind: NumberLiteral { base: Binary, has_digits: true, has_exponent: false, suffix: Some(U64) }, value: "0b1_u64", len: 7, offset: 80 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 87 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: Some(Invalid) }, value: "5else", len: 5, offset: 88 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 93 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "0", len: 1, offset: 94 }
+            Token { kind: DotDot, value: "..", len: 2, offset: 95 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "5", len: 1, offset: 97 }
+        "#]],
+    );
+    check_diagnostics(
+        "5_i128 0b102 0o78 0b1f32 0x1_double 1.5i32 1e3u8 2.5_f64 0xAu8 0x1_dx 0xa_b_cdu8 0xabyte 0x1_f32 1e_5 1.5E-__2",
+        expect![[r#"
+            LexerDiagnostic { kind: InvalidNumberSuffix, offset: 2, len: 4 } "i128": invalid number suffix, expected one of i8, i16, i32, i64, u8, u16, u32, u64, f32, f64 or their aliases
+            LexerDiagnostic { kind: InvalidDigit, offset: 11, len: 1 } "2": invalid digit for the base of the number literal
+            LexerDiagnostic { kind: InvalidDigit, offset: 16, len: 1 } "8": invalid digit for the base of the number literal
+            LexerDiagnostic { kind: FloatSuffixOnNonDecimal, offset: 21, len: 3 } "f32": only decimal number literals can have a float suffix
+            LexerDiagnostic { kind: FloatSuffixOnNonDecimal, offset: 29, len: 6 } "double": only decimal number literals can have a float suffix
+            LexerDiagnostic { kind: IntegerSuffixOnFloat, offset: 39, len: 3 } "i32": float number literals can not have an integer suffix
+            LexerDiagnostic { kind: IntegerSuffixOnFloat, offset: 46, len: 2 } "u8": float number literals can not have an integer suffix
+            LexerDiagnostic { kind: InvalidNumberSuffix, offset: 68, len: 1 } "x": invalid number suffix, expected one of i8, i16, i32, i64, u8, u16, u32, u64, f32, f64 or their aliases
+            LexerDiagnostic { kind: MissingDigits, offset: 98, len: 2 } "e_": number literal is missing digits
+            LexerDiagnostic { kind: MissingDigits, offset: 105, len: 4 } "E-__": number literal is missing digits
+        "#]],
+    )
+}
+
+fn check_evaluation(src: &str, expect: Expect) {
+    let actual: String = tokenize(src)
+        .filter(|token| matches!(token.kind, TokenKind::NumberLiteral{..}))
+        .map(|token| format!("{} => {:?} / {:?}\n", token.value, evaluate_number(&token), evaluate_negated_number(&token)))
+        .collect();
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn number_evaluation() {
+    check_evaluation(
+        r"
+0 525 1_000_000 0xAFfe 0b1011_0011 0o3710 0xFFFF_FFFF_FFFF_FFFF 2147483647 2147483648 4294967296
+525.52 1.5e-3 2E10 1_0.0_1 0.1 5f32 0.1f32 1e3double
+127i8 128i8 255u8 256u8 5u8 0x1_0000_0000_0000_0000 1e400 3.5e38f32 0x 5_abc 1.5i32
+",
+        expect![[r#"
+            0 => Ok(Number { value: Integer(0), number_type: I32 }) / Ok(Number { value: Integer(0), number_type: I32 })
+            525 => Ok(Number { value: Integer(525), number_type: I32 }) / Ok(Number { value: Integer(525), number_type: I32 })
+            1_000_000 => Ok(Number { value: Integer(1000000), number_type: I32 }) / Ok(Number { value: Integer(1000000), number_type: I32 })
+            0xAFfe => Ok(Number { value: Integer(45054), number_type: I32 }) / Ok(Number { value: Integer(45054), number_type: I32 })
+            0b1011_0011 => Ok(Number { value: Integer(179), number_type: I32 }) / Ok(Number { value: Integer(179), number_type: I32 })
+            0o3710 => Ok(Number { value: Integer(1992), number_type: I32 }) / Ok(Number { value: Integer(1992), number_type: I32 })
+            0xFFFF_FFFF_FFFF_FFFF => Ok(Number { value: Integer(18446744073709551615), number_type: U64 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 43, len: 21 })
+            2147483647 => Ok(Number { value: Integer(2147483647), number_type: I32 }) / Ok(Number { value: Integer(2147483647), number_type: I32 })
+            2147483648 => Ok(Number { value: Integer(2147483648), number_type: U32 }) / Ok(Number { value: Integer(2147483648), number_type: I32 })
+            4294967296 => Ok(Number { value: Integer(4294967296), number_type: I64 }) / Ok(Number { value: Integer(4294967296), number_type: I64 })
+            525.52 => Ok(Number { value: Float(525.52), number_type: F64 }) / Ok(Number { value: Float(525.52), number_type: F64 })
+            1.5e-3 => Ok(Number { value: Float(0.0015), number_type: F64 }) / Ok(Number { value: Float(0.0015), number_type: F64 })
+            2E10 => Ok(Number { value: Float(20000000000.0), number_type: F64 }) / Ok(Number { value: Float(20000000000.0), number_type: F64 })
+            1_0.0_1 => Ok(Number { value: Float(10.01), number_type: F64 }) / Ok(Number { value: Float(10.01), number_type: F64 })
+            0.1 => Ok(Number { value: Float(0.1), number_type: F64 }) / Ok(Number { value: Float(0.1), number_type: F64 })
+            5f32 => Ok(Number { value: Float(5.0), number_type: F32 }) / Ok(Number { value: Float(5.0), number_type: F32 })
+            0.1f32 => Ok(Number { value: Float(0.10000000149011612), number_type: F32 }) / Ok(Number { value: Float(0.10000000149011612), number_type: F32 })
+            1e3double => Ok(Number { value: Float(1000.0), number_type: F64 }) / Ok(Number { value: Float(1000.0), number_type: F64 })
+            127i8 => Ok(Number { value: Integer(127), number_type: I8 }) / Ok(Number { value: Integer(127), number_type: I8 })
+            128i8 => Err(LiteralError { kind: NumberOutOfRange, offset: 157, len: 5 }) / Ok(Number { value: Integer(128), number_type: I8 })
+            255u8 => Ok(Number { value: Integer(255), number_type: U8 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 163, len: 5 })
+            256u8 => Err(LiteralError { kind: NumberOutOfRange, offset: 169, len: 5 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 169, len: 5 })
+            5u8 => Ok(Number { value: Integer(5), number_type: U8 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 175, len: 3 })
+            0x1_0000_0000_0000_0000 => Err(LiteralError { kind: NumberOutOfRange, offset: 179, len: 23 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 179, len: 23 })
+            1e400 => Err(LiteralError { kind: NumberOutOfRange, offset: 203, len: 5 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 203, len: 5 })
+            3.5e38f32 => Err(LiteralError { kind: NumberOutOfRange, offset: 209, len: 9 }) / Err(LiteralError { kind: NumberOutOfRange, offset: 209, len: 9 })
+            0x => Err(LiteralError { kind: MalformedNumber, offset: 219, len: 2 }) / Err(LiteralError { kind: MalformedNumber, offset: 219, len: 2 })
+            5_abc => Err(LiteralError { kind: MalformedNumber, offset: 222, len: 5 }) / Err(LiteralError { kind: MalformedNumber, offset: 222, len: 5 })
+            1.5i32 => Err(LiteralError { kind: MalformedNumber, offset: 228, len: 6 }) / Err(LiteralError { kind: MalformedNumber, offset: 228, len: 6 })
+        "#]],
+    )
+}
+
+#[test]
+fn raw_strings() {
+    check_lexing(
+        r###"r"C:\path\" r#"contains "quotes""# r##"a "# b"## r#x r"unterminated"###,
+        expect![[r###"
+            Token { kind: RawStringLiteral { terminated: true }, value: "r\"C:\\path\\\"", len: 11, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 11 }
+            Token { kind: RawStringLiteral { terminated: true }, value: "r#\"contains \"quotes\"\"#", len: 22, offset: 12 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 34 }
+            Token { kind: RawStringLiteral { terminated: true }, value: "r##\"a \"# b\"##", len: 13, offset: 35 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 48 }
+            Token { kind: Identifier, value: "r", len: 1, offset: 49 }
+            Token { kind: Hash, value: "#", len: 1, offset: 50 }
+            Token { kind: Identifier, value: "x", len: 1, offset: 51 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 52 }
+            Token { kind: RawStringLiteral { terminated: false }, value: "r\"unterminated", len: 14, offset: 53 }
+        "###]],
+    );
+    check_cooking(
+        r###"r"C:\path\" r#"contains "quotes""# r##"a "# b"## r"" r#"unterminated"###,
+        expect![[r###"
+            r"C:\path\" => Ok("C:\\path\\")
+            r#"contains "quotes""# => Ok("contains \"quotes\"")
+            r##"a "# b"## => Ok("a \"# b")
+            r"" => Ok("")
+            r#"unterminated => Ok("unterminated")
+        "###]],
+    )
+}
+
+#[test]
+fn multiline_strings() {
+    check_lexing(
+        "\"\" \"\"\"\n    a \"quote\"\n    \"\"\" \"\"\"\"\n  contains \"\"\"\n  \"\"\"\" \"\"\"unterminated\"\"",
+        expect![[r#"
+            Token { kind: StringLiteral { terminated: true }, value: "\"\"", len: 2, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 2 }
+            Token { kind: MultilineStringLiteral { terminated: true }, value: "\"\"\"\n    a \"quote\"\n    \"\"\"", len: 25, offset: 3 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 28 }
+            Token { kind: MultilineStringLiteral { terminated: true }, value: "\"\"\"\"\n  contains \"\"\"\n  \"\"\"\"", len: 26, offset: 29 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 55 }
+            Token { kind: MultilineStringLiteral { terminated: false }, value: "\"\"\"unterminated\"\"", len: 17, offset: 56 }
+        "#]],
+    );
+    check_cooking(
+        "\"\"\"\n    first\n\n      indented\n    last\n    \"\"\" \"\"\"one line\"\"\" \"\"\"\"\n  \"\"\" inside\n  \"\"\"\"\n\"\"\"text\n  b\n c\n  d\"\"\" \"\"\"\n    a\n  b\n    \"\"\"",
+        expect![[r#"
+            """
+                first
+
+                  indented
+                last
+                """ => Ok("first\n\n  indented\nlast")
+            """one line""" => Ok("one line")
+            """"
+              """ inside
+              """" => Ok("\"\"\" inside")
+            """text
+              b
+             c
+              d""" => Err([LiteralError { kind: ContentAfterOpeningQuotes, offset: 90, len: 4 }, LiteralError { kind: ContentBeforeClosingQuotes, offset: 102, len: 3 }])
+            """
+                a
+              b
+                """ => Err([LiteralError { kind: InsufficientIndentation, offset: 119, len: 3 }])
+        "#]],
+    )
+}
+
+#[test]
+fn interpolated_strings() {
+    check_lexing(
+        r#"$"Hello {name}!" $"{{not a hole}}" $"{a + "}"} {match x { 1 => "{" }}" $"{x" $"open"#,
+        expect![[r#"
+            Token { kind: InterpolatedStringLiteral { terminated: true }, value: "$\"Hello {name}!\"", len: 16, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 16 }
+            Token { kind: InterpolatedStringLiteral { terminated: true }, value: "$\"{{not a hole}}\"", len: 17, offset: 17 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 34 }
+            Token { kind: InterpolatedStringLiteral { terminated: true }, value: "$\"{a + \"}\"} {match x { 1 => \"{\" }}\"", len: 35, offset: 35 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 70 }
+            Token { kind: InterpolatedStringLiteral { terminated: false }, value: "$\"{x\" $\"open", len: 12, offset: 71 }
+        "#]],
+    );
+    check_diagnostics(
+        r#"$"a } \q {"\q" + #}" $"{0x"#,
+        expect![[r#"
+            LexerDiagnostic { kind: InvalidLiteral(UnescapedClosingBrace), offset: 4, len: 1 } "}": '}' in an interpolated string has to be escaped as '}}'
+            LexerDiagnostic { kind: InvalidLiteral(UnknownEscape), offset: 6, len: 2 } "\\q": unknown escape sequence
+            LexerDiagnostic { kind: InvalidLiteral(UnknownEscape), offset: 11, len: 2 } "\\q": unknown escape sequence
+            LexerDiagnostic { kind: UnterminatedStringLiteral, offset: 21, len: 5 } "$\"{0x": unterminated string literal, missing '"'
+            LexerDiagnostic { kind: UnterminatedInterpolationHole, offset: 23, len: 1 } "{": unterminated interpolation hole, missing '}'
+            LexerDiagnostic { kind: MissingDigits, offset: 24, len: 2 } "0x": number literal is missing digits
+        "#]],
+    )
+}
+
+fn check_interpolation(src: &str, expect: Expect) {
+    let actual: String = tokenize(src)
+        .filter(|token| matches!(token.kind, TokenKind::InterpolatedStringLiteral{..}))
+        .map(|token| {
+            let split = split_interpolated_string(&token);
+            let mut actual = format!("{}\n    {:?}\n", token.value, split.start);
+            for part in split.parts {
+                match part {
+                    InterpolatedPart::Text{ text } => actual += &format!("    {:?} => {:?}\n", text, cook_string(&text)),
+                    InterpolatedPart::Hole{ l_brace, tokens, r_brace } => {
+                        actual += &format!("    {:?}\n", l_brace);
+                        actual += &tokens.iter().map(|token| format!("        {:?}\n", token)).collect::<String>();
+                        actual += &format!("    {:?}\n", r_brace);
+                    },
+                }
+            }
+            actual + &format!("    {:?}\n", split.end)
+        })
+        .collect();
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn interpolation_parts() {
+    check_interpolation(
+        r#"$"Hello {name}!" $"{{\n}} {{{x}}}" $"{a + "}"}" $"{ {1} }" $"a } b" $"{x" $"""#,
+        expect![[r#"
+            $"Hello {name}!"
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 0 }
+                Token { kind: InterpolatedStringText, value: "Hello ", len: 6, offset: 2 } => Ok("Hello ")
+                Token { kind: LBrace, value: "{", len: 1, offset: 8 }
+                    Token { kind: Identifier, value: "name", len: 4, offset: 9 }
+                Some(Token { kind: RBrace, value: "}", len: 1, offset: 13 })
+                Token { kind: InterpolatedStringText, value: "!", len: 1, offset: 14 } => Ok("!")
+                Some(Token { kind: InterpolatedStringEnd, value: "\"", len: 1, offset: 15 })
+            $"{{\n}} {{{x}}}"
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 17 }
+                Token { kind: InterpolatedStringText, value: "{{\\n}} {{", len: 9, offset: 19 } => Ok("{\n} {")
+                Token { kind: LBrace, value: "{", len: 1, offset: 28 }
+                    Token { kind: Identifier, value: "x", len: 1, offset: 29 }
+                Some(Token { kind: RBrace, value: "}", len: 1, offset: 30 })
+                Token { kind: InterpolatedStringText, value: "}}", len: 2, offset: 31 } => Ok("}")
+                Some(Token { kind: InterpolatedStringEnd, value: "\"", len: 1, offset: 33 })
+            $"{a + "}"}"
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 35 }
+                Token { kind: LBrace, value: "{", len: 1, offset: 37 }
+                    Token { kind: Identifier, value: "a", len: 1, offset: 38 }
+                    Token { kind: Whitespace, value: " ", len: 1, offset: 39 }
+                    Token { kind: Plus, value: "+", len: 1, offset: 40 }
+                    Token { kind: Whitespace, value: " ", len: 1, offset: 41 }
+                    Token { kind: StringLiteral { terminated: true }, value: "\"}\"", len: 3, offset: 42 }
+                Some(Token { kind: RBrace, value: "}", len: 1, offset: 45 })
+                Some(Token { kind: InterpolatedStringEnd, value: "\"", len: 1, offset: 46 })
+            $"{ {1} }"
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 48 }
+                Token { kind: LBrace, value: "{", len: 1, offset: 50 }
+                    Token { kind: Whitespace, value: " ", len: 1, offset: 51 }
+                    Token { kind: LBrace, value: "{", len: 1, offset: 52 }
+                    Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "1", len: 1, offset: 53 }
+                    Token { kind: RBrace, value: "}", len: 1, offset: 54 }
+                    Token { kind: Whitespace, value: " ", len: 1, offset: 55 }
+                Some(Token { kind: RBrace, value: "}", len: 1, offset: 56 })
+                Some(Token { kind: InterpolatedStringEnd, value: "\"", len: 1, offset: 57 })
+            $"a } b"
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 59 }
+                Token { kind: InterpolatedStringText, value: "a } b", len: 5, offset: 61 } => Err([LiteralError { kind: UnescapedClosingBrace, offset: 63, len: 1 }])
+                Some(Token { kind: InterpolatedStringEnd, value: "\"", len: 1, offset: 66 })
+            $"{x" $""
+                Token { kind: InterpolatedStringStart, value: "$\"", len: 2, offset: 68 }
+                Token { kind: LBrace, value: "{", len: 1, offset: 70 }
+                    Token { kind: Identifier, value: "x", len: 1, offset: 71 }
+                    Token { kind: StringLiteral { terminated: true }, value: "\" $\"", len: 4, offset: 72 }
+                    Token { kind: StringLiteral { terminated: false }, value: "\"", len: 1, offset: 76 }
+                None
+                None
+        "#]],
+    )
+}
+
+#[test]
+fn doc_comments() {
+    check_lexing(
+        "/// doc\n//// not doc\n/** doc */ /**/ /*** not doc */ /** unterminated",
+        expect![[r#"
+            Token { kind: DocComment, value: "/// doc", len: 7, offset: 0 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 7 }
+            Token { kind: LineComment, value: "//// not doc", len: 12, offset: 8 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 20 }
+            Token { kind: MultilineDocComment { terminated: true }, value: "/** doc */", len: 10, offset: 21 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 31 }
+            Token { kind: MultilineComment { terminated: true }, value: "/**/", len: 4, offset: 32 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 36 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*** not doc */", len: 15, offset: 37 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 52 }
+            Token { kind: MultilineDocComment { terminated: false }, value: "/** unterminated", len: 16, offset: 53 }
+        "#]],
+    );
+
+    let actual: String = tokenize("///  indented\n///\n/**\n * first\n *   second\n */ /** one line */ /**unterminated")
+        .filter(|token| matches!(token.kind, TokenKind::DocComment | TokenKind::MultilineDocComment{..}))
+        .map(|token| format!("{:?}\n", cook_doc_comment(&token)))
+        .collect();
+    expect![[r#"
+        " indented"
+        ""
+        "first\n  second"
+        "one line"
+        "unterminated"
+    "#]].assert_eq(&actual);
+}
+
+#[test]
+fn nested_comments() {
+    check_lexing(
+        "/* a /* b */ c */ d /* /* */ */ */ /*/**/*/",
+        expect![[r#"
+            Token { kind: MultilineComment { terminated: true }, value: "/* a /* b */ c */", len: 17, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 17 }
+            Token { kind: Identifier, value: "d", len: 1, offset: 18 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 19 }
+            Token { kind: MultilineComment { terminated: true }, value: "/* /* */ */", len: 11, offset: 20 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 31 }
+            Token { kind: Star, value: "*", len: 1, offset: 32 }
+            Token { kind: Slash, value: "/", len: 1, offset: 33 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 34 }
+            Token { kind: MultilineComment { terminated: true }, value: "/*/**/*/", len: 8, offset: 35 }
+        "#]],
+    );
+    check_diagnostics(
+        "x /* outer /* inner */ still outer\n/* unrelated */",
+        expect![[r#"
+            LexerDiagnostic { kind: UnterminatedMultilineComment, offset: 2, len: 2 } "/*": unterminated multiline comment, missing '*/'
+        "#]],
+    );
+    check_diagnostics(
+        "/* /* /* */",
+        expect![[r#"
+            LexerDiagnostic { kind: UnterminatedMultilineComment, offset: 0, len: 2 } "/*": unterminated multiline comment, missing '*/'
+            LexerDiagnostic { kind: UnterminatedMultilineComment, offset: 3, len: 2 } "/*": unterminated multiline comment, missing '*/'
+        "#]],
+    )
+}
+
+fn check_relex(src: &str, edit: TextEdit, expect: Expect) {
+    let previous: Vec<Token> = tokenize(src).collect();
+    let new_src = format!("{}{}{}", &src[..edit.range.start], edit.text, &src[edit.range.end..]);
+    let relexed = relex(&previous, &edit, &new_src);
+
+    let mut actual = format!("removed {:?}, inserted {:?}\n", relexed.removed, relexed.inserted);
+    for token in &relexed.tokens[relexed.inserted.clone()] {
+        actual += &format!("{:?}\n", token);
+    }
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn relexing() {
+    check_relex(
+        "let abc = 5;",
+        TextEdit { range: 5..6, text: "x" },
+        expect![[r#"
+            removed 2..3, inserted 2..3
+            Token { kind: Identifier, value: "axc", len: 3, offset: 4 }
+        "#]],
+    );
+    check_relex(
+        "let a = 5;",
+        TextEdit { range: 5..5, text: "b" },
+        expect![[r#"
+            removed 2..3, inserted 2..3
+            Token { kind: Identifier, value: "ab", len: 2, offset: 4 }
+        "#]],
+    );
+    check_relex(
+        "x = 1e+a;",
+        TextEdit { range: 7..8, text: "5" },
+        expect![[r#"
+            removed 4..7, inserted 4..5
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: true, suffix: None }, value: "1e+5", len: 4, offset: 4 }
+        "#]],
+    );
+    check_relex(
+        "a r##b",
+        TextEdit { range: 5..6, text: "\"c\"##" },
+        expect![[r###"
+            removed 2..6, inserted 2..3
+            Token { kind: RawStringLiteral { terminated: true }, value: "r##\"c\"##", len: 8, offset: 2 }
+        "###]],
+    );
+    check_relex(
+        "a /* b */ c",
+        TextEdit { range: 7..9, text: "" },
+        expect![[r#"
+            removed 2..5, inserted 2..3
+            Token { kind: MultilineComment { terminated: false }, value: "/* b  c", len: 7, offset: 2 }
+        "#]],
+    );
+    check_relex(
+        "#!/bin/ferrous\nlet",
+        TextEdit { range: 0..0, text: "\u{FEFF}" },
+        expect![[r##"
+            removed 0..1, inserted 0..2
+            Token { kind: ByteOrderMark, value: "\u{feff}", len: 3, offset: 0 }
+            Token { kind: Shebang, value: "#!/bin/ferrous", len: 14, offset: 3 }
+        "##]],
+    );
+    check_relex(
+        "\u{FEFF}#!/bin/ferrous\nlet",
+        TextEdit { range: 0..3, text: " " },
+        expect![[r##"
+            removed 0..2, inserted 0..7
+            Token { kind: Whitespace, value: " ", len: 1, offset: 0 }
+            Token { kind: Hash, value: "#", len: 1, offset: 1 }
+            Token { kind: Bang, value: "!", len: 1, offset: 2 }
+            Token { kind: Slash, value: "/", len: 1, offset: 3 }
+            Token { kind: Identifier, value: "bin", len: 3, offset: 4 }
+            Token { kind: Slash, value: "/", len: 1, offset: 7 }
+            Token { kind: Identifier, value: "ferrous", len: 7, offset: 8 }
+        "##]],
+    );
+}
+
+/// xorshift, so the random edits are the same on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+const FRAGMENTS: [&str; 35] = [
+    "r", "#", "\"", "\"\"\"", "$", "{", "}", ".", "..", "_", "1", "0x", "e", "+", "-", "/", "*",
+    "//", "///", "/*", "*/", "\n", "\r", " ", "\t", "a", "fn", "'", "\\", "=", "<", "ä",
+    "!", "[", "\u{FEFF}",
+];
+
+fn random_text(rng: &mut Rng, fragments: usize) -> String {
+    (0..fragments).map(|_| FRAGMENTS[rng.next(FRAGMENTS.len())]).collect()
+}
+
+fn random_char_boundary(rng: &mut Rng, src: &str) -> usize {
+    loop {
+        let offset = rng.next(src.len() + 1);
+        if src.is_char_boundary(offset) {
+            return offset;
+        }
+    }
+}
+
+#[test]
+fn lookahead_is_bounded() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..2000 {
+        let src = random_text(&mut rng, 20);
+        for token in tokenize(&src) {
+            let rest = &src[token.end()..];
+            if rest.starts_with('#') {
+                continue;
+            }
+
+            let mut cursor = Cursor::new(&src[token.offset..]);
+            cursor.advance_token();
+            let reach = token.offset + cursor.reach();
+            let allowed = rest.char_indices().nth(MAX_LOOKAHEAD).map_or(src.len() + 1, |(i, _)| token.end() + i);
+            assert!(reach <= allowed, "{:?} in {:?} looks too far ahead", token, src);
+        }
+    }
+}
+
+#[test]
+fn relexing_matches_tokenize() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..20000 {
+        let fragments = rng.next(30);
+        let src = random_text(&mut rng, fragments);
+        let start = random_char_boundary(&mut rng, &src);
+        let end = start + random_char_boundary(&mut rng, &src[start..]);
+        let fragments = rng.next(4);
+        let text = random_text(&mut rng, fragments);
+        let edit = TextEdit { range: start..end, text: &text };
+
+        let previous: Vec<Token> = tokenize(&src).collect();
+        let new_src = format!("{}{}{}", &src[..start], text, &src[end..]);
+        let relexed = relex(&previous, &edit, &new_src);
+
+        let expected: Vec<Token> = tokenize(&new_src).collect();
+        assert_eq!(relexed.tokens, expected, "{:?} with {:?}", src, edit);
+        assert_eq!(relexed.inserted.start, relexed.removed.start);
+        assert_eq!(relexed.tokens.len() - relexed.inserted.end, previous.len() - relexed.removed.end);
+    }
+}
+
+#[test]
+fn unicode_identifiers() {
+    check_lexing(
+        "über x٣ ٣x 😀 cafe\u{301} _",
+        expect![[r#"
+            Token { kind: Identifier, value: "über", len: 5, offset: 0 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 5 }
+            Token { kind: Identifier, value: "x٣", len: 3, offset: 6 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 9 }
+            Token { kind: Unknown, value: "٣", len: 2, offset: 10 }
+            Token { kind: Identifier, value: "x", len: 1, offset: 12 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 13 }
+            Token { kind: Unknown, value: "😀", len: 4, offset: 14 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 18 }
+            Token { kind: Identifier, value: "cafe\u{301}", len: 6, offset: 19 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 25 }
+            Token { kind: Identifier, value: "_", len: 1, offset: 26 }
+        "#]],
+    );
+    check_diagnostics(
+        "let paramеter = рор + apple + аpple + apple;",
+        expect![[r#"
+            LexerDiagnostic { kind: MixedScriptIdentifier, offset: 4, len: 10 } "paramеter": identifier mixes characters of different scripts
+            LexerDiagnostic { kind: LookalikeIdentifier, offset: 4, len: 10 } "paramеter": identifier looks like an ascii identifier, but contains non-ascii characters
+            LexerDiagnostic { kind: LookalikeIdentifier, offset: 17, len: 6 } "рор": identifier looks like an ascii identifier, but contains non-ascii characters
+            LexerDiagnostic { kind: MixedScriptIdentifier, offset: 34, len: 6 } "аpple": identifier mixes characters of different scripts
+            LexerDiagnostic { kind: LookalikeIdentifier, offset: 34, len: 6 } "аpple": identifier looks like an ascii identifier, but contains non-ascii characters
+            LexerDiagnostic { kind: ConfusableIdentifier { other: 26 }, offset: 34, len: 6 } "аpple": identifier can be confused with the identifier at byte 26
+        "#]],
+    );
+    check_diagnostics(
+        "let café = cafe\u{301} + 幸験 + Straße;",
+        expect![[""]],
+    );
+
+    assert_eq!(normalize_identifier("cafe\u{301}"), "café");
+    assert!(matches!(normalize_identifier("café"), std::borrow::Cow::Borrowed(_)));
+}
+
+fn check_trivia(src: &str, expect: Expect) {
+    fn values<'src>(trivia: &[Token<'src>]) -> Vec<&'src str> {
+        trivia.iter().map(|token| token.value).collect()
+    }
+
+    let actual: String = tokenize_with_trivia(src)
+        .map(|token| format!("{:?} {:?} {:?}\n", values(&token.leading_trivia), token.token.value, values(&token.trailing_trivia)))
+        .collect();
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn trivia() {
+    check_trivia(
+        "// leading\n\n/// docs\nfn main() { // trailing\n    let a = 1; /* b */ /* c\n */ a\n}\n\n// end\n",
+        expect![[r#"
+            ["// leading", "\n", "\n", "/// docs", "\n"] "fn" [" "]
+            [] "main" []
+            [] "(" []
+            [] ")" [" "]
+            [] "{" [" ", "// trailing", "\n"]
+            ["    "] "let" [" "]
+            [] "a" [" "]
+            [] "=" [" "]
+            [] "1" []
+            [] ";" [" ", "/* b */", " ", "/* c\n */", " "]
+            [] "a" ["\n"]
+            [] "}" ["\n"]
+            ["\n", "// end", "\n"] "" []
+        "#]],
+    );
+    check_trivia(
+        "",
+        expect![[r#"
+            [] "" []
+        "#]],
+    );
+    check_trivia(
+        "  @ a",
+        expect![[r#"
+            ["  ", "@", " "] "a" []
+            [] "" []
+        "#]],
+    );
+}
+
+#[test]
+fn shebang_and_byte_order_mark() {
+    check_lexing(
+        "\u{FEFF}#!/usr/bin/env ferrous run\nlet a = 1;",
+        expect![[r##"
+            Token { kind: ByteOrderMark, value: "\u{feff}", len: 3, offset: 0 }
+            Token { kind: Shebang, value: "#!/usr/bin/env ferrous run", len: 26, offset: 3 }
+            Token { kind: Newline, value: "\n", len: 1, offset: 29 }
+            Token { kind: LetKeyword, value: "let", len: 3, offset: 30 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 33 }
+            Token { kind: Identifier, value: "a", len: 1, offset: 34 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 35 }
+            Token { kind: Equal, value: "=", len: 1, offset: 36 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 37 }
+            Token { kind: NumberLiteral { base: Decimal, has_digits: true, has_exponent: false, suffix: None }, value: "1", len: 1, offset: 38 }
+            Token { kind: Semicolon, value: ";", len: 1, offset: 39 }
+        "##]],
+    );
+    check_lexing(
+        "#![attribute]",
+        expect![[r##"
+            Token { kind: Hash, value: "#", len: 1, offset: 0 }
+            Token { kind: Bang, value: "!", len: 1, offset: 1 }
+            Token { kind: LBracket, value: "[", len: 1, offset: 2 }
+            Token { kind: Identifier, value: "attribute", len: 9, offset: 3 }
+            Token { kind: RBracket, value: "]", len: 1, offset: 12 }
+        "##]],
+    );
+    check_lexing(
+        " #!/usr/bin/env ferrous run",
+        expect![[r##"
+            Token { kind: Whitespace, value: " ", len: 1, offset: 0 }
+            Token { kind: Hash, value: "#", len: 1, offset: 1 }
+            Token { kind: Bang, value: "!", len: 1, offset: 2 }
+            Token { kind: Slash, value: "/", len: 1, offset: 3 }
+            Token { kind: Identifier, value: "usr", len: 3, offset: 4 }
+            Token { kind: Slash, value: "/", len: 1, offset: 7 }
+            Token { kind: Identifier, value: "bin", len: 3, offset: 8 }
+            Token { kind: Slash, value: "/", len: 1, offset: 11 }
+            Token { kind: Identifier, value: "env", len: 3, offset: 12 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 15 }
+            Token { kind: Identifier, value: "ferrous", len: 7, offset: 16 }
+            Token { kind: Whitespace, value: " ", len: 1, offset: 23 }
+            Token { kind: Identifier, value: "run", len: 3, offset: 24 }
+        "##]],
+    );
+    check_diagnostics(
+        "\u{FEFF}let a\u{FEFF} = 1;\u{FEFF}",
+        expect![[r#"
+            LexerDiagnostic { kind: MisplacedByteOrderMark, offset: 8, len: 3 } "\u{feff}": byte order mark (U+FEFF) is only allowed at the start of the file
+            LexerDiagnostic { kind: MisplacedByteOrderMark, offset: 16, len: 3 } "\u{feff}": byte order mark (U+FEFF) is only allowed at the start of the file
+        "#]],
+    );
+}
+
+#[test]
+fn reserved_keywords() {
+    check_diagnostics(
+        "let const = impl_ + where; $\"{type}\" union",
+        expect![[r#"
+            LexerDiagnostic { kind: ReservedKeyword, offset: 4, len: 5 } "const": reserved keyword can not be used as an identifier
+            LexerDiagnostic { kind: ReservedKeyword, offset: 20, len: 5 } "where": reserved keyword can not be used as an identifier
+            LexerDiagnostic { kind: ReservedKeyword, offset: 30, len: 4 } "type": reserved keyword can not be used as an identifier
+        "#]],
+    );
+    assert_eq!(lex_with_edition("let struct = impl + trait + enum; $\"{self}\"", Edition::Edition2025).diagnostics, vec![]);
+
+    // the keywords Edition2026 added are identifiers in Edition2025, in interpolation holes as well
+    let src = "let struct = impl + trait + enum; $\"{self}\"";
+    let keywords = ["struct", "enum", "impl", "self", "trait"];
+    for (edition, expected) in [
+        (Edition::Edition2025, [TokenKind::Identifier; 5]),
+        (Edition::Edition2026, [TokenKind::StructKeyword, TokenKind::ImplKeyword, TokenKind::TraitKeyword, TokenKind::EnumKeyword, TokenKind::SelfKeyword]),
+    ].iter() {
+        let mut tokens = vec![];
+        for token in tokenize_with_edition(src, *edition) {
+            if let TokenKind::InterpolatedStringLiteral{..} = token.kind {
+                for part in split_interpolated_string_with_edition(&token, *edition).parts {
+                    if let InterpolatedPart::Hole{ tokens: hole, .. } = part {
+                        tokens.extend(hole);
+                    }
+                }
+            }
+            tokens.push(token);
+        }
+        let kinds: Vec<TokenKind> = tokens.iter().filter(|token| keywords.contains(&token.value)).map(|token| token.kind).collect();
+        assert_eq!(&kinds, expected, "{:?}", edition);
+    }
+}
+
+proptest::proptest! {
+    #[test]
+    fn tokens_round_trip(src in "\\PC*") {
+        let tokens: Vec<_> = tokenize(&src).collect();
+        proptest::prop_assert_eq!(to_source(&tokens), src.as_str());
+
+        let with_trivia: String = tokenize_with_trivia(&src).map(|token| token.to_source()).collect();
+        proptest::prop_assert_eq!(with_trivia, src.as_str());
+    }
+
+    #[test]
+    fn fragments_round_trip(fragments in proptest::collection::vec(proptest::sample::select(&FRAGMENTS[..]), 0..40)) {
+        let src = fragments.concat();
+        let tokens: Vec<_> = tokenize(&src).collect();
+        proptest::prop_assert_eq!(to_source(&tokens), src.as_str());
+
+        let with_trivia: String = tokenize_with_trivia(&src).map(|token| token.to_source()).collect();
+        proptest::prop_assert_eq!(with_trivia, src.as_str());
+    }
+}
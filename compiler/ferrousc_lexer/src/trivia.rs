@@ -0,0 +1,74 @@
+use crate::{tokenize, Token, TokenKind};
+
+/// A significant token with the trivia around it. Like in Roslyn, the trailing trivia of a token
+/// runs up to and including the end of its line, everything else in front of a token is its leading trivia.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenWithTrivia<'src> {
+    pub leading_trivia: Vec<Token<'src>>,
+    pub token: Token<'src>,
+    pub trailing_trivia: Vec<Token<'src>>,
+}
+
+impl TokenWithTrivia<'_> {
+    /// byte offset of the first char of the leading trivia
+    pub fn full_offset(&self) -> usize {
+        self.leading_trivia.first().unwrap_or(&self.token).offset
+    }
+
+    /// byte offset one past the last char of the trailing trivia
+    pub fn full_end(&self) -> usize {
+        self.trailing_trivia.last().unwrap_or(&self.token).end()
+    }
+
+    /// the text of the token with all of its trivia
+    pub fn to_source(&self) -> String {
+        crate::to_source(self.leading_trivia.iter().chain(Some(&self.token)).chain(&self.trailing_trivia))
+    }
+}
+
+/// the syntax token mode of the lexer
+pub fn tokenize_with_trivia(src: &str) -> impl Iterator<Item = TokenWithTrivia<'_>> {
+    attach_trivia(tokenize(src))
+}
+
+/// Groups the tokens into significant tokens with their trivia.
+/// The last item is always an EndOfFile token, its leading trivia is whatever comes after the last significant token.
+pub fn attach_trivia<'src>(tokens: impl Iterator<Item = Token<'src>>) -> impl Iterator<Item = TokenWithTrivia<'src>> {
+    let mut tokens = tokens.peekable();
+    let mut end = 0;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let mut leading_trivia = vec![];
+        let token = loop {
+            match tokens.next() {
+                Some(token) if token.kind.is_trivia() => {
+                    end = token.end();
+                    leading_trivia.push(token);
+                },
+                Some(token) => break token,
+                None => {
+                    done = true;
+                    break Token{ kind: TokenKind::EndOfFile, value: "", len: 0, offset: end };
+                },
+            }
+        };
+        end = token.end();
+
+        let mut trailing_trivia = vec![];
+        while let Some(trivia) = tokens.next_if(|token| token.kind.is_trivia()) {
+            end = trivia.end();
+            let is_newline = trivia.kind == TokenKind::Newline;
+            trailing_trivia.push(trivia);
+            if is_newline {
+                break;
+            }
+        }
+
+        Some(TokenWithTrivia{ leading_trivia, token, trailing_trivia })
+    })
+}
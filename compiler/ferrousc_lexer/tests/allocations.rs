@@ -0,0 +1,68 @@
+//! Counts heap allocations while lexing generated sources of growing size.
+//! Tokens borrow their text from the source, so lexing should not allocate at all,
+//! no matter how many tokens there are.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ferrousc_lexer::tokenize;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SNIPPET: &str = r#"/// adds things
+fn add_{n}(a: i32, b: i32) -> i32 {
+    let mut x = a + b * 0x{n}_u32 - 1.5e3 + 0xFF_ab_ushort; // trailing comment
+    /* block /* nested */ comment */
+    if x >= 10 && b != 'c' { x += 1; } else { x -= 2; }
+    let s = "text {n}" + r"raw" + $"hole {x + 1} done";
+    return x;
+}
+"#;
+
+fn generate(functions: usize) -> String {
+    (0..functions)
+        .map(|n| SNIPPET.replace("{n}", &n.to_string()))
+        .collect()
+}
+
+/// returns (allocations, tokens)
+fn count_allocations(src: &str) -> (usize, usize) {
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let tokens = tokenize(src).count();
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+    (after - before, tokens)
+}
+
+#[test]
+fn lexing_does_not_allocate_per_token() {
+    let small = generate(10);
+    let large = generate(1000);
+
+    let (small_allocations, small_tokens) = count_allocations(&small);
+    let (large_allocations, large_tokens) = count_allocations(&large);
+
+    assert!(large_tokens > 50 * small_tokens);
+    assert_eq!(small_allocations, large_allocations);
+    assert_eq!(large_allocations, 0);
+}
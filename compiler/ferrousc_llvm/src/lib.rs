@@ -9,13 +9,16 @@ use crate::wrapper::*;
 use llvm_sys::core::*;
 
 use std::convert::TryInto;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::fs;
 use std::ptr;
 
 use ferrousc_ast::nodes::*;
+use ferrousc_ast::source::node_span;
+use ferrousc_diagnostics::{codes, Diagnostic, Diagnostics};
 use ferrousc_lexer::tokenize;
 use ferrousc_parser::generate_ast;
+use ferrousc_semantic::{monomorphize, Instances};
 
 static TEST_CODE: &str = "./llvm_test.fe";
 
@@ -26,14 +29,30 @@ fn read_file(filename: &str) -> String {
 pub fn run() {
     let test_code = read_file(TEST_CODE);
 
-    let ast = generate_ast(tokenize(&test_code));
+    let mut diagnostics = Diagnostics::new();
+    let ir = compile(&test_code, &mut diagnostics);
+    if !diagnostics.is_empty() {
+        diagnostics.sort();
+        eprintln!("{}", diagnostics.render(&test_code, TEST_CODE));
+    }
+
+    // export
+    fs::write("main.ll", ir).expect("could not write main.ll");
+}
+
+/// Builds the module of a program that passed ferrousc_semantic::check and returns its LLVM IR.
+/// Code the backend can not compile yet is reported and left out
+pub fn compile(src: &str, diagnostics: &mut Diagnostics) -> String {
+    let ast = generate_ast(tokenize(src));
+    // the errors are reported by ferrousc_semantic::check before
+    let instances = monomorphize(&ast, &mut Diagnostics::new());
 
     unsafe {
         // setup            
         let mut context = Context::new();
         let mut module = Module::new("main", &context);
         let mut builder = Builder::new(&context);
-        let functions = Functions::new();
+        let mut functions = Functions::new();
 
         // declare an function without body to tell llvm to import this
         let puts_func_type = LLVMFunctionType(context.types.i32_type, [context.types.i8_ptr_type].as_ptr() as *mut _, 1, 0);
@@ -49,64 +68,135 @@ pub fn run() {
         let hello_world_str = LLVMBuildGlobalStringPtr(builder.builder, c_str!("hello, world."), c_str!(""));
         let puts_args = [hello_world_str].as_ptr() as *mut _;
         LLVMBuildCall(builder.builder, puts_func, puts_args, 1, c_str!(""));
-        LLVMBuildRet(builder.builder, LLVMConstInt(context.types.i32_type, 0, 0));
+
+        // every function is declared before the first body is built, so a call finds functions further down
+        // in the source and instances that monomorphization found after the one the call is in
+        for st in &ast.statements {
+            if let Stat::FunctionDefinition{ identifier, generic_parameters: None, parameter_list, return_type, .. } = st {
+                declare_function(identifier.identifier.token.value, parameter_list, return_type, &mut functions, &module, &context);
+            }
+        }
+        // generic functions only have code for the type arguments they are called with
+        for instance in &instances.functions {
+            if let Stat::FunctionDefinition{ parameter_list, return_type, .. } = instance.declaration {
+                declare_function(&instance.to_string(), parameter_list, return_type, &mut functions, &module, &context);
+            }
+        }
 
         ast.walk(|st|{
-            walk(st, &functions, &builder, &module, &context);
+            // statements outside of functions are code of main
+            builder.position_at_end(main_block);
+            walk(st, None, &instances, &functions, diagnostics, &builder, &context);
         });
 
-        // export
-        LLVMPrintModuleToFile(module.module, c_str!("main.ll"), ptr::null_mut());
+        for (index, instance) in instances.functions.iter().enumerate() {
+            if let Stat::FunctionDefinition{ body, .. } = instance.declaration {
+                build_function(&instance.to_string(), body, Some(index), &instances, &functions, diagnostics, &builder, &context);
+            }
+        }
+
+        builder.position_at_end(main_block);
+        LLVMBuildRet(builder.builder, LLVMConstInt(context.types.i32_type, 0, 0));
+
+        let ir = LLVMPrintModuleToString(module.module);
+        let text = CStr::from_ptr(ir).to_string_lossy().into_owned();
+        LLVMDisposeMessage(ir);
 
         // cleanup
         builder.drop();
         module.drop();
         context.drop();
+
+        text
     }
 }
 
-unsafe fn walk(st: &Stat, functions: &mut Functions, builder: &Builder, module: &Module, context: &Context) {
+/// caller is the index of the function instance st is in, None outside of generic functions
+unsafe fn walk(st: &Stat, caller: Option<usize>, instances: &Instances, functions: &Functions, diagnostics: &mut Diagnostics, builder: &Builder, context: &Context) {
     match &*st {
-        Stat::FunctionDefinition {
-            fn_token, 
-            identifier, 
-            parameter_list,
-            return_type,
-            body,
-        } => {
-            let func_ret_type = if return_type.is_some() { context.types.i32_type } else { context.types.void_type };
-            let func_type = LLVMFunctionType(func_ret_type, ptr::null_mut(), parameter_list.parameters.len().try_into().unwrap(), 0);
-            let func_name = CString::new(identifier.identifier.token.value.clone()).unwrap().into_raw();
+        Stat::FunctionDefinition { identifier, generic_parameters, body, .. } => {
+            // built for every instance instead
+            if generic_parameters.is_some() {
+                return;
+            }
+            build_function(identifier.identifier.token.value, body, caller, instances, functions, diagnostics, builder, context);
+        },
+        Stat::Expr { expr: Expr::Call { callee, .. }, .. } => {
+            // only calls of functions by their name can be compiled yet
+            let identifier = match callee.as_ref() {
+                Expr::IdentifierUsage { identifier } => Some(identifier),
+                Expr::Turbofish { expr, .. } => match expr.as_ref() {
+                    Expr::IdentifierUsage { identifier } => Some(identifier),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let identifier = match identifier {
+                Some(identifier) => identifier,
+                None => {
+                    diagnostics.push(
+                        Diagnostic::error(codes::UNSUPPORTED_CALLEE, "only functions called by their name can be compiled yet")
+                            .with_primary_label(node_span(callee), "not the name of a function")
+                    );
+                    return;
+                },
+            };
+            // a generic function is called by the name of its instance, like max<i32>
+            let name = match instances.callee(caller, callee) {
+                Some(instance) => instance.to_string(),
+                None => identifier.identifier.token.value.to_owned(),
+            };
+            let func = match functions.functions.get(&name) {
+                Some(func) => *func,
+                None => {
+                    diagnostics.push(
+                        Diagnostic::error(codes::UNKNOWN_FUNCTION, format!("cannot find function `{}`", name))
+                            .with_primary_label(node_span(callee), "no function is built with this name")
+                    );
+                    return;
+                },
+            };
 
-            let func = LLVMAddFunction(module.module, func_name, func_type);
+            // the arguments are not compiled yet
+            LLVMBuildCall(builder.builder, func, ptr::null_mut(), 0, c_str!(""));
+        }
+        #[allow(unreachable_patterns)]
+        stat => println!("unknown statement! {:?}", stat),
+    }
+}
 
-            functions.functions.insert(identifier.identifier.token.value, func);
+/// adds the function called name to the module, without a body yet
+unsafe fn declare_function(name: &str, parameter_list: &ParameterList, return_type: &Option<ReturnType>, functions: &mut Functions, module: &Module, context: &Context) {
+    let func_ret_type = if return_type.is_some() { context.types.i32_type } else { context.types.void_type };
+    let func_type = LLVMFunctionType(func_ret_type, ptr::null_mut(), parameter_list.parameters.len().try_into().unwrap(), 0);
+    let func_name = CString::new(name).unwrap();
 
-            let func_block = LLVMAppendBasicBlockInContext(context.context, func, func_name);
+    let func = LLVMAddFunction(module.module, func_name.as_ptr(), func_type);
 
-            builder.position_at_end(func_block);
-            LLVMBuildRetVoid(builder.builder);
+    functions.functions.insert(name.to_owned(), func);
+}
 
+/// builds the body of the function called name, generic functions are built once for every instance
+#[allow(clippy::too_many_arguments)]
+unsafe fn build_function(name: &str, body: &FunctionBody, caller: Option<usize>, instances: &Instances, functions: &Functions, diagnostics: &mut Diagnostics, builder: &Builder, context: &Context) {
+    // every function and instance is declared before the first body is built
+    let func = functions.functions[name];
+    let func_name = CString::new(name).unwrap();
 
-            match body.as_ref() {
-                FunctionBody::BlockStatement{ block } => {
-                    walk(block, &functions, &builder, &module, &context);
-                },
-                FunctionBody::ExpressionBody{ fat_arrow_token, statement } => {
-                    walk(statement, &functions, &builder, &module, &context);
-                },
-            }
+    let func_block = LLVMAppendBasicBlockInContext(context.context, func, func_name.as_ptr());
+
+    builder.position_at_end(func_block);
+
+    match body {
+        FunctionBody::BlockStatement{ block } => {
+            walk(block, caller, instances, functions, diagnostics, builder, context);
         },
-        Stat::Expr { expr, semicolon_token } => {
-            match expr {
-                Expr::Call { identifier, argument_list } => {
-                    let func_name = CString::new(identifier.identifier.token.value.clone()).unwrap().into_raw();
-                    
-                    LLVMBuildCall(builder.builder, functions.functions.get(&identifier.identifier.token.value).unwrap().to_owned(), ptr::null_mut(), 3, func_name);
-                }
-            }
-        }
-        #[allow(unreachable_patterns)]
-        stat => println!("unknown statement! {:?}", stat),
+        FunctionBody::ExpressionBody{ statement, .. } => {
+            walk(statement, caller, instances, functions, diagnostics, builder, context);
+        },
+        // the semantic checks only let signatures through in traits
+        FunctionBody::Signature{..} => {},
     }
-}
\ No newline at end of file
+
+    LLVMBuildRetVoid(builder.builder);
+}
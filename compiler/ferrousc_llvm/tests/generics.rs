@@ -0,0 +1,32 @@
+use ferrousc_diagnostics::Diagnostics;
+use ferrousc_llvm::compile;
+
+fn check(src: &str) -> (String, String) {
+    let mut diagnostics = Diagnostics::new();
+    let ir = compile(src, &mut diagnostics);
+    diagnostics.sort();
+
+    (ir, diagnostics.render(src, "main.fe"))
+}
+
+#[test]
+fn generic_calls_call_their_instance() {
+    // second<i32> is found before first<i32>, which it calls, and done is declared after both
+    let (ir, diagnostics) = check("fn first<T>() => done();\nfn second<T>() => first::<T>();\nsecond::<int>();\nfn done() {}\n");
+
+    assert_eq!(diagnostics, "");
+    for function in ["@\"first<i32>\"", "@\"second<i32>\"", "@done"].iter() {
+        assert!(ir.contains(&format!("define void {}()", function)), "{} is not built in\n{}", function, ir);
+        assert!(ir.contains(&format!("call void {}()", function)), "{} is not called in\n{}", function, ir);
+    }
+    // only the instances have code
+    assert!(!ir.contains("@first()") && !ir.contains("@second()"), "{}", ir);
+}
+
+#[test]
+fn calls_that_can_not_be_built_are_reported() {
+    let (_, diagnostics) = check("a.b();\nmissing();\n");
+
+    assert!(diagnostics.contains("error[E0300]: only functions called by their name can be compiled yet"), "{}", diagnostics);
+    assert!(diagnostics.contains("error[E0301]: cannot find function `missing`"), "{}", diagnostics);
+}
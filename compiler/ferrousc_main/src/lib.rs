@@ -1,7 +1,9 @@
 use std::fs;
 
+use ferrousc_ast::diagnostic::emit_diagnostics;
 use ferrousc_ast::nodes::{Expr, FunctionBody, Stat};
-use ferrousc_lexer::tokenize;
+use ferrousc_diagnostics::Diagnostics;
+use ferrousc_lexer::{lex, LexerDiagnostic};
 use ferrousc_parser::generate_ast;
 
 static TEST_CODE: &str = "./test_code.fe";
@@ -19,12 +21,22 @@ pub fn run() {
 pub fn print() {
     println!();
     let test_code = read_file(TEST_CODE);
-    for token in tokenize(&test_code) {
+    let lexed = lex(&test_code);
+    for token in &lexed.tokens {
         println!("{:?}", token);
     }
     println!();
+
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.extend(lexed.diagnostics.iter().map(LexerDiagnostic::to_diagnostic));
     
-    let ast = generate_ast(tokenize(&test_code));
+    let ast = generate_ast(lexed.tokens.into_iter());
+
+    // the parser reports malformed tokens as well, the sink keeps only one of each
+    emit_diagnostics(&ast, &mut diagnostics);
+    ferrousc_semantic::check(&ast, &mut diagnostics);
+    diagnostics.sort();
+    println!("{}", diagnostics.render(&test_code, TEST_CODE));
 
     println!("{:?}", ast);
     println!();
@@ -38,6 +50,7 @@ pub fn print() {
 fn walk(st: &Stat, tab_index: i32) {
     match &*st {
         Stat::VarDefinition{ 
+            attributes,
             let_token, 
             mut_token, 
             identifier, 
@@ -48,6 +61,10 @@ fn walk(st: &Stat, tab_index: i32) {
             indent_n(tab_index);
             println!("Var Definition {{");
             indent_n(tab_index + 1);
+            println!("docs: {:?},", st.docs());
+            indent_n(tab_index + 1);
+            println!("attributes: {:?},", attributes);
+            indent_n(tab_index + 1);
             println!("let: {:?},", let_token);
             indent_n(tab_index + 1);
             println!("mut: {:?},", mut_token);
@@ -106,8 +123,10 @@ fn walk(st: &Stat, tab_index: i32) {
             println!("}}");
         },
         Stat::FunctionDefinition {
+            attributes,
             fn_token, 
             identifier, 
+            generic_parameters,
             parameter_list,
             return_type,
             body,
@@ -115,14 +134,24 @@ fn walk(st: &Stat, tab_index: i32) {
             indent_n(tab_index);
             println!("Function Definition Statement {{");
             indent_n(tab_index + 1);
+            println!("docs: {:?},", st.docs());
+            indent_n(tab_index + 1);
+            println!("attributes: {:?},", attributes);
+            indent_n(tab_index + 1);
             println!("fn_token: {:?},", fn_token);
             indent_n(tab_index + 1);
             println!("identifier: {:?},", identifier);
+            indent_n(tab_index + 1);
+            println!("generic_parameters: {:?},", generic_parameters);
 
             indent_n(tab_index + 1);
             println!("parameter_list: {{");
             indent_n(tab_index + 2);
             println!("l_paran: {:?}", parameter_list.l_paran);
+            if let Some(receiver) = &parameter_list.receiver {
+                indent_n(tab_index + 2);
+                println!("receiver: {:?}", receiver);
+            }
             
             for parameter in &parameter_list.parameters {
                 indent_n(tab_index + 2);
@@ -186,6 +215,10 @@ fn walk(st: &Stat, tab_index: i32) {
                     indent_n(tab_index + 2);
                     println!("}}");
                 },
+                FunctionBody::Signature{ semicolon_token } => {
+                    indent_n(tab_index + 2);
+                    println!("semicolon_token: {:?}", semicolon_token);
+                },
             }
 
             indent_n(tab_index + 1);
@@ -194,6 +227,121 @@ fn walk(st: &Stat, tab_index: i32) {
             indent_n(tab_index);
             println!("}}");
         },
+        Stat::StructDefinition {
+            attributes,
+            struct_token,
+            identifier,
+            generic_parameters,
+            l_brace,
+            fields,
+            r_brace,
+        } => {
+            indent_n(tab_index);
+            println!("Struct Definition Statement {{");
+            indent_n(tab_index + 1);
+            println!("docs: {:?},", st.docs());
+            indent_n(tab_index + 1);
+            println!("attributes: {:?},", attributes);
+            indent_n(tab_index + 1);
+            println!("struct_token: {:?},", struct_token);
+            indent_n(tab_index + 1);
+            println!("identifier: {:?},", identifier);
+            indent_n(tab_index + 1);
+            println!("generic_parameters: {:?},", generic_parameters);
+            indent_n(tab_index + 1);
+            println!("l_brace: {:?},", l_brace);
+            for field in fields {
+                indent_n(tab_index + 1);
+                println!("field: {:?},", field);
+            }
+            indent_n(tab_index + 1);
+            println!("r_brace: {:?},", r_brace);
+            indent_n(tab_index);
+            println!("}}");
+        },
+        Stat::EnumDefinition {
+            attributes,
+            enum_token,
+            identifier,
+            generic_parameters,
+            l_brace,
+            variants,
+            r_brace,
+        } => {
+            indent_n(tab_index);
+            println!("Enum Definition Statement {{");
+            indent_n(tab_index + 1);
+            println!("docs: {:?},", st.docs());
+            indent_n(tab_index + 1);
+            println!("attributes: {:?},", attributes);
+            indent_n(tab_index + 1);
+            println!("enum_token: {:?},", enum_token);
+            indent_n(tab_index + 1);
+            println!("identifier: {:?},", identifier);
+            indent_n(tab_index + 1);
+            println!("generic_parameters: {:?},", generic_parameters);
+            indent_n(tab_index + 1);
+            println!("l_brace: {:?},", l_brace);
+            for variant in variants {
+                indent_n(tab_index + 1);
+                println!("variant: {:?},", variant);
+            }
+            indent_n(tab_index + 1);
+            println!("r_brace: {:?},", r_brace);
+            indent_n(tab_index);
+            println!("}}");
+        },
+        Stat::TraitDefinition {
+            trait_token,
+            identifier,
+            l_brace,
+            functions,
+            r_brace,
+        } => {
+            indent_n(tab_index);
+            println!("Trait Definition Statement {{");
+            indent_n(tab_index + 1);
+            println!("docs: {:?},", st.docs());
+            indent_n(tab_index + 1);
+            println!("trait_token: {:?},", trait_token);
+            indent_n(tab_index + 1);
+            println!("identifier: {:?},", identifier);
+            indent_n(tab_index + 1);
+            println!("l_brace: {:?},", l_brace);
+            for function in functions {
+                walk(function, tab_index + 1);
+            }
+            indent_n(tab_index + 1);
+            println!("r_brace: {:?},", r_brace);
+            indent_n(tab_index);
+            println!("}}");
+        },
+        Stat::ImplBlock {
+            impl_token,
+            trait_for,
+            type_kind,
+            l_brace,
+            functions,
+            r_brace,
+        } => {
+            indent_n(tab_index);
+            println!("Impl Block Statement {{");
+            indent_n(tab_index + 1);
+            println!("impl_token: {:?},", impl_token);
+            indent_n(tab_index + 1);
+            println!("trait_for: {:?},", trait_for);
+            indent_n(tab_index + 1);
+            println!("type_kind: {:?},", type_kind);
+            indent_n(tab_index + 1);
+            println!("l_brace: {:?},", l_brace);
+            for function in functions {
+                walk(function, tab_index + 1);
+            }
+            indent_n(tab_index + 1);
+            println!("r_brace: {:?},", r_brace);
+            indent_n(tab_index);
+            println!("}}");
+        },
         Stat::Return {
             return_token, 
             expression, 
@@ -1,10 +1,13 @@
 mod parser;
 
 use ferrousc_ast::nodes::*;
-use ferrousc_lexer::Token;
+use ferrousc_lexer::{Edition, Token};
 use parser::Parser;
 
-pub fn generate_ast(tokens: impl Iterator<Item = Token>) -> CompilationUnit {
-    let mut parser = Parser::new(tokens);
-    parser.parse()
+pub fn generate_ast<'src>(tokens: impl Iterator<Item = Token<'src>>) -> CompilationUnit<'src> {
+    Parser::new(tokens).parse()
+}
+
+pub fn generate_ast_with_edition<'src>(tokens: impl Iterator<Item = Token<'src>>, edition: Edition) -> CompilationUnit<'src> {
+    Parser::with_edition(tokens, edition).parse()
 }
\ No newline at end of file
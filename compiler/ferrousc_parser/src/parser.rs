@@ -1,12 +1,14 @@
+use std::collections::VecDeque;
+
 use ferrousc_ast::nodes::*;
-use ferrousc_lexer::{Token, TokenKind};
+use ferrousc_lexer::{attach_trivia, diagnose, is_reserved_keyword, split_interpolated_string_with_edition, Base, ContextualKeyword, Edition, InterpolatedString, LexerDiagnostic, LexerErrorKind, Token, TokenKind, TokenWithTrivia};
 
 const ASSIGNMENT_TOKENS: [TokenKind; 2] = [
     TokenKind::Equal, 
     TokenKind::QuestionQuestionEquals,
 ];
 
-const OPERATORS: [TokenKind; 44] = [
+const OPERATORS: [TokenKind; 45] = [
     TokenKind::Plus, 
     TokenKind::Minus,     
     TokenKind::Star,     
@@ -14,6 +16,7 @@ const OPERATORS: [TokenKind; 44] = [
     TokenKind::Bang,
     TokenKind::LBracket,
     TokenKind::QuestionLBracket,
+    TokenKind::LParen,
 
     TokenKind::Dot,
     TokenKind::QuestionDot,
@@ -57,6 +60,14 @@ const OPERATORS: [TokenKind; 44] = [
     TokenKind::QuestionQuestion,
 ];
 
+/// attributes the compiler understands, every other attribute is ignored with a warning
+const KNOWN_ATTRIBUTES: [&str; 4] = [
+    "inline",
+    "test",
+    "extern",
+    "deprecated",
+];
+
 const INT_TYPES: [&str; 16] = [
     "sbyte", "i8", 
     "short", "i16", 
@@ -86,42 +97,58 @@ const BOOL_TYPES: [&str; 1] = [
     "bool"
 ];
 
-pub struct Parser {
-    tokens: Vec<Token>,
-    pos: usize,
+pub struct Parser<'src> {
+    /// the significant tokens with their trivia, without the end of file
+    tokens: VecDeque<TokenWithTrivia<'src>>,
+    end_of_file: TokenWithTrivia<'src>,
+    /// byte offset behind the last eaten token, without its trivia
+    previous_end: usize,
+    /// false in front of a block, where `a {` is an identifier followed by the block
+    struct_literals: bool,
+    /// decides which identifiers are reserved
+    edition: Edition,
 }
 
-impl Parser {
-    pub fn new(token_iterator: impl Iterator<Item = Token>) -> Parser {
-        Parser{ tokens: token_iterator.collect(), pos: 0 }
+impl<'src> Parser<'src> {
+    /// a parser for the latest edition
+    pub fn new(token_iterator: impl Iterator<Item = Token<'src>>) -> Parser<'src> {
+        Parser::with_edition(token_iterator, Edition::default())
+    }
+
+    pub fn with_edition(token_iterator: impl Iterator<Item = Token<'src>>, edition: Edition) -> Parser<'src> {
+        let mut tokens: VecDeque<_> = attach_trivia(token_iterator).collect();
+        let end_of_file = tokens.pop_back().expect("attach_trivia always ends with the end of file");
+        Parser{ tokens, end_of_file, previous_end: 0, struct_literals: true, edition }
     }
 
-    pub(crate) fn peek(&self) -> Option<Token> {
+    pub(crate) fn peek(&self) -> Option<Token<'src>> {
         self.peek_n(0)
     }
 
-    pub(crate) fn peek_n(&self, n: usize) -> Option<Token> {
-        match self.tokens.get(self.pos + n) {
-            Some(token) => Some(token.clone()),
-            None => None,
-        }
+    pub(crate) fn peek_n(&self, n: usize) -> Option<Token<'src>> {
+        self.tokens.get(n).map(|token| token.token)
+    }
+
+    pub(crate) fn eat(&mut self) -> Option<TokenWithTrivia<'src>> {
+        let token = self.tokens.pop_front()?;
+        self.previous_end = token.token.end();
+        Some(token)
     }
 
-    pub(crate) fn eat(&mut self) -> Option<Token> {
-        let token = self.peek();
-        self.pos += 1;
-        token
+    /// Byte offset of a token that is missing in front of the next token. It is right behind the previous token,
+    /// so diagnostics point at the end of its line instead of the start of the next one.
+    pub(crate) fn offset(&self) -> usize {
+        self.previous_end
     }
 }
 
-impl Parser {
-    pub fn parse(&mut self) -> CompilationUnit {
-        let trivia = self.eat_trivia();
+impl<'src> Parser<'src> {
+    pub fn parse(mut self) -> CompilationUnit<'src> {
         let statements = self.parse_statements();
-        CompilationUnit { leading_trivia: trivia, statements }
+        CompilationUnit { statements, end_of_file: syntax_token(self.end_of_file) }
     }
 
-    fn parse_statements(&mut self) -> Vec<Stat> {
+    fn parse_statements(&mut self) -> Vec<Stat<'src>> {
         let mut statements = vec![];
         while self.peek().is_some() {
             statements.push(self.parse_statement());
@@ -130,14 +157,16 @@ impl Parser {
         statements
     }
 
-    fn parse_statement(&mut self) -> Stat {
-        let next = self.peek();
-        
-        if next.is_none() {
-            todo!("early return some indication of end of tokens here!");
-        }
+    fn parse_statement(&mut self) -> Stat<'src> {
+        let next = match self.peek() {
+            Some(next) => next,
+            None => {
+                let missing = self.missing_token(TokenKind::Unknown, ErrorKind::ExpectedStatement{ actual: None });
+                return Stat::Error{ tokens: vec![missing] };
+            },
+        };
 
-        match next.unwrap().kind {
+        match next.kind {
             TokenKind::LetKeyword => self.parse_var_definition(),
             TokenKind::LBrace => self.parse_block_statement(),
             TokenKind::IfKeyword => self.parse_if_statement(),
@@ -146,35 +175,73 @@ impl Parser {
             TokenKind::WhileKeyword => self.parse_while_statement(),
             TokenKind::FunctionKeyword => self.parse_function_definition(),
             TokenKind::ForKeyword => self.parse_for_statement(),
+            TokenKind::StructKeyword => self.parse_struct_definition(),
+            TokenKind::EnumKeyword => self.parse_enum_definition(),
+            TokenKind::ImplKeyword => self.parse_impl_block(),
+            TokenKind::TraitKeyword => self.parse_trait_definition(),
+            TokenKind::Hash => {
+                let attributes_len = self.attributes_len();
+                match self.peek_n(attributes_len).map(|token| token.kind) {
+                    Some(TokenKind::LetKeyword) => self.parse_var_definition(),
+                    Some(TokenKind::FunctionKeyword) => self.parse_function_definition(),
+                    Some(TokenKind::StructKeyword) => self.parse_struct_definition(),
+                    Some(TokenKind::EnumKeyword) => self.parse_enum_definition(),
+                    // a # without [ is no attribute at all
+                    _ if attributes_len == 0 => self.parse_error_statement(),
+                    // attributes can only be put on declarations
+                    _ => {
+                        let mut tokens: Vec<_> = (0..attributes_len).map(|_| self.parse_token()).collect();
+                        tokens[0].diagnostics.push(Diagnostic{ kind: ErrorKind::MisplacedAttribute });
+                        Stat::Error{ tokens }
+                    },
+                }
+            },
             _ if is_possible_expression(&self.peek()) => self.parse_expression_statement(),
-            _ => {
-                let _unexpected_token = self.eat();
-                let statement = self.parse_statement();
-                // TODO: unwrap statment until a valid statement or end of tokens is found 
-                // then aggregate the tokens together and add them in diagnostics before returning
-                statement
+            _ => self.parse_error_statement(),
+        }
+    }
+
+    fn parse_error_statement(&mut self) -> Stat<'src> {
+        let actual = self.peek();
+        self.parse_skipped_statement(ErrorKind::ExpectedStatement{ actual })
+    }
+
+    /// Skips tokens up to the next point the parser can continue from: behind a ';', or in front of a '}' or a statement keyword.
+    /// There has to be a next token, it is always skipped, so the parser makes progress. The first token holds the error.
+    fn parse_skipped_statement(&mut self, error: ErrorKind<'src>) -> Stat<'src> {
+        let mut first = self.parse_token();
+        first.diagnostics.push(Diagnostic{ kind: error });
+
+        let mut tokens = vec![first];
+        while let Some(next) = self.peek() {
+            let after_semicolon = tokens.last().is_some_and(|token| token.token.kind == TokenKind::Semicolon);
+            if after_semicolon || next.kind == TokenKind::RBrace || next.kind == TokenKind::Hash || is_statement_keyword(next.kind) {
+                break;
             }
+            tokens.push(self.parse_token());
         }
+
+        Stat::Error{ tokens }
     }
 
-    fn parse_for_statement(&mut self) -> Stat {        
+    fn parse_for_statement(&mut self) -> Stat<'src> {        
         let for_token = self.parse_token();
         let identifier = self.parse_identifier();
         let in_token = self.parse_expected_token(TokenKind::InKeyword);
-        let range = self.parse_expression();
+        let range = self.parse_condition();
         let statement = Box::new(self.parse_statement());
 
         Stat::For{ for_token, identifier, in_token, range, statement }
     }
 
-    fn parse_expression_statement(&mut self) -> Stat {
+    fn parse_expression_statement(&mut self) -> Stat<'src> {
         let expr = self.parse_expression();
         let semicolon_token = self.parse_expected_token(TokenKind::Semicolon);
 
         Stat::Expr{ expr, semicolon_token }
     }
 
-    fn parse_return_statement(&mut self) -> Stat {        
+    fn parse_return_statement(&mut self) -> Stat<'src> {        
         let return_token = self.parse_token();
         let expression = if !is_some_and_kind(&self.peek(), TokenKind::Semicolon) {
             Some(self.parse_expression())
@@ -187,26 +254,26 @@ impl Parser {
         Stat::Return{return_token, expression, semicolon_token}
     }
 
-    fn parse_break_statement(&mut self) -> Stat {        
+    fn parse_break_statement(&mut self) -> Stat<'src> {        
         let break_token = self.parse_token();
         let semicolon_token = self.parse_expected_token(TokenKind::Semicolon);
 
         Stat::Break{break_token, semicolon_token}
     }
 
-    fn parse_while_statement(&mut self) -> Stat {        
+    fn parse_while_statement(&mut self) -> Stat<'src> {        
         let while_token = self.parse_token();
 
-        let expression = self.parse_expression();
+        let expression = self.parse_condition();
         let statement = self.parse_statement();
 
         Stat::While{while_token, expression, statement: Box::new(statement)}
     }
 
-    fn parse_if_statement(&mut self) -> Stat {        
+    fn parse_if_statement(&mut self) -> Stat<'src> {        
         let if_token = self.parse_token();
 
-        let expression = self.parse_expression();
+        let expression = self.parse_condition();
         let statement = self.parse_statement();
         
         let else_statement = self.parse_else_statement();
@@ -214,7 +281,7 @@ impl Parser {
         Stat::If{if_token, expression, statement: Box::new(statement), else_statement}
     }
 
-    fn parse_else_statement(&mut self) -> Option<Box<Stat>> { 
+    fn parse_else_statement(&mut self) -> Option<Box<Stat<'src>>> { 
         if is_some_and_kind(&self.peek(), TokenKind::ElseKeyword) {        
             let else_token = self.parse_token();
             let statement = self.parse_statement();
@@ -225,11 +292,12 @@ impl Parser {
         }
     }
 
-    fn parse_block_statement(&mut self) -> Stat {        
+    fn parse_block_statement(&mut self) -> Stat<'src> {        
         let mut statements = vec![];
-        let l_brace = self.parse_token();
+        let has_l_brace = is_some_and_kind(&self.peek(), TokenKind::LBrace);
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
 
-        while !is_some_and_kind(&self.peek(), TokenKind::RBrace) {
+        while has_l_brace && self.peek().is_some_and(|token| token.kind != TokenKind::RBrace) {
             statements.push(self.parse_statement());
         }
 
@@ -238,24 +306,32 @@ impl Parser {
         Stat::Block{l_brace, statements, r_brace}
     }
 
-    fn parse_function_definition(&mut self) -> Stat {
+    fn parse_function_definition(&mut self) -> Stat<'src> {
+        let attributes = self.parse_attributes();
+
         let fn_token = self.parse_token();
 
         let identifier = self.parse_identifier();
 
+        let generic_parameters = self.parse_generic_parameters();
+
         let parameter_list = self.parse_parameter_list();
 
         let return_type = self.parse_function_return_type();
 
         let body = self.parse_function_body();
 
-        Stat::FunctionDefinition{ fn_token, identifier, parameter_list, return_type, body }
+        Stat::FunctionDefinition{ attributes, fn_token, identifier, generic_parameters, parameter_list, return_type, body }
     }
 
 
 
-    fn parse_function_body(&mut self) -> Box<FunctionBody> {
-        if is_some_and_kind(&self.peek(), TokenKind::EqualsGreater) {
+    fn parse_function_body(&mut self) -> Box<FunctionBody<'src>> {
+        if is_some_and_kind(&self.peek(), TokenKind::Semicolon) {
+            let semicolon_token = self.parse_token();
+            Box::new(FunctionBody::Signature{ semicolon_token })
+        }
+        else if is_some_and_kind(&self.peek(), TokenKind::EqualsGreater) {
             let fat_arrow_token = self.parse_token();
             let statement = self.parse_statement();
             Box::new(FunctionBody::ExpressionBody{ fat_arrow_token, statement })
@@ -266,7 +342,7 @@ impl Parser {
         }
     }
 
-    fn parse_function_return_type(&mut self) -> Option<ReturnType> {
+    fn parse_function_return_type(&mut self) -> Option<ReturnType<'src>> {
         if is_some_and_kind(&self.peek(), TokenKind::MinusGreater) {
             let small_arrow_token = self.parse_token();
             let type_kind = self.parse_type();
@@ -277,22 +353,46 @@ impl Parser {
         }
     }
 
-    fn parse_parameter_list(&mut self) -> ParameterList {
-        let l_paran = self.parse_token();
+    fn parse_parameter_list(&mut self) -> ParameterList<'src> {
+        let l_paran = self.parse_expected_token(TokenKind::LParen);
+
+        let receiver = self.parse_self_parameter();
 
         let parameters = self.parse_parameters();
 
         let r_paran = self.parse_expected_token(TokenKind::RParen);
 
-        ParameterList{ l_paran, parameters, r_paran }
+        ParameterList{ l_paran, receiver, parameters, r_paran }
+    }
+
+    /// self or mut self, only the first parameter can be the receiver
+    fn parse_self_parameter(&mut self) -> Option<SelfParameter<'src>> {
+        let mut_token = match self.peek().map(|token| token.kind) {
+            Some(TokenKind::SelfKeyword) => None,
+            Some(TokenKind::MutKeyword) if is_some_and_kind(&self.peek_n(1), TokenKind::SelfKeyword) => Some(self.parse_token()),
+            _ => return None,
+        };
+        let self_token = self.parse_token();
+        let comma_token = self.parse_optional_comma();
+
+        Some(SelfParameter{ mut_token, self_token, comma_token })
     }
     
-    fn parse_parameters(&mut self) -> Vec<Parameter> {
+    fn parse_parameters(&mut self) -> Vec<Parameter<'src>> {
         let mut parameters = Vec::<Parameter>::new();
 
         while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
             let identifier = self.parse_identifier();
-            let type_id = self.parse_type_id().unwrap();
+            let type_id = match self.parse_type_id() {
+                Some(type_id) => type_id,
+                None => {
+                    // the missing ':' already reports the missing type
+                    let colon_token = self.parse_expected_token(TokenKind::Colon);
+                    let token = Token{ kind: TokenKind::Identifier, len: 0, value: "", offset: self.offset() };
+                    let identifier = SyntaxToken{ leading_trivia: vec![], token, trailing_trivia: vec![], diagnostics: vec![] };
+                    TypeId{ colon_token, type_kind: TypeKind::UserDefined{ identifier: Identifier{ identifier }, generic_arguments: None } }
+                },
+            };
             
             let comma_token = if is_some_and_kind(&self.peek(), TokenKind::Comma) {
                 Some(self.parse_token())
@@ -307,7 +407,133 @@ impl Parser {
         parameters
     }
 
-    fn parse_var_definition(&mut self) -> Stat {
+    fn parse_struct_definition(&mut self) -> Stat<'src> {
+        let attributes = self.parse_attributes();
+        let struct_token = self.parse_token();
+        let identifier = self.parse_identifier();
+        let generic_parameters = self.parse_generic_parameters();
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
+        let fields = self.parse_field_definitions();
+        let r_brace = self.parse_expected_token(TokenKind::RBrace);
+
+        Stat::StructDefinition{ attributes, struct_token, identifier, generic_parameters, l_brace, fields, r_brace }
+    }
+
+    /// the fields of a struct or of a struct variant, up to their '}'
+    fn parse_field_definitions(&mut self) -> Vec<FieldDefinition<'src>> {
+        let mut fields = vec![];
+        while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+            let identifier = self.parse_identifier();
+            let colon_token = self.parse_expected_token(TokenKind::Colon);
+            let type_kind = self.parse_type();
+            let comma_token = self.parse_optional_comma();
+
+            fields.push(FieldDefinition{ identifier, type_id: TypeId{ colon_token, type_kind }, comma_token });
+        }
+
+        fields
+    }
+
+    fn parse_enum_definition(&mut self) -> Stat<'src> {
+        let attributes = self.parse_attributes();
+        let enum_token = self.parse_token();
+        let identifier = self.parse_identifier();
+        let generic_parameters = self.parse_generic_parameters();
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
+
+        let mut variants = vec![];
+        while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+            let identifier = self.parse_identifier();
+            let payload = match self.peek().map(|token| token.kind) {
+                Some(TokenKind::LParen) => {
+                    let l_paran = self.parse_token();
+                    let mut fields = vec![];
+                    while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+                        let type_kind = self.parse_type();
+                        let comma_token = self.parse_optional_comma();
+                        fields.push(TupleField{ type_kind, comma_token });
+                    }
+                    let r_paran = self.parse_expected_token(TokenKind::RParen);
+                    Some(VariantPayload::Tuple{ l_paran, fields, r_paran })
+                },
+                Some(TokenKind::LBrace) => {
+                    let l_brace = self.parse_token();
+                    let fields = self.parse_field_definitions();
+                    let r_brace = self.parse_expected_token(TokenKind::RBrace);
+                    Some(VariantPayload::Struct{ l_brace, fields, r_brace })
+                },
+                _ => None,
+            };
+            let comma_token = self.parse_optional_comma();
+
+            variants.push(EnumVariant{ identifier, payload, comma_token });
+        }
+
+        let r_brace = self.parse_expected_token(TokenKind::RBrace);
+
+        Stat::EnumDefinition{ attributes, enum_token, identifier, generic_parameters, l_brace, variants, r_brace }
+    }
+
+    fn parse_impl_block(&mut self) -> Stat<'src> {
+        let impl_token = self.parse_token();
+        let trait_for = if is_some_and_kind(&self.peek(), TokenKind::Identifier) && is_some_and_kind(&self.peek_n(1), TokenKind::ForKeyword) {
+            let identifier = self.parse_identifier();
+            let for_token = self.parse_token();
+            Some(TraitFor{ identifier, for_token })
+        }
+        else {
+            None
+        };
+        let type_kind = self.parse_type();
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
+        let functions = self.parse_function_items();
+        let r_brace = self.parse_expected_token(TokenKind::RBrace);
+
+        Stat::ImplBlock{ impl_token, trait_for, type_kind, l_brace, functions, r_brace }
+    }
+
+    fn parse_trait_definition(&mut self) -> Stat<'src> {
+        let trait_token = self.parse_token();
+        let identifier = self.parse_identifier();
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
+        let functions = self.parse_function_items();
+        let r_brace = self.parse_expected_token(TokenKind::RBrace);
+
+        Stat::TraitDefinition{ trait_token, identifier, l_brace, functions, r_brace }
+    }
+
+    /// the functions of an impl block or trait up to their '}', everything else is skipped
+    fn parse_function_items(&mut self) -> Vec<Stat<'src>> {
+        let mut functions = vec![];
+        while let Some(next) = self.peek().filter(|token| token.kind != TokenKind::RBrace) {
+            let is_function = match next.kind {
+                TokenKind::FunctionKeyword => true,
+                TokenKind::Hash => self.peek_n(self.attributes_len()).is_some_and(|token| token.kind == TokenKind::FunctionKeyword),
+                _ => false,
+            };
+
+            if is_function {
+                functions.push(self.parse_function_definition());
+            }
+            else {
+                functions.push(self.parse_skipped_statement(ErrorKind::ExpectedFunction{ actual: Some(next) }));
+            }
+        }
+
+        functions
+    }
+
+    fn parse_optional_comma(&mut self) -> Option<SyntaxToken<'src>> {
+        if is_some_and_kind(&self.peek(), TokenKind::Comma) {
+            Some(self.parse_token())
+        }
+        else {
+            None
+        }
+    }
+
+    fn parse_var_definition(&mut self) -> Stat<'src> {
+        let attributes = self.parse_attributes();
         let let_token = self.parse_token();
 
         let mut_token = if is_some_and_kind(&self.peek(), TokenKind::MutKeyword) {
@@ -323,10 +549,101 @@ impl Parser {
 
         let semicolon_token = self.parse_expected_token(TokenKind::Semicolon);
 
-        Stat::VarDefinition{ let_token, mut_token, identifier, type_id, initial_value, semicolon_token }
+        Stat::VarDefinition{ attributes, let_token, mut_token, identifier, type_id, initial_value, semicolon_token }
+    }
+
+    /// the number of tokens of the #[...] in front of the next statement
+    fn attributes_len(&self) -> usize {
+        let mut len = 0;
+        while is_some_and_kind(&self.peek_n(len), TokenKind::Hash) && is_some_and_kind(&self.peek_n(len + 1), TokenKind::LBracket) {
+            len += 1;
+            let mut depth = 0;
+            loop {
+                match self.peek_n(len).map(|token| token.kind) {
+                    Some(TokenKind::LBracket) | Some(TokenKind::QuestionLBracket) => depth += 1,
+                    Some(TokenKind::RBracket) => depth -= 1,
+                    Some(_) => {},
+                    None => return len,
+                }
+                len += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+
+        len
     }
 
-    fn parse_equals_value(&mut self) -> Option<EqualsValue> {
+    fn parse_attributes(&mut self) -> Vec<Attribute<'src>> {
+        let mut attributes = vec![];
+        while is_some_and_kind(&self.peek(), TokenKind::Hash) {
+            attributes.push(self.parse_attribute());
+        }
+
+        attributes
+    }
+
+    fn parse_attribute(&mut self) -> Attribute<'src> {
+        let hash_token = self.parse_token();
+        let l_bracket = self.parse_expected_token(TokenKind::LBracket);
+        let mut identifier = self.parse_identifier();
+        let arguments = if is_some_and_kind(&self.peek(), TokenKind::LParen) {
+            Some(self.parse_attribute_argument_list())
+        }
+        else {
+            None
+        };
+        let r_bracket = self.parse_expected_token(TokenKind::RBracket);
+
+        let name = identifier.identifier.token.value;
+        if !name.is_empty() && !KNOWN_ATTRIBUTES.contains(&name) {
+            identifier.identifier.diagnostics.push(Diagnostic{ kind: ErrorKind::UnknownAttribute{ name } });
+        }
+
+        Attribute{ hash_token, l_bracket, identifier, arguments, r_bracket }
+    }
+
+    fn parse_attribute_argument_list(&mut self) -> AttributeArgumentList<'src> {
+        let mut arguments = vec![];
+        let l_paran = self.parse_token();
+
+        while is_possible_expression(&self.peek()) {
+            let kind = if is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+                let identifier = self.parse_identifier();
+                if is_some_and_kind(&self.peek(), TokenKind::Equal) {
+                    let equals_token = self.parse_token();
+                    let value = self.parse_expression();
+                    AttributeArgumentKind::KeyValue{ key: identifier, equals_token, value: Box::new(value) }
+                }
+                else if is_some_and_kind(&self.peek(), TokenKind::LParen) {
+                    AttributeArgumentKind::Nested{ identifier, arguments: Some(self.parse_attribute_argument_list()) }
+                }
+                else {
+                    AttributeArgumentKind::Nested{ identifier, arguments: None }
+                }
+            }
+            else {
+                AttributeArgumentKind::Literal{ expr: Box::new(self.parse_expression()) }
+            };
+
+            // the comma is only optional behind the last argument
+            let comma_token = if is_possible_expression(&self.peek()) {
+                Some(self.parse_expected_token(TokenKind::Comma))
+            }
+            else {
+                self.parse_optional_comma()
+            };
+
+            arguments.push(AttributeArgument{ kind, comma_token });
+        }
+
+        let r_paran = self.parse_expected_token(TokenKind::RParen);
+
+        AttributeArgumentList{ l_paran, arguments, r_paran }
+    }
+
+    fn parse_equals_value(&mut self) -> Option<EqualsValue<'src>> {
         if !is_some_and_some_kind(&self.peek(), ASSIGNMENT_TOKENS.iter()) {
             return None;
         }
@@ -337,40 +654,146 @@ impl Parser {
         Some(EqualsValue{ equals_token, expression })
     }
 
-    fn parse_type(&mut self) -> TypeKind {
+    fn parse_type(&mut self) -> TypeKind<'src> {
+        if is_some_and_kind(&self.peek(), TokenKind::ImplKeyword) {
+            let impl_token = self.parse_token();
+            let bounds = self.parse_trait_bounds();
+            return TypeKind::ImplTrait{ impl_token, bounds };
+        }
+
         let identifier = self.parse_identifier();
-        if INT_TYPES.contains(&identifier.identifier.token.value.as_str()) 
-        || FLOAT_TYPES.contains(&identifier.identifier.token.value.as_str()) 
-        || STRING_TYPES.contains(&identifier.identifier.token.value.as_str())
-        || CHAR_TYPES.contains(&identifier.identifier.token.value.as_str())
-        || BOOL_TYPES.contains(&identifier.identifier.token.value.as_str()) {
+        if INT_TYPES.contains(&identifier.identifier.token.value) 
+        || FLOAT_TYPES.contains(&identifier.identifier.token.value) 
+        || STRING_TYPES.contains(&identifier.identifier.token.value)
+        || CHAR_TYPES.contains(&identifier.identifier.token.value)
+        || BOOL_TYPES.contains(&identifier.identifier.token.value) {
             TypeKind::Internal{identifier}
         }
         else {
-            TypeKind::UserDefined{identifier}
+            let generic_arguments = if is_some_and_kind(&self.peek(), TokenKind::Less) {
+                Some(Box::new(self.parse_generic_arguments()))
+            }
+            else {
+                None
+            };
+            TypeKind::UserDefined{identifier, generic_arguments}
         }
     }
 
-    fn parse_expression(&mut self) -> Expr {
+    /// Shape + Named, there is always at least one trait
+    fn parse_trait_bounds(&mut self) -> Vec<TraitBound<'src>> {
+        let mut bounds = vec![];
+        loop {
+            let identifier = self.parse_identifier();
+            if !is_some_and_kind(&self.peek(), TokenKind::Plus) {
+                bounds.push(TraitBound{ identifier, plus_token: None });
+                break;
+            }
+            bounds.push(TraitBound{ identifier, plus_token: Some(self.parse_token()) });
+        }
+
+        bounds
+    }
+
+    /// <T: Shape, U> behind the name of a declaration, if there is a '<'
+    fn parse_generic_parameters(&mut self) -> Option<GenericParameterList<'src>> {
+        if !is_some_and_kind(&self.peek(), TokenKind::Less) {
+            return None;
+        }
+
+        let less_token = self.parse_token();
+        let mut parameters = vec![];
+        while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+            let identifier = self.parse_identifier();
+            let bounds = if is_some_and_kind(&self.peek(), TokenKind::Colon) {
+                let colon_token = self.parse_token();
+                let bounds = self.parse_trait_bounds();
+                Some(GenericBounds{ colon_token, bounds })
+            }
+            else {
+                None
+            };
+            let comma_token = self.parse_optional_comma();
+
+            parameters.push(GenericParameter{ identifier, bounds, comma_token });
+        }
+        let greater_token = self.parse_closing_angle();
+
+        Some(GenericParameterList{ less_token, parameters, greater_token })
+    }
+
+    /// <int, List<T>>, the next token is the '<'
+    fn parse_generic_arguments(&mut self) -> GenericArgumentList<'src> {
+        let less_token = self.parse_token();
+        let mut arguments = vec![];
+        while is_some_and_some_kind(&self.peek(), [TokenKind::Identifier, TokenKind::ImplKeyword].iter()) {
+            let type_kind = self.parse_type();
+            let comma_token = self.parse_optional_comma();
+
+            arguments.push(GenericArgument{ type_kind, comma_token });
+        }
+        let greater_token = self.parse_closing_angle();
+
+        GenericArgumentList{ less_token, arguments, greater_token }
+    }
+
+    /// The '>' that closes generics. The lexer reads the end of List<List<int>> as '>>', so '>>', '>=' and '>>=' are split
+    /// in front of their first '>'. The trivia in front stays with the '>', the trivia behind moves to the rest.
+    fn parse_closing_angle(&mut self) -> SyntaxToken<'src> {
+        let rest_kind = match self.peek().map(|token| token.kind) {
+            Some(TokenKind::GreaterGreater) => Some(TokenKind::Greater),
+            Some(TokenKind::GreaterEqual) => Some(TokenKind::Equal),
+            Some(TokenKind::GreaterGreaterEqual) => Some(TokenKind::GreaterEqual),
+            _ => None,
+        };
+
+        if let Some(rest_kind) = rest_kind {
+            let mut greater = self.tokens.pop_front().unwrap();
+            let Token{ value, offset, .. } = greater.token;
+            let rest = Token{ kind: rest_kind, value: &value[1..], len: value.len() - 1, offset: offset + 1 };
+            let rest = TokenWithTrivia{ leading_trivia: vec![], token: rest, trailing_trivia: std::mem::take(&mut greater.trailing_trivia) };
+            greater.token = Token{ kind: TokenKind::Greater, value: &value[..1], len: 1, offset };
+
+            self.tokens.push_front(rest);
+            self.tokens.push_front(greater);
+        }
+
+        self.parse_expected_token(TokenKind::Greater)
+    }
+
+    fn parse_expression(&mut self) -> Expr<'src> {
         self.parse_expression_bp(0)
     }
 
-    fn parse_expression_bp(&mut self, min_bp: u8) -> Expr {
+    /// the expression in front of the block of an if, while, for or match. Struct literals need parentheses there
+    fn parse_condition(&mut self) -> Expr<'src> {
+        let struct_literals = std::mem::replace(&mut self.struct_literals, false);
+        let expr = self.parse_expression();
+        self.struct_literals = struct_literals;
+        expr
+    }
+
+    /// an expression between brackets, struct literals are always allowed there
+    fn parse_nested_expression(&mut self) -> Expr<'src> {
+        let struct_literals = std::mem::replace(&mut self.struct_literals, true);
+        let expr = self.parse_expression();
+        self.struct_literals = struct_literals;
+        expr
+    }
+
+    fn parse_expression_bp(&mut self, min_bp: u8) -> Expr<'src> {
         // for more information: https://en.wikipedia.org/wiki/Operator-precedence_parser
         // based on: https://matklad.github.io/2020/04/13/simple-but-powerful-pratt-parsing.html
 
-        let mut lhs = if is_operator(&self.peek()) 
-                            // special rule for lbracket, since they are used for array initializers!
-                            && self.peek().unwrap().kind != TokenKind::LBracket {
-
+        // lbracket is no prefix operator, since they are used for array initializers!
+        let mut lhs = if let Some(((), r_bp)) = self.peek().and_then(|token| prefix_binding_power(token.kind)) {
             let op = self.parse_token();
-            let ((), r_bp) = prefix_binding_power(op.token.kind);
             let rhs = self.parse_expression_bp(r_bp);
             bake_unary_expression(op, rhs)
         }
         else if is_some_and_kind(&self.peek(), TokenKind::LParen) {
             let lparen = self.parse_token();
-            let expr = self.parse_expression_bp(0);
+            let expr = self.parse_nested_expression();
             let rparen = self.parse_expected_token(TokenKind::RParen);
 
             decorate_expression(lparen, rparen, expr)
@@ -389,12 +812,18 @@ impl Parser {
                     break;
                 }
 
+                if is_some_and_kind(&self.peek(), TokenKind::LParen) {
+                    let argument_list = self.parse_argument_list();
+                    lhs = Expr::Call{ callee: Box::new(lhs), argument_list };
+                    continue;
+                }
+
                 let op = self.parse_token();
 
                 lhs = if op.token.kind == TokenKind::LBracket 
                         || op.token.kind == TokenKind::QuestionLBracket {
                     let lbracket = op;
-                    let expr = self.parse_expression_bp(0);
+                    let expr = self.parse_nested_expression();
                     let rbracket = self.parse_expected_token(TokenKind::RBracket);
                     Expr::Index{ lhs: Box::new(lhs), lbracket, expr: Box::new(expr), rbracket }
                 } else {
@@ -410,7 +839,19 @@ impl Parser {
                 
                 let op = self.parse_token();
                 
-                lhs = if op.token.kind == TokenKind::Question {
+                lhs = if op.token.kind == TokenKind::Dot || op.token.kind == TokenKind::QuestionDot {
+                    // tuple.0 indexes the fields of a tuple by their position
+                    let field = if self.peek().is_some_and(is_tuple_index) {
+                        Identifier{ identifier: self.parse_token() }
+                    }
+                    else {
+                        self.parse_identifier()
+                    };
+                    Expr::FieldAccess{ expr: Box::new(lhs), dot_token: op, field }
+                } else if op.token.kind == TokenKind::ColonColon && is_some_and_kind(&self.peek(), TokenKind::Less) {
+                    let generic_arguments = self.parse_generic_arguments();
+                    Expr::Turbofish{ expr: Box::new(lhs), colon_colon_token: op, generic_arguments }
+                } else if op.token.kind == TokenKind::Question {
                 let mhs = self.parse_expression_bp(0);
                 let op2 = self.parse_expected_token(TokenKind::Colon);
                 let rhs = self.parse_expression_bp(r_bp);
@@ -429,20 +870,17 @@ impl Parser {
         lhs
     }
 
-    fn parse_expression_atom(&mut self) -> Expr {
+    fn parse_expression_atom(&mut self) -> Expr<'src> {
         if let Some(expr) = self.peek() {
             match expr.kind {
                 TokenKind::NumberLiteral{..} => {
+                    // the type suffix (5_i32) is part of the number literal token
                     let number_literal =  self.parse_token();
-                    let type_kind = if is_some_and_kind(&self.peek(), TokenKind::Identifier) {
-                        Some(self.parse_type())
-                    }
-                    else {
-                        None
-                    };
-                    Expr::Literal{ kind: LiteralKind::Number{ number_literal }, type_kind }
+                    Expr::Literal{ kind: LiteralKind::Number{ number_literal }, type_kind: None }
                 },
-                TokenKind::StringLiteral{..} => {
+                TokenKind::StringLiteral{..}
+                | TokenKind::RawStringLiteral{..}
+                | TokenKind::MultilineStringLiteral{..} => {
                     let string_literal =  self.parse_token();
                     let type_kind = if is_some_and_kind(&self.peek(), TokenKind::Identifier) {
                         Some(self.parse_type())
@@ -452,6 +890,7 @@ impl Parser {
                     };
                     Expr::Literal{ kind: LiteralKind::String{ string_literal }, type_kind }
                 },
+                TokenKind::InterpolatedStringLiteral{..} => self.parse_interpolated_string(),
                 TokenKind::CharLiteral{..} => {
                     let char_literal =  self.parse_token();
                     let type_kind = if is_some_and_kind(&self.peek(), TokenKind::Identifier) {
@@ -474,34 +913,69 @@ impl Parser {
                 },
                 TokenKind::LBracket => self.parse_array_initializer(),
                 TokenKind::MatchKeyword => self.parse_match_expression(),
+                TokenKind::SelfKeyword => Expr::IdentifierUsage{ identifier: Identifier{ identifier: self.parse_token() } },
                 TokenKind::Identifier{..} => {
                     let identifier = self.parse_identifier();
                     
-                    if let Some(expr) = self.peek() {
-                        match expr.kind {
-                            TokenKind::LParen => self.parse_call(identifier),
-                            _ => Expr::IdentifierUsage{ identifier },
-                        }
+                    if self.struct_literals && is_some_and_kind(&self.peek(), TokenKind::LBrace) {
+                        self.parse_struct_literal(identifier)
                     }
                     else {
-                        panic!("unexpected end of stream")
+                        Expr::IdentifierUsage{ identifier }
                     }
                 },
-                _ => panic!("unknown expression type {:?}", expr),
+                _ => self.parse_error_expression(),
             }
         }
         else {
-            panic!("unexpected end of stream")
+            self.parse_error_expression()
         }
     }
 
-    fn parse_array_initializer(&mut self) -> Expr {
+    /// Skips the next token, unless the parser can continue from it. Closing brackets, separators and statement keywords are left for the enclosing node.
+    fn parse_error_expression(&mut self) -> Expr<'src> {
+        let actual = self.peek();
+        let diagnostic = Diagnostic{ kind: ErrorKind::ExpectedExpression{ actual } };
+        let token = match actual {
+            Some(token) if !is_expression_end(token.kind) => {
+                let mut token = self.parse_token();
+                token.diagnostics.push(diagnostic);
+                token
+            },
+            _ => self.missing_token(TokenKind::Unknown, diagnostic.kind),
+        };
+
+        Expr::Error{ tokens: vec![token] }
+    }
+
+    fn parse_interpolated_string(&mut self) -> Expr<'src> {
+        let TokenWithTrivia{ leading_trivia, token, trailing_trivia } = self.eat().unwrap();
+        let InterpolatedString{ start, parts, end } = split_interpolated_string_with_edition(&token, self.edition);
+
+        // the trivia of the literal belongs to its quotes
+        let mut start = bare_syntax_token(start);
+        start.leading_trivia = leading_trivia.into_iter().map(trivia).collect();
+        let parts = parts.into_iter().map(|part| match part {
+            ferrousc_lexer::InterpolatedPart::Text{ text } => InterpolatedPart::Text{ text: bare_syntax_token(text) },
+            ferrousc_lexer::InterpolatedPart::Hole{ l_brace, tokens, r_brace } => parse_interpolation_hole(l_brace, tokens, r_brace, self.edition),
+        }).collect();
+
+        let mut end = match end {
+            Some(end) => bare_syntax_token(end),
+            None => self.parse_expected_token(TokenKind::InterpolatedStringEnd),
+        };
+        end.trailing_trivia = trailing_trivia.into_iter().map(trivia).collect();
+
+        Expr::Interpolated{ start, parts, end }
+    }
+
+    fn parse_array_initializer(&mut self) -> Expr<'src> {
         let mut items = Vec::<InitializerItem>::new();
 
         let lbracket = self.parse_token();
 
         while is_possible_expression(&self.peek()) {
-            let expr = self.parse_expression();
+            let expr = self.parse_nested_expression();
             
             let comma_token = if is_some_and_kind(&self.peek(), TokenKind::Comma) {
                 Some(self.parse_token())
@@ -518,17 +992,17 @@ impl Parser {
         Expr::ArrayInitializer{ lbracket, items, rbracket }
     }
 
-    fn parse_match_expression(&mut self) -> Expr {
+    fn parse_match_expression(&mut self) -> Expr<'src> {
         let match_token = self.parse_token();
-        let expr = Box::new(self.parse_expression());
+        let expr = Box::new(self.parse_condition());
         let body = self.parse_match_body();
         
         Expr::Match{ match_token, expr, body }
     }
 
-    fn parse_match_body(&mut self) -> MatchBody {
+    fn parse_match_body(&mut self) -> MatchBody<'src> {
         let mut arms = Vec::<MatchArm>::new();
-        let l_brace = self.parse_token();
+        let l_brace = self.parse_expected_token(TokenKind::LBrace);
 
         while is_possible_match_arm(&self.peek()) {
             arms.push(self.parse_match_arm());
@@ -539,20 +1013,21 @@ impl Parser {
         MatchBody{ l_brace, r_brace, arms }
     }
 
-    fn parse_match_arm(&mut self) -> MatchArm {
-        let pattern = match self.peek().as_ref().unwrap().kind {
-            TokenKind::StringLiteral{..}
-            | TokenKind::NumberLiteral{..}
-            | TokenKind::CharLiteral{..}
-            | TokenKind::FalseKeyword 
-            | TokenKind::TrueKeyword => MatchPattern::Literal(self.parse_token()),
-            TokenKind::Identifier => MatchPattern::Identifier(self.parse_identifier()),
-            _ => panic!("unexpected token found when trying to parse match pattern {:?}", self.peek()),
+    fn parse_match_arm(&mut self) -> MatchArm<'src> {
+        let pattern = self.parse_pattern();
+
+        let guard = if is_some_and_kind(&self.peek(), TokenKind::IfKeyword) {
+            let if_token = self.parse_token();
+            let expr = self.parse_nested_expression();
+            Some(MatchGuard{ if_token, expr })
+        }
+        else {
+            None
         };
 
         let fat_arrow = self.parse_expected_token(TokenKind::EqualsGreater);
 
-        let expr = self.parse_expression();
+        let expr = self.parse_nested_expression();
 
         let comma_token = if is_some_and_kind(&self.peek(), TokenKind::Comma) {
             Some(self.parse_token())
@@ -561,20 +1036,138 @@ impl Parser {
             None
         };
 
-        MatchArm{ pattern, fat_arrow, expr, comma_token }
+        MatchArm{ pattern, guard, fat_arrow, expr, comma_token }
+    }
+
+    /// a pattern with all of its alternatives, a | b | c is ((a | b) | c)
+    fn parse_pattern(&mut self) -> MatchPattern<'src> {
+        let mut pattern = self.parse_single_pattern();
+        while is_some_and_kind(&self.peek(), TokenKind::Bar) {
+            let bar_token = self.parse_token();
+            let rhs = self.parse_single_pattern();
+            pattern = MatchPattern::Or{ lhs: Box::new(pattern), bar_token, rhs: Box::new(rhs) };
+        }
+
+        pattern
+    }
+
+    fn parse_single_pattern(&mut self) -> MatchPattern<'src> {
+        match self.peek() {
+            Some(token) if is_literal_pattern(token.kind) => {
+                let start = self.parse_token();
+                if !is_some_and_some_kind(&self.peek(), [TokenKind::DotDot, TokenKind::DotDotEqual].iter()) {
+                    return MatchPattern::Literal(start);
+                }
+
+                let op = self.parse_token();
+                let end = match self.peek() {
+                    Some(token) if is_literal_pattern(token.kind) => self.parse_token(),
+                    _ => self.parse_expected_token(start.token.kind),
+                };
+                MatchPattern::Range{ start, op, end }
+            },
+            Some(token) if token.kind == TokenKind::Identifier && token.value == "_" => MatchPattern::Wildcard(self.parse_token()),
+            Some(token) if token.kind == TokenKind::Identifier => {
+                let is_variant = is_some_and_some_kind(&self.peek_n(1), [TokenKind::ColonColon, TokenKind::LParen, TokenKind::LBrace].iter());
+                // default is a keyword only as the whole pattern, Default::Value is still a path
+                if !is_variant && ContextualKeyword::from_identifier(token.value, self.edition) == Some(ContextualKeyword::Default) {
+                    return MatchPattern::Wildcard(self.parse_token());
+                }
+                if !is_variant {
+                    return MatchPattern::Identifier(self.parse_identifier());
+                }
+
+                let path = self.parse_path();
+                let payload = self.parse_pattern_payload();
+                MatchPattern::Variant{ path, payload }
+            },
+            // reports the missing pattern
+            _ => MatchPattern::Identifier(self.parse_identifier()),
+        }
+    }
+
+    /// Shape::Circle, a single identifier is a path as well
+    fn parse_path(&mut self) -> Path<'src> {
+        let mut segments = vec![];
+        loop {
+            let identifier = self.parse_identifier();
+            if !is_some_and_kind(&self.peek(), TokenKind::ColonColon) {
+                segments.push(PathSegment{ identifier, colon_colon_token: None });
+                break;
+            }
+
+            let colon_colon_token = Some(self.parse_token());
+            segments.push(PathSegment{ identifier, colon_colon_token });
+        }
+
+        Path{ segments }
     }
 
-    fn parse_call(&mut self, identifier: Identifier) -> Expr {
-        let argument_list = self.parse_argument_list();
-        Expr::Call{ identifier, argument_list }
+    fn parse_pattern_payload(&mut self) -> Option<PatternPayload<'src>> {
+        match self.peek().map(|token| token.kind) {
+            Some(TokenKind::LParen) => {
+                let l_paran = self.parse_token();
+                let mut patterns = vec![];
+                while is_possible_match_arm(&self.peek()) {
+                    let pattern = self.parse_pattern();
+                    let comma_token = self.parse_optional_comma();
+                    patterns.push(PatternItem{ pattern, comma_token });
+                }
+                let r_paran = self.parse_expected_token(TokenKind::RParen);
+                Some(PatternPayload::Tuple{ l_paran, patterns, r_paran })
+            },
+            Some(TokenKind::LBrace) => {
+                let l_brace = self.parse_token();
+                let mut fields = vec![];
+                while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+                    let identifier = self.parse_identifier();
+                    let value = if is_some_and_kind(&self.peek(), TokenKind::Colon) {
+                        let colon_token = self.parse_token();
+                        let pattern = self.parse_pattern();
+                        Some(FieldPatternValue{ colon_token, pattern })
+                    }
+                    else {
+                        None
+                    };
+                    let comma_token = self.parse_optional_comma();
+                    fields.push(FieldPattern{ identifier, value, comma_token });
+                }
+                let r_brace = self.parse_expected_token(TokenKind::RBrace);
+                Some(PatternPayload::Struct{ l_brace, fields, r_brace })
+            },
+            _ => None,
+        }
     }
 
-    fn parse_argument_list(&mut self) -> ArgumentList {
+    fn parse_struct_literal(&mut self, identifier: Identifier<'src>) -> Expr<'src> {
+        let l_brace = self.parse_token();
+
+        let mut fields = vec![];
+        while is_some_and_kind(&self.peek(), TokenKind::Identifier) {
+            let identifier = self.parse_identifier();
+            let colon_token = self.parse_expected_token(TokenKind::Colon);
+            let expr = self.parse_nested_expression();
+            let comma_token = if is_some_and_kind(&self.peek(), TokenKind::Comma) {
+                Some(self.parse_token())
+            }
+            else {
+                None
+            };
+
+            fields.push(FieldInitializer{ identifier, colon_token, expr, comma_token });
+        }
+
+        let r_brace = self.parse_expected_token(TokenKind::RBrace);
+
+        Expr::StructLiteral{ identifier, l_brace, fields, r_brace }
+    }
+
+    fn parse_argument_list(&mut self) -> ArgumentList<'src> {
         let mut arguments = Vec::<Argument>::new();
         let l_paran = self.parse_token();
         
         while is_possible_expression(&self.peek()) {
-            let expr = self.parse_expression();
+            let expr = self.parse_nested_expression();
             
             let comma_token = if is_some_and_kind(&self.peek(), TokenKind::Comma) {
                 Some(self.parse_token())
@@ -591,11 +1184,26 @@ impl Parser {
         ArgumentList { l_paran, arguments, r_paran}
     }
 
-    fn parse_identifier(&mut self) -> Identifier {
-        Identifier{ identifier: self.parse_expected_token(TokenKind::Identifier) }
+    fn parse_identifier(&mut self) -> Identifier<'src> {
+        let mut identifier = match self.peek() {
+            // struct in let struct = 1; was an identifier before it became a keyword
+            Some(token) if token.kind != TokenKind::Identifier && is_reserved_keyword(token.value, self.edition) => {
+                let mut identifier = self.parse_token();
+                identifier.token.kind = TokenKind::Identifier;
+                identifier
+            },
+            _ => self.parse_expected_token(TokenKind::Identifier),
+        };
+        if is_reserved_keyword(identifier.token.value, self.edition) {
+            // still an identifier, so the rest of the statement parses as usual
+            let diagnostic = LexerDiagnostic{ kind: LexerErrorKind::ReservedKeyword, offset: identifier.token.offset, len: identifier.token.len };
+            identifier.diagnostics.push(Diagnostic{ kind: ErrorKind::LexerError{ diagnostic } });
+        }
+
+        Identifier{ identifier }
     }
 
-    fn parse_type_id(&mut self) -> Option<TypeId> {
+    fn parse_type_id(&mut self) -> Option<TypeId<'src>> {
         if !is_some_and_kind(&self.peek(), TokenKind::Colon) {
             return None;
         }
@@ -605,51 +1213,75 @@ impl Parser {
         Some(TypeId{ colon_token, type_kind })
     }
 
-    fn parse_token(&mut self) -> SyntaxToken {
-        SyntaxToken{ token: self.eat().unwrap(), trivia: self.eat_trivia(), diagnostics: vec![], }
+    fn parse_token(&mut self) -> SyntaxToken<'src> {
+        syntax_token(self.eat().unwrap())
     }
 
-    fn parse_expected_token(&mut self, expected_kind: TokenKind) -> SyntaxToken {
+    fn parse_expected_token(&mut self, expected_kind: TokenKind) -> SyntaxToken<'src> {
         if is_some_and_kind(&self.peek(), expected_kind) {
             self.parse_token()
         }
         else {
-            // TODO: prev whitespace could be used for expected pos and length
-            let next = self.peek();
-            let expected = Token{ kind: TokenKind::Identifier, len: 0, value: String::new() };
-            let diagnostic = Diagnostic{ 
-                kind: ErrorKind::MissingToken{
-                    expected: expected.clone(), 
-                    actual: next,
-                },
-            };
-            SyntaxToken{ token: expected, trivia: self.eat_trivia(), diagnostics: vec![diagnostic], }
+            let expected = Token{ kind: expected_kind, len: 0, value: "", offset: self.offset() };
+            self.missing_token(expected_kind, ErrorKind::MissingToken{ expected, actual: self.peek() })
         }
     }
 
-    fn eat_trivia(&mut self) -> Vec<Trivia> {
-        let mut vec: Vec<Trivia> = vec![];
-        while let Some(trivia_token) = self.peek() {
-            match trivia_token.kind {
-                TokenKind::Whitespace
-                | TokenKind::Newline
-                | TokenKind::LineComment 
-                | TokenKind::MultilineComment{..} => {
-                    self.eat();
-                    vec.push(Trivia{ trivia_token });
-                },
-                _ => break,
-            }
-        }
-        vec
+    /// a zero length token in front of the next token that holds the diagnostic for what is missing there
+    fn missing_token(&self, kind: TokenKind, error: ErrorKind<'src>) -> SyntaxToken<'src> {
+        let token = Token{ kind, len: 0, value: "", offset: self.offset() };
+        SyntaxToken{ leading_trivia: vec![], token, trailing_trivia: vec![], diagnostics: vec![Diagnostic{ kind: error }] }
     }
 }
 
-fn is_some_and_kind(token: &Option<Token>, kind: TokenKind) -> bool {
+/// holes get their own parser, so they can not run past their '}'
+fn parse_interpolation_hole<'src>(l_brace: Token<'src>, tokens: Vec<Token<'src>>, r_brace: Option<Token<'src>>, edition: Edition) -> InterpolatedPart<'src> {
+    let mut parser = Parser::with_edition(std::iter::once(l_brace).chain(tokens).chain(r_brace), edition);
+    let l_brace = parser.parse_token();
+    let expr = Box::new(parser.parse_expression());
+    let mut r_brace = parser.parse_expected_token(TokenKind::RBrace);
+
+    // whatever the expression did not use is kept as trivia, the missing '}' already reports it
+    while let Some(token) = parser.eat() {
+        r_brace.trailing_trivia.extend(token.leading_trivia.into_iter().map(trivia));
+        r_brace.trailing_trivia.push(trivia(token.token));
+        r_brace.trailing_trivia.extend(token.trailing_trivia.into_iter().map(trivia));
+    }
+    r_brace.trailing_trivia.extend(parser.end_of_file.leading_trivia.into_iter().map(trivia));
+
+    InterpolatedPart::Hole{ l_brace, expr, r_brace }
+}
+
+fn syntax_token(token: TokenWithTrivia<'_>) -> SyntaxToken<'_> {
+    SyntaxToken{
+        leading_trivia: token.leading_trivia.into_iter().map(trivia).collect(),
+        diagnostics: lexer_diagnostics(&token.token),
+        token: token.token,
+        trailing_trivia: token.trailing_trivia.into_iter().map(trivia).collect(),
+    }
+}
+
+/// a token without trivia, like the parts of an interpolated string
+fn bare_syntax_token(token: Token<'_>) -> SyntaxToken<'_> {
+    SyntaxToken{ leading_trivia: vec![], diagnostics: lexer_diagnostics(&token), token, trailing_trivia: vec![] }
+}
+
+fn trivia(trivia_token: Token<'_>) -> Trivia<'_> {
+    Trivia{ diagnostics: lexer_diagnostics(&trivia_token), trivia_token }
+}
+
+fn lexer_diagnostics<'src>(token: &Token<'src>) -> Vec<Diagnostic<'src>> {
+    diagnose(token)
+        .into_iter()
+        .map(|diagnostic| Diagnostic{ kind: ErrorKind::LexerError{ diagnostic } })
+        .collect()
+}
+
+fn is_some_and_kind(token: &Option<Token<'_>>, kind: TokenKind) -> bool {
     token.is_some() && token.as_ref().unwrap().kind == kind
 }
 
-fn is_some_and_some_kind<'a>(token: &Option<Token>, kinds: impl Iterator<Item = &'a TokenKind>) -> bool {
+fn is_some_and_some_kind<'a>(token: &Option<Token<'_>>, kinds: impl Iterator<Item = &'a TokenKind>) -> bool {
     if token.is_none() {
         return false;
     }
@@ -661,40 +1293,85 @@ fn is_some_kind<'a>(kind: TokenKind, mut kinds: impl Iterator<Item = &'a TokenKi
     kinds.any(|k| kind == *k)
 }
 
-fn is_possible_match_arm(token: &Option<Token>) -> bool {
-    token.is_some() && matches!(token.as_ref().unwrap().kind, TokenKind::StringLiteral{..}
+fn is_possible_match_arm(token: &Option<Token<'_>>) -> bool {
+    token.is_some() && (is_literal_pattern(token.as_ref().unwrap().kind) || token.as_ref().unwrap().kind == TokenKind::Identifier)
+}
+
+/// the literals a pattern can compare against, and start or end a range with
+fn is_literal_pattern(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::StringLiteral{..}
+        | TokenKind::RawStringLiteral{..}
+        | TokenKind::MultilineStringLiteral{..}
         | TokenKind::NumberLiteral{..}
         | TokenKind::CharLiteral{..}
         | TokenKind::FalseKeyword 
-        | TokenKind::TrueKeyword
-        | TokenKind::Identifier)
+        | TokenKind::TrueKeyword)
+}
+
+/// a plain decimal integer like the 0 in tuple.0
+fn is_tuple_index(token: Token<'_>) -> bool {
+    matches!(token.kind, TokenKind::NumberLiteral{ base: Base::Decimal, has_exponent: false, suffix: None, .. })
+        && token.value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// the keywords that start a statement, the parser continues from them after an error
+fn is_statement_keyword(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::LetKeyword
+        | TokenKind::FunctionKeyword
+        | TokenKind::IfKeyword
+        | TokenKind::ElseKeyword
+        | TokenKind::WhileKeyword
+        | TokenKind::ForKeyword
+        | TokenKind::ReturnKeyword
+        | TokenKind::BreakKeyword
+        | TokenKind::StructKeyword
+        | TokenKind::EnumKeyword
+        | TokenKind::ImplKeyword
+        | TokenKind::TraitKeyword)
+}
+
+/// tokens that end an expression or belong to the node around it, so an error expression leaves them there
+fn is_expression_end(kind: TokenKind) -> bool {
+    is_statement_keyword(kind) || matches!(kind, TokenKind::Semicolon
+        | TokenKind::Comma
+        | TokenKind::Colon
+        | TokenKind::EqualsGreater
+        | TokenKind::RParen
+        | TokenKind::RBracket
+        | TokenKind::LBrace
+        | TokenKind::RBrace
+        | TokenKind::Hash)
 }
 
-fn is_operator(token: &Option<Token>) -> bool {
+fn is_operator(token: &Option<Token<'_>>) -> bool {
     is_some_and_some_kind(token, OPERATORS.iter())
 }
 
-fn is_possible_expression(token: &Option<Token>) -> bool {
+fn is_possible_expression(token: &Option<Token<'_>>) -> bool {
     token.is_some() && 
     (is_some_kind(token.as_ref().unwrap().kind, OPERATORS.iter()) 
     || matches!(token.as_ref().unwrap().kind, TokenKind::StringLiteral{..} 
+                                    | TokenKind::RawStringLiteral{..} 
+                                    | TokenKind::MultilineStringLiteral{..} 
+                                    | TokenKind::InterpolatedStringLiteral{..} 
                                     | TokenKind::CharLiteral{..} 
                                     | TokenKind::NumberLiteral{..} 
                                     | TokenKind::TrueKeyword 
                                     | TokenKind::FalseKeyword
                                     | TokenKind::MatchKeyword
+                                    | TokenKind::SelfKeyword
                                     | TokenKind::Identifier))
 }
 
-fn prefix_binding_power(kind: TokenKind) -> ((), u8) {
+fn prefix_binding_power(kind: TokenKind) -> Option<((), u8)> {
     match kind {
         TokenKind::MinusMinus
         | TokenKind::PlusPlus
         | TokenKind::Plus
         | TokenKind::Minus
         | TokenKind::Tilde
-        | TokenKind::Bang => ((), 29),
-        _ => panic!("unknown operator: {:?}", kind),
+        | TokenKind::Bang => Some(((), 29)),
+        _ => None,
     }
 }
 
@@ -761,23 +1438,24 @@ fn postfix_binding_power(kind: TokenKind) -> Option<(u8, ())> {
         TokenKind::MinusMinus
         | TokenKind::PlusPlus
         | TokenKind::QuestionLBracket
-        | TokenKind::LBracket => Some((30, ())),
+        | TokenKind::LBracket
+        | TokenKind::LParen => Some((30, ())),
         _ => None,
     }
 }
 
-fn bake_ternary_expression(lhs: Expr, op1: SyntaxToken, mhs: Expr, op2: SyntaxToken, rhs: Expr) -> Expr {
+fn bake_ternary_expression<'src>(lhs: Expr<'src>, op1: SyntaxToken<'src>, mhs: Expr<'src>, op2: SyntaxToken<'src>, rhs: Expr<'src>) -> Expr<'src> {
     Expr::Ternary{ lhs: Box::new(lhs), op1, mhs: Box::new(mhs), op2, rhs: Box::new(rhs) }
 }
 
-fn bake_binary_expression(lhs: Expr, op: SyntaxToken, rhs: Expr) -> Expr {
+fn bake_binary_expression<'src>(lhs: Expr<'src>, op: SyntaxToken<'src>, rhs: Expr<'src>) -> Expr<'src> {
     Expr::Binary{ lhs: Box::new(lhs), op, rhs: Box::new(rhs) }
 }
 
-fn bake_unary_expression(op: SyntaxToken, operand: Expr) -> Expr {
+fn bake_unary_expression<'src>(op: SyntaxToken<'src>, operand: Expr<'src>) -> Expr<'src> {
     Expr::Unary{ op, operand: Box::new(operand) }
 }
 
-fn decorate_expression(l: SyntaxToken, r: SyntaxToken, expr: Expr) -> Expr {
+fn decorate_expression<'src>(l: SyntaxToken<'src>, r: SyntaxToken<'src>, expr: Expr<'src>) -> Expr<'src> {
     Expr::Decorated{ l, expr: Box::new(expr), r }
 }                       
\ No newline at end of file
@@ -0,0 +1,220 @@
+use expect_test::{expect, Expect};
+use ferrousc_ast::diagnostic::emit_diagnostics;
+use ferrousc_diagnostics::Diagnostics;
+use ferrousc_lexer::{lex, LexerDiagnostic};
+use ferrousc_parser::generate_ast;
+
+fn check(src: &str, expect: Expect) {
+    let lexed = lex(src);
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.extend(lexed.diagnostics.iter().map(LexerDiagnostic::to_diagnostic));
+    let ast = generate_ast(lexed.tokens.into_iter());
+    emit_diagnostics(&ast, &mut diagnostics);
+    diagnostics.sort();
+
+    expect.assert_eq(&diagnostics.render(src, "main.fe"));
+}
+
+#[test]
+fn parser_diagnostics() {
+    check("let a = 1\nlet b = ;\n", expect![[r#"
+        error[E0100]: expected `;`, found `let`
+         --> main.fe:1:10
+          |
+        1 | let a = 1
+          |          ^ expected `;`
+        help: insert `;`
+          |
+        1 | let a = 1;
+
+        error[E0102]: expected an expression, found `;`
+         --> main.fe:2:8
+          |
+        2 | let b = ;
+          |        ^
+    "#]]);
+    check("#[inline] return;\n#[custom] fn f() {}", expect![[r#"
+        error[E0103]: attributes can only be put on fn, let, struct and enum declarations
+         --> main.fe:1:1
+          |
+        1 | #[inline] return;
+          | ^
+          = help: move the attributes in front of a fn, let, struct or enum
+
+        warning[E0104]: unknown attribute `custom`
+         --> main.fe:2:3
+          |
+        2 | #[custom] fn f() {}
+          |   ^^^^^^
+          = note: unknown attributes are ignored
+    "#]]);
+    check("#[cfg(a b)] fn f() {}", expect![[r#"
+        warning[E0104]: unknown attribute `cfg`
+         --> main.fe:1:3
+          |
+        1 | #[cfg(a b)] fn f() {}
+          |   ^^^
+          = note: unknown attributes are ignored
+
+        error[E0100]: expected `,`, found identifier `b`
+         --> main.fe:1:8
+          |
+        1 | #[cfg(a b)] fn f() {}
+          |        ^ expected `,`
+        help: insert `,`
+          |
+        1 | #[cfg(a, b)] fn f() {}
+    "#]]);
+    check("impl P {\n    let a = 1;\n    fn f(self) {}\n}", expect![[r#"
+        error[E0105]: expected a fn declaration, found `let`
+         --> main.fe:2:5
+          |
+        2 |     let a = 1;
+          |     ^^^
+          = note: impl blocks and traits can only contain functions
+    "#]]);
+    check("let a: List<int = 1;\nf::<int(1);", expect![[r#"
+        error[E0100]: expected `>`, found `=`
+         --> main.fe:1:16
+          |
+        1 | let a: List<int = 1;
+          |                ^ expected `>`
+        help: insert `>`
+          |
+        1 | let a: List<int> = 1;
+
+        error[E0100]: expected `>`, found `(`
+         --> main.fe:2:8
+          |
+        2 | f::<int(1);
+          |        ^ expected `>`
+        help: insert `>`
+          |
+        2 | f::<int>(1);
+    "#]]);
+    check(") let a", expect![[r#"
+        error[E0101]: expected a statement, found `)`
+         --> main.fe:1:1
+          |
+        1 | ) let a
+          | ^
+
+        error[E0100]: expected `;`, found end of file
+         --> main.fe:1:8
+          |
+        1 | ) let a
+          |        ^ expected `;`
+        help: insert `;`
+          |
+        1 | ) let a;
+    "#]]);
+}
+
+#[test]
+fn lexer_diagnostics_are_reported_once() {
+    check("let const = 0b102 ` 1;\nlet s = $\"{a\";", expect![[r#"
+        error[E0012]: reserved keyword can not be used as an identifier
+         --> main.fe:1:5
+          |
+        1 | let const = 0b102 ` 1;
+          |     ^^^^^
+          = help: rename the identifier, the word is reserved for an upcoming feature
+
+        error[E0007]: invalid digit for the base of the number literal
+         --> main.fe:1:17
+          |
+        1 | let const = 0b102 ` 1;
+          |                 ^
+
+        error[E0100]: expected `;`, found number literal
+         --> main.fe:1:18
+          |
+        1 | let const = 0b102 ` 1;
+          |                  ^ expected `;`
+        help: insert `;`
+          |
+        1 | let const = 0b102; ` 1;
+
+        error[E0001]: unknown character
+         --> main.fe:1:19
+          |
+        1 | let const = 0b102 ` 1;
+          |                   ^
+
+        error[E0002]: unterminated string literal, missing '"'
+         --> main.fe:2:9
+          |
+        2 | let s = $"{a";
+          |         ^^^^^^
+
+        error[E0005]: unterminated interpolation hole, missing '}'
+         --> main.fe:2:11
+          |
+        2 | let s = $"{a";
+          |           ^
+          = help: use '{{' for a '{' that does not start a hole
+
+        error[E0002]: unterminated string literal, missing '"'
+         --> main.fe:2:13
+          |
+        2 | let s = $"{a";
+          |             ^^
+
+        error[E0100]: expected `}`, found string literal
+         --> main.fe:2:13
+          |
+        2 | let s = $"{a";
+          |             ^ expected `}`
+        help: insert `}`
+          |
+        2 | let s = $"{a}";
+
+        error[E0100]: expected `"`, found end of file
+         --> main.fe:2:15
+          |
+        2 | let s = $"{a";
+          |               ^ expected `"`
+
+        error[E0100]: expected `;`, found end of file
+         --> main.fe:2:15
+          |
+        2 | let s = $"{a";
+          |               ^ expected `;`
+        help: insert `;`
+          |
+        2 | let s = $"{a";;
+    "#]]);
+}
+
+#[test]
+fn new_keywords_are_reserved_as_identifiers() {
+    check("let struct = 1;\nlet enum = 2;\nfn impl() {}\nlet x = a.trait;\n", expect![[r#"
+        error[E0012]: reserved keyword can not be used as an identifier
+         --> main.fe:1:5
+          |
+        1 | let struct = 1;
+          |     ^^^^^^
+          = help: rename the identifier, the word is reserved for an upcoming feature
+
+        error[E0012]: reserved keyword can not be used as an identifier
+         --> main.fe:2:5
+          |
+        2 | let enum = 2;
+          |     ^^^^
+          = help: rename the identifier, the word is reserved for an upcoming feature
+
+        error[E0012]: reserved keyword can not be used as an identifier
+         --> main.fe:3:4
+          |
+        3 | fn impl() {}
+          |    ^^^^
+          = help: rename the identifier, the word is reserved for an upcoming feature
+
+        error[E0012]: reserved keyword can not be used as an identifier
+         --> main.fe:4:11
+          |
+        4 | let x = a.trait;
+          |           ^^^^^
+          = help: rename the identifier, the word is reserved for an upcoming feature
+    "#]]);
+}
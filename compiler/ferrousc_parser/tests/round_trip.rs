@@ -0,0 +1,594 @@
+use ferrousc_ast::nodes::{Attribute, AttributeArgument, AttributeArgumentKind, Expr, FunctionBody, MatchPattern, PatternPayload, Stat, TypeKind, VariantPayload};
+use ferrousc_ast::source::ToSource;
+use ferrousc_lexer::{tokenize, tokenize_with_edition, Edition, TokenKind};
+use ferrousc_parser::{generate_ast, generate_ast_with_edition};
+use proptest::prelude::*;
+use proptest::sample::select;
+
+fn check_round_trip(src: &str) {
+    let ast = generate_ast(tokenize(src));
+    assert_eq!(ast.to_source(), src);
+}
+
+#[test]
+fn round_trip() {
+    check_round_trip("");
+    check_round_trip("  // only trivia\n");
+    check_round_trip("/// docs\nfn main() -> int {\n    let mut a: int = -b++ * (c + 1); // trailing\n    return a[0] ? f(a, 'c',) : 0x1F;\n}\n");
+    check_round_trip("let s = $\"Hello { name /* hole */ }!\" string;\nlet m = match x { 1 => \"one\", y => y, };\n");
+    check_round_trip("for i in 0 .. 10 { if i { break; } else { while true {} } }");
+    check_round_trip("/// docs\n#[inline] #[deprecated(since = \"1.0\", note = \"msg\")]\nfn f() {}\n#[cfg(any(test, -1),)] let a = 1;");
+    check_round_trip("/// a point\nstruct Point { x: f64, y: f64, }\nlet p = Point { x: 1.0, y: f(Point {}).y };\nif p.x == p?.y { p.x = 0; }");
+    check_round_trip("#[derive] enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty, }\nenum E {}\n");
+    check_round_trip("impl Point {\n    /// docs\n    fn len(self) -> f64 => self.x;\n    #[inline] fn set(mut self, x: f64,) { self.x = x; }\n}\n\"test\".len(); a.b(c)(d)[0](); fns[0](x);");
+    check_round_trip("/// shapes\ntrait Shape {\n    fn area(self) -> f64;\n    fn name(self) -> string => \"shape\";\n}\nimpl Shape for Circle { fn area(self) -> f64 => 0; }\nfn total(a: impl Shape, b: impl Shape + Named) -> impl Shape;");
+    check_round_trip("fn max<T: Ord + Eq, U,>(a: T, b: List<U>) -> T;\nstruct List<T> { head: T, tail: List<List<T>>, }\nenum Option<T> { Some(T), None }\nlet a: List<int>= b;\nlet c: List<List<int>>= d;\nmax::<int, List<int>>(1, 2) < 3;");
+    check_round_trip("match s { Shape::Circle(r) if r > 0 => r, Shape::Rect { w, h: 1 | 2, } => w, 1..=5 | 'a'..'z' => 0, _ => 1 }");
+}
+
+#[test]
+fn round_trip_keeps_unexpected_tokens() {
+    check_round_trip(") ; let a = 1;");
+    check_round_trip("let a = 1 let b = 2;");
+    check_round_trip("fn f(a: int) => a; } ] break;");
+    check_round_trip("let s = $\"{a b}\";");
+    check_round_trip("#[test] return; # let a = 1; #[unknown] let b;");
+    check_round_trip("#[cfg(a b c(d e))] fn f() {}");
+}
+
+#[test]
+fn round_trip_recovers_from_errors() {
+    check_round_trip("let a = ;");
+    check_round_trip("fn f(a) {");
+    check_round_trip("fn f(a: int, b: ) => ;");
+    check_round_trip("match a; let b = match c { => 1, 2 => };");
+    check_round_trip(") }");
+    check_round_trip("let a = [1, 2 } ]");
+    check_round_trip("#[inline");
+    check_round_trip("if { while for i in ");
+    check_round_trip("f(a, , b) * ;");
+    check_round_trip("let");
+    check_round_trip("struct { x f64 } struct P");
+    check_round_trip("let p = Point { x: , y 1 ; a. ; a?.");
+    check_round_trip("enum { A(, B { x } C( } enum");
+    check_round_trip("impl { let a = 1; #[inline] struct S {} fn f(self self) {} impl P");
+    check_round_trip("f(; a.b(c ; (a)(");
+    check_round_trip("trait { fn f() let } trait T fn g(a: impl, b: impl A +) impl for C {} impl A for {");
+    check_round_trip("fn f<T: >() {} struct S<T fn g<>(a: List<int) {} max::<(1); let a: List<,> = 1; f::<");
+    check_round_trip("match a { A:: => 1, B(1 | => 2, 1..= => 3, _ if => 4, C { x: } => 5 }");
+}
+
+#[test]
+fn attributes_and_their_arguments() {
+    let ast = generate_ast(tokenize("#[inline] #[deprecated(since = \"1.0\", note = \"msg\")]\nfn f() {}\n#[cfg(any(test, -1),)] let a = 1;\nf();"));
+    let names: Vec<Vec<&str>> = ast.statements.iter().map(|statement| statement.attributes().iter().map(Attribute::name).collect()).collect();
+    assert_eq!(names, [vec!["inline", "deprecated"], vec!["cfg"], vec![]]);
+
+    assert!(ast.statements[0].attribute("inline").unwrap().arguments.is_none());
+    let deprecated = &ast.statements[0].attribute("deprecated").unwrap().arguments.as_ref().unwrap().arguments;
+    let keys: Vec<&str> = deprecated.iter().map(|argument| match &argument.kind {
+        AttributeArgumentKind::KeyValue{ key, value, .. } if matches!(**value, Expr::Literal{..}) => key.identifier.token.value,
+        kind => panic!("expected key = \"value\", found {:?}", kind),
+    }).collect();
+    assert_eq!(keys, ["since", "note"]);
+
+    let cfg = &ast.statements[1].attribute("cfg").unwrap().arguments.as_ref().unwrap().arguments;
+    match &cfg[..] {
+        [AttributeArgument{ kind: AttributeArgumentKind::Nested{ identifier, arguments: Some(any) }, comma_token: Some(_) }] => {
+            assert_eq!(identifier.identifier.token.value, "any");
+            assert!(matches!(&any.arguments[0].kind, AttributeArgumentKind::Nested{ arguments: None, .. }));
+            assert!(matches!(&any.arguments[1].kind, AttributeArgumentKind::Literal{..}));
+        },
+        arguments => panic!("expected any(test, -1), found {:?}", arguments),
+    }
+    assert!(ast.statements[1].attribute("inline").is_none());
+}
+
+#[test]
+fn docs_attach_to_the_next_declaration() {
+    let ast = generate_ast(tokenize("/// adds\n/// two numbers\nfn add() {}\n/**\n * the answer\n */\n#[inline] let a = 42;\n// not a doc\nlet b = 1; /// after b\nf();\n"));
+    let docs: Vec<Option<String>> = ast.statements.iter().map(Stat::docs).collect();
+    assert_eq!(docs, [Some("adds\ntwo numbers".to_string()), Some("the answer".to_string()), None, None]);
+}
+
+fn parse_arm_pattern(src: &str) -> MatchPattern<'_> {
+    match parse_expression(src) {
+        Expr::Match{ body, .. } => body.arms.into_iter().next().expect("an arm").pattern,
+        expr => panic!("expected a match, found {:?}", expr),
+    }
+}
+
+#[test]
+fn enums_and_patterns() {
+    let ast = generate_ast(tokenize("enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }"));
+    match &ast.statements[0] {
+        Stat::EnumDefinition{ variants, .. } => {
+            assert_eq!(variants.len(), 3);
+            assert!(matches!(&variants[0].payload, Some(VariantPayload::Tuple{ fields, .. }) if fields.len() == 1));
+            assert!(matches!(&variants[1].payload, Some(VariantPayload::Struct{ fields, .. }) if fields.len() == 2));
+            assert!(variants[2].payload.is_none());
+        },
+        statement => panic!("expected an enum definition, found {:?}", statement),
+    }
+
+    // a | b | c is ((a | b) | c)
+    match parse_arm_pattern("match x { 1 | 2..=5 | _ => 0 };") {
+        MatchPattern::Or{ lhs, rhs, .. } => {
+            assert!(matches!(*rhs, MatchPattern::Wildcard(_)));
+            assert!(matches!(*lhs, MatchPattern::Or{ ref rhs, .. } if matches!(**rhs, MatchPattern::Range{ ref op, .. } if op.token.kind == TokenKind::DotDotEqual)));
+        },
+        pattern => panic!("expected an or pattern, found {:?}", pattern),
+    }
+
+    match parse_arm_pattern("match s { Shape::Rect { w, h: 0 } => w };") {
+        MatchPattern::Variant{ path, payload: Some(PatternPayload::Struct{ fields, .. }) } => {
+            assert_eq!(path.segments.len(), 2);
+            assert!(fields[0].value.is_none());
+            assert!(matches!(&fields[1].value, Some(value) if matches!(value.pattern, MatchPattern::Literal(_))));
+        },
+        pattern => panic!("expected a struct variant pattern, found {:?}", pattern),
+    }
+
+    // a lone identifier binds, with a payload or path it is a variant
+    assert!(matches!(parse_arm_pattern("match o { x => 0 };"), MatchPattern::Identifier(_)));
+    assert!(matches!(parse_arm_pattern("match o { Some(x) => 0 };"), MatchPattern::Variant{ payload: Some(PatternPayload::Tuple{..}), .. }));
+
+    // default is the contextual keyword for _, only as a whole pattern and only from Edition2026 on
+    assert!(matches!(parse_arm_pattern("match o { default => 0 };"), MatchPattern::Wildcard(_)));
+    assert!(matches!(parse_arm_pattern("match o { default(x) => 0 };"), MatchPattern::Variant{..}));
+    let ast = generate_ast_with_edition(tokenize_with_edition("match o { default => 0 };", Edition::Edition2025), Edition::Edition2025);
+    assert!(matches!(&ast.statements[0], Stat::Expr{ expr: Expr::Match{ body, .. }, .. } if matches!(body.arms[0].pattern, MatchPattern::Identifier(_))));
+
+    match parse_expression("match o { Some(x) if x > 1 => x, _ => 0 };") {
+        Expr::Match{ body, .. } => {
+            assert!(matches!(&body.arms[0].guard, Some(guard) if matches!(guard.expr, Expr::Binary{..})));
+            assert!(body.arms[1].guard.is_none());
+        },
+        expr => panic!("expected a match, found {:?}", expr),
+    }
+}
+
+#[test]
+fn calls_and_methods() {
+    // a.b(c).d() is ((a.b)(c).d)()
+    match parse_expression("a.b(c).d();") {
+        Expr::Call{ callee, argument_list } => {
+            assert!(argument_list.arguments.is_empty());
+            match *callee {
+                Expr::FieldAccess{ expr, field, .. } => {
+                    assert_eq!(field.identifier.token.value, "d");
+                    assert!(matches!(*expr, Expr::Call{ ref callee, ref argument_list } if argument_list.arguments.len() == 1 && matches!(**callee, Expr::FieldAccess{..})));
+                },
+                callee => panic!("expected a method, found {:?}", callee),
+            }
+        },
+        expr => panic!("expected a call, found {:?}", expr),
+    }
+
+    assert!(matches!(parse_expression("fns[0](x);"), Expr::Call{ callee, .. } if matches!(*callee, Expr::Index{..})));
+    // calls bind tighter than prefix operators
+    assert!(matches!(parse_expression("-f();"), Expr::Unary{ operand, .. } if matches!(*operand, Expr::Call{..})));
+
+    let ast = generate_ast(tokenize("impl Point { fn f(mut self, a: int) => self; fn g() {} let a; }"));
+    match &ast.statements[0] {
+        Stat::ImplBlock{ functions, .. } => {
+            assert_eq!(functions.len(), 3);
+            match &functions[0] {
+                Stat::FunctionDefinition{ parameter_list, .. } => {
+                    assert!(matches!(&parameter_list.receiver, Some(receiver) if receiver.mut_token.is_some()));
+                    assert_eq!(parameter_list.parameters.len(), 1);
+                },
+                statement => panic!("expected a function, found {:?}", statement),
+            }
+            assert!(matches!(&functions[1], Stat::FunctionDefinition{ parameter_list, .. } if parameter_list.receiver.is_none()));
+            assert!(matches!(&functions[2], Stat::Error{..}));
+        },
+        statement => panic!("expected an impl block, found {:?}", statement),
+    }
+}
+
+#[test]
+fn traits_and_bounds() {
+    let ast = generate_ast(tokenize("trait Shape { fn area(self) -> f64; fn name(self) => 0; }\nimpl Shape for Circle {}\nfn f(s: impl Shape + Named) {}"));
+    match &ast.statements[0] {
+        Stat::TraitDefinition{ functions, .. } => {
+            assert!(matches!(&functions[0], Stat::FunctionDefinition{ body, .. } if matches!(**body, FunctionBody::Signature{..})));
+            assert!(matches!(&functions[1], Stat::FunctionDefinition{ body, .. } if matches!(**body, FunctionBody::ExpressionBody{..})));
+        },
+        statement => panic!("expected a trait, found {:?}", statement),
+    }
+    assert!(matches!(&ast.statements[1], Stat::ImplBlock{ trait_for: Some(trait_for), .. } if trait_for.identifier.identifier.token.value == "Shape"));
+    match &ast.statements[2] {
+        Stat::FunctionDefinition{ parameter_list, .. } => {
+            assert!(matches!(&parameter_list.parameters[0].type_id.type_kind, TypeKind::ImplTrait{ bounds, .. } if bounds.len() == 2));
+        },
+        statement => panic!("expected a function, found {:?}", statement),
+    }
+}
+
+#[test]
+fn generics() {
+    let ast = generate_ast(tokenize("fn max<T: Ord + Eq, U>(a: T) -> List<List<int>> {}\nlet a: List<int>= b;"));
+    match &ast.statements[0] {
+        Stat::FunctionDefinition{ generic_parameters: Some(generic_parameters), return_type: Some(return_type), .. } => {
+            assert_eq!(generic_parameters.parameters.len(), 2);
+            assert!(matches!(&generic_parameters.parameters[0].bounds, Some(bounds) if bounds.bounds.len() == 2));
+            assert!(generic_parameters.parameters[1].bounds.is_none());
+            // the >> closes both lists
+            match &return_type.type_kind {
+                TypeKind::UserDefined{ generic_arguments: Some(generic_arguments), .. } => {
+                    assert_eq!(generic_arguments.greater_token.token.value, ">");
+                    assert!(matches!(&generic_arguments.arguments[0].type_kind, TypeKind::UserDefined{ generic_arguments: Some(_), .. }));
+                },
+                type_kind => panic!("expected a generic type, found {:?}", type_kind),
+            }
+        },
+        statement => panic!("expected a generic function, found {:?}", statement),
+    }
+    // the >= is split into the > of the type and the = of the value
+    assert!(matches!(&ast.statements[1], Stat::VarDefinition{ type_id: Some(_), initial_value: Some(initial_value), .. } if initial_value.equals_token.token.kind == TokenKind::Equal));
+
+    // without a turbofish the < is a comparison
+    assert!(matches!(parse_expression("a < b > c;"), Expr::Binary{ lhs, .. } if matches!(*lhs, Expr::Binary{..})));
+    match parse_expression("max::<int, List<int>>(1, 2);") {
+        Expr::Call{ callee, .. } => assert!(matches!(*callee, Expr::Turbofish{ ref generic_arguments, .. } if generic_arguments.arguments.len() == 2)),
+        expr => panic!("expected a call, found {:?}", expr),
+    }
+}
+
+/// every statement that starts at an error is one error statement
+fn error_statements(src: &str) -> usize {
+    generate_ast(tokenize(src)).statements.iter().filter(|statement| matches!(statement, Stat::Error{..})).count()
+}
+
+#[test]
+fn errors_synchronize_on_statements() {
+    assert_eq!(error_statements(") ] let a = 1;"), 1);
+    assert_eq!(error_statements(") let a = 1; ] } let b = 2;"), 3);
+    assert_eq!(error_statements(") ; ) ; ) ; let a = 1;"), 3);
+    assert_eq!(error_statements("#[inline] return; fn f() {}"), 1);
+    assert_eq!(error_statements(") struct A {} ]"), 2);
+}
+
+fn parse_expression(src: &str) -> Expr<'_> {
+    match generate_ast(tokenize(src)).statements.remove(0) {
+        Stat::Expr{ expr, .. } => expr,
+        statement => panic!("expected an expression statement, found {:?}", statement),
+    }
+}
+
+#[test]
+fn struct_literals_and_field_access() {
+    match parse_expression("Point { x: 1.0, y: a.b }.x;") {
+        Expr::FieldAccess{ expr, field, .. } => {
+            assert_eq!(field.identifier.token.value, "x");
+            assert!(matches!(*expr, Expr::StructLiteral{ ref fields, .. } if fields.len() == 2));
+        },
+        expr => panic!("expected a field access, found {:?}", expr),
+    }
+
+    // a.b.c is (a.b).c
+    match parse_expression("a?.b.c;") {
+        Expr::FieldAccess{ expr, field, .. } => {
+            assert_eq!(field.identifier.token.value, "c");
+            assert!(matches!(*expr, Expr::FieldAccess{ ref dot_token, .. } if dot_token.token.kind == TokenKind::QuestionDot));
+        },
+        expr => panic!("expected a field access, found {:?}", expr),
+    }
+
+    match parse_expression("\"string\".2 > 3;") {
+        Expr::Binary{ lhs, .. } => assert!(matches!(*lhs, Expr::FieldAccess{ ref field, .. } if field.identifier.token.value == "2")),
+        expr => panic!("expected a comparison, found {:?}", expr),
+    }
+
+    // the braces after a condition are the block, unless the literal is in parentheses
+    let ast = generate_ast(tokenize("if a { b: 1; } while (P { x: 1 }).x {}"));
+    assert!(matches!(&ast.statements[0], Stat::If{ expression: Expr::IdentifierUsage{..}, .. }));
+    assert!(matches!(&ast.statements[1], Stat::While{ expression: Expr::FieldAccess{..}, .. }));
+}
+
+const IDENTIFIERS: [&str; 5] = ["a", "b", "foo", "bar_1", "größe"];
+
+const LITERALS: [&str; 11] = [
+    "1", "42_i32", "0x1F", "3.5", "\"text\"", "r#\"raw\"#", "'c'", "true", "false",
+    "$\"x = {x}\"", "$\"{a + 1} and {f(b, \"}\")}\"",
+];
+
+const BINARY_OPERATORS: [&str; 12] = ["+", "-", "*", "/", "%", "==", "!=", "<", ">=", "&&", "||", ".."];
+
+const PREFIX_OPERATORS: [&str; 4] = ["-", "!", "~", "++"];
+
+const ATTRIBUTES: [&[&str]; 4] = [
+    &["#", "[", "inline", "]"],
+    &["#", "[", "extern", "(", "\"C\"", ")", "]"],
+    &["#", "[", "deprecated", "(", "note", "=", "\"msg\"", ",", ")", "]"],
+    &["#", "[", "cfg", "(", "any", "(", "test", ",", "-", "1", ")", ")", "]"],
+];
+
+const PUNCTUATION: [&str; 35] = [
+    "let", "mut", "fn", "if", "else", "while", "for", "in", "return", "break", "match", "struct", "enum", "impl", "self", "trait",
+    "(", ")", "[", "]", "{", "}", ";", ",", ":", "=>", "#", ".", "?.", "|", "::", "..=", "_", ">", ">>",
+];
+
+const TYPES: [&str; 4] = ["int", "string", "bool", "Point"];
+
+/// only user defined types take type arguments
+const GENERIC_TYPES: [&str; 2] = ["List", "Point"];
+
+/// always contains whitespace, so neighbouring tokens never lex as one
+const TRIVIA: [&str; 8] = [" ", "  ", "\n", "\r\n    ", "\t", " /* comment */ ", " // comment\n", "\n/// doc comment\n"];
+
+type Tokens = Vec<String>;
+
+fn token(text: &str) -> Tokens {
+    vec![text.to_string()]
+}
+
+fn concat(parts: impl IntoIterator<Item = Tokens>) -> Tokens {
+    parts.into_iter().flatten().collect()
+}
+
+fn comma_separated(items: Vec<Tokens>, trailing_comma: bool) -> Tokens {
+    let count = items.len();
+    let mut tokens = vec![];
+    for (i, item) in items.into_iter().enumerate() {
+        tokens.extend(item);
+        if i + 1 < count || trailing_comma {
+            tokens.push(",".to_string());
+        }
+    }
+    tokens
+}
+
+fn expression() -> impl Strategy<Value = Tokens> {
+    let atom = prop_oneof![
+        select(&IDENTIFIERS[..]).prop_map(token),
+        select(&LITERALS[..]).prop_map(token),
+        Just(token("self")),
+    ];
+
+    atom.prop_recursive(4, 32, 4, |expr| prop_oneof![
+        (expr.clone(), select(&BINARY_OPERATORS[..]), expr.clone())
+            .prop_map(|(lhs, op, rhs)| concat([lhs, token(op), rhs])),
+        (expr.clone(), select(&BINARY_OPERATORS[..]), expr.clone())
+            .prop_map(|(lhs, op, rhs)| concat([lhs, token(op), token("("), rhs, token(")")])),
+        (select(&PREFIX_OPERATORS[..]), expr.clone())
+            .prop_map(|(op, operand)| concat([token(op), operand])),
+        expr.clone().prop_map(|operand| concat([operand, token("++")])),
+        (expr.clone(), expr.clone())
+            .prop_map(|(lhs, index)| concat([lhs, token("["), index, token("]")])),
+        (expr.clone(), expr.clone(), expr.clone())
+            .prop_map(|(lhs, mhs, rhs)| concat([lhs, token("?"), mhs, token(":"), rhs])),
+        (expr.clone(), prop::collection::vec(expr.clone(), 0..3), any::<bool>())
+            .prop_map(|(callee, arguments, trailing_comma)| concat([callee, token("("), comma_separated(arguments, trailing_comma), token(")")])),
+        (prop::collection::vec(expr.clone(), 0..3), any::<bool>())
+            .prop_map(|(items, trailing_comma)| concat([token("["), comma_separated(items, trailing_comma), token("]")])),
+        (expr.clone(), prop::collection::vec((pattern(), prop::option::of(expr.clone()), expr.clone()), 0..3))
+            .prop_map(|(scrutinee, arms)| {
+                let arms = arms.into_iter().map(|(pattern, guard, expr)| match guard {
+                    Some(guard) => concat([pattern, token("if"), guard, token("=>"), expr]),
+                    None => concat([pattern, token("=>"), expr]),
+                }).collect();
+                concat([token("match"), scrutinee, token("{"), comma_separated(arms, true), token("}")])
+            }),
+        (expr.clone(), prop_oneof![Just("."), Just("?.")], select(&IDENTIFIERS[..]))
+            .prop_map(|(lhs, dot, field)| concat([lhs, token(dot), token(field)])),
+        (select(&TYPES[..]), prop::collection::vec((select(&IDENTIFIERS[..]), expr), 0..3), any::<bool>())
+            .prop_map(|(name, fields, trailing_comma)| {
+                let fields = fields.into_iter().map(|(field, expr)| concat([token(field), token(":"), expr])).collect();
+                concat([token(name), token("{"), comma_separated(fields, trailing_comma), token("}")])
+            }),
+        (select(&IDENTIFIERS[..]), prop::collection::vec(type_kind(), 0..3), any::<bool>())
+            .prop_map(|(name, arguments, trailing_comma)| concat([token(name), token("::"), generic_arguments(arguments, trailing_comma)])),
+    ])
+}
+
+fn path() -> impl Strategy<Value = Tokens> {
+    prop::collection::vec(select(&TYPES[..]), 1..3)
+        .prop_map(|segments| segments.into_iter().map(token).collect::<Vec<_>>().join(&"::".to_string()))
+}
+
+fn pattern() -> impl Strategy<Value = Tokens> {
+    let atom = prop_oneof![
+        select(&IDENTIFIERS[..]).prop_map(token),
+        select(&LITERALS[..9]).prop_map(token),
+        Just(token("_")),
+        (select(&LITERALS[..4]), prop_oneof![Just(".."), Just("..=")], select(&LITERALS[..4]))
+            .prop_map(|(start, op, end)| concat([token(start), token(op), token(end)])),
+        (select(&TYPES[..]), select(&TYPES[..])).prop_map(|(ty, variant)| concat([token(ty), token("::"), token(variant)])),
+    ];
+
+    atom.prop_recursive(3, 16, 3, |pattern| prop_oneof![
+        (pattern.clone(), pattern.clone()).prop_map(|(lhs, rhs)| concat([lhs, token("|"), rhs])),
+        (path(), prop::collection::vec(pattern.clone(), 0..3), any::<bool>())
+            .prop_map(|(path, patterns, trailing_comma)| concat([path, token("("), comma_separated(patterns, trailing_comma), token(")")])),
+        (path(), prop::collection::vec((select(&IDENTIFIERS[..]), prop::option::of(pattern)), 0..3), any::<bool>())
+            .prop_map(|(path, fields, trailing_comma)| {
+                let fields = fields.into_iter().map(|(field, pattern)| match pattern {
+                    Some(pattern) => concat([token(field), token(":"), pattern]),
+                    None => token(field),
+                }).collect();
+                concat([path, token("{"), comma_separated(fields, trailing_comma), token("}")])
+            }),
+    ])
+}
+
+fn attributes() -> impl Strategy<Value = Tokens> {
+    prop::collection::vec(select(&ATTRIBUTES[..]), 0..3)
+        .prop_map(|attributes| attributes.concat().into_iter().map(String::from).collect())
+}
+
+fn statement() -> impl Strategy<Value = Tokens> {
+    let simple = prop_oneof![
+        (attributes(), any::<bool>(), select(&IDENTIFIERS[..]), prop::option::of(type_kind()), prop::option::of(expression()))
+            .prop_map(|(attributes, mutable, identifier, type_kind, value)| {
+                let mut tokens = concat([attributes, token("let")]);
+                if mutable {
+                    tokens.push("mut".to_string());
+                }
+                tokens.push(identifier.to_string());
+                if let Some(type_kind) = type_kind {
+                    tokens.extend(concat([token(":"), type_kind]));
+                }
+                if let Some(value) = value {
+                    tokens.extend(concat([token("="), value]));
+                }
+                concat([tokens, token(";")])
+            }),
+        expression().prop_map(|expr| concat([expr, token(";")])),
+        prop::option::of(expression()).prop_map(|expr| concat([token("return"), expr.unwrap_or_default(), token(";")])),
+        Just(concat([token("break"), token(";")])),
+        (attributes(), select(&TYPES[..]), generic_parameters(), prop::collection::vec((select(&IDENTIFIERS[..]), type_kind()), 0..3), any::<bool>())
+            .prop_map(|(attributes, name, generic_parameters, fields, trailing_comma)| {
+                let fields = fields.into_iter().map(|(field, type_kind)| concat([token(field), token(":"), type_kind])).collect();
+                concat([attributes, token("struct"), token(name), generic_parameters, token("{"), comma_separated(fields, trailing_comma), token("}")])
+            }),
+        (attributes(), select(&TYPES[..]), generic_parameters(), prop::collection::vec((select(&TYPES[..]), 0..3usize, prop::collection::vec((select(&IDENTIFIERS[..]), select(&TYPES[..])), 0..3)), 0..3), any::<bool>())
+            .prop_map(|(attributes, name, generic_parameters, variants, trailing_comma)| {
+                let variants = variants.into_iter().map(|(variant, payload, fields)| match payload {
+                    0 => token(variant),
+                    1 => {
+                        let types = fields.into_iter().map(|(_, type_kind)| token(type_kind)).collect();
+                        concat([token(variant), token("("), comma_separated(types, trailing_comma), token(")")])
+                    },
+                    _ => {
+                        let fields = fields.into_iter().map(|(field, type_kind)| concat([token(field), token(":"), token(type_kind)])).collect();
+                        concat([token(variant), token("{"), comma_separated(fields, trailing_comma), token("}")])
+                    },
+                }).collect();
+                concat([attributes, token("enum"), token(name), generic_parameters, token("{"), comma_separated(variants, trailing_comma), token("}")])
+            }),
+    ];
+
+    simple.prop_recursive(3, 24, 4, |statement| {
+        let block = prop::collection::vec(statement, 0..4)
+            .prop_map(|statements| concat([token("{"), concat(statements), token("}")]));
+        prop_oneof![
+            block.clone(),
+            (expression(), block.clone(), prop::option::of(block.clone()))
+                .prop_map(|(condition, then, otherwise)| match otherwise {
+                    Some(otherwise) => concat([token("if"), condition, then, token("else"), otherwise]),
+                    None => concat([token("if"), condition, then]),
+                }),
+            (expression(), block.clone())
+                .prop_map(|(condition, body)| concat([token("while"), condition, body])),
+            (select(&IDENTIFIERS[..]), expression(), block.clone())
+                .prop_map(|(identifier, range, body)| concat([token("for"), token(identifier), token("in"), range, body])),
+            function(block.clone()),
+            (prop::option::of(select(&TYPES[..])), select(&TYPES[..]), prop::collection::vec(function(block.clone()), 0..3))
+                .prop_map(|(trait_name, type_kind, functions)| {
+                    let trait_for = trait_name.map(|trait_name| concat([token(trait_name), token("for")])).unwrap_or_default();
+                    concat([token("impl"), trait_for, token(type_kind), token("{"), concat(functions), token("}")])
+                }),
+            (select(&TYPES[..]), prop::collection::vec(prop_oneof![function(block), signature()], 0..3))
+                .prop_map(|(name, functions)| concat([token("trait"), token(name), token("{"), concat(functions), token("}")])),
+        ]
+    })
+}
+
+/// int, impl with up to three traits, or a generic type like List<int, Point<bool>>
+fn type_kind() -> impl Strategy<Value = Tokens> {
+    let leaf = prop_oneof![
+        select(&TYPES[..]).prop_map(token),
+        prop::collection::vec(select(&TYPES[..]).prop_map(token), 1..4)
+            .prop_map(|bounds| concat([token("impl"), bounds.join(&"+".to_string())])),
+    ];
+
+    leaf.prop_recursive(3, 12, 3, |type_kind| {
+        (select(&GENERIC_TYPES[..]), prop::collection::vec(type_kind, 0..3), any::<bool>())
+            .prop_map(|(name, arguments, trailing_comma)| concat([token(name), generic_arguments(arguments, trailing_comma)]))
+    })
+}
+
+/// <int, List<bool>>, the last two '>' are one '>>' like the lexer reads them
+fn generic_arguments(arguments: Vec<Tokens>, trailing_comma: bool) -> Tokens {
+    let mut tokens = concat([token("<"), comma_separated(arguments, trailing_comma)]);
+    match tokens.last_mut() {
+        Some(last) if last == ">" => *last = ">>".to_string(),
+        _ => tokens.push(">".to_string()),
+    }
+    tokens
+}
+
+/// <T: A + B, U> behind the name of a declaration, or nothing
+fn generic_parameters() -> impl Strategy<Value = Tokens> {
+    prop::option::of((prop::collection::vec((select(&TYPES[..]), prop::collection::vec(select(&TYPES[..]).prop_map(token), 0..3)), 0..3), any::<bool>()))
+        .prop_map(|generic_parameters| match generic_parameters {
+            Some((parameters, trailing_comma)) => {
+                let parameters = parameters.into_iter().map(|(name, bounds)| match bounds.is_empty() {
+                    true => token(name),
+                    false => concat([token(name), token(":"), bounds.join(&"+".to_string())]),
+                }).collect();
+                concat([token("<"), comma_separated(parameters, trailing_comma), token(">")])
+            },
+            None => vec![],
+        })
+}
+
+/// a fn declaration, with self or mut self in front of its parameters if receiver is set
+fn function(block: impl Strategy<Value = Tokens>) -> impl Strategy<Value = Tokens> {
+    (attributes(), select(&IDENTIFIERS[..]), generic_parameters(), prop::option::of(any::<bool>()), prop::collection::vec((select(&IDENTIFIERS[..]), type_kind()), 0..3), any::<bool>(), prop::option::of(type_kind()), block)
+        .prop_map(|(attributes, identifier, generic_parameters, receiver, parameters, trailing_comma, return_type, body)| {
+            let mut parameters: Vec<Tokens> = parameters.into_iter().map(|(name, type_kind)| concat([token(name), token(":"), type_kind])).collect();
+            match receiver {
+                Some(true) => parameters.insert(0, concat([token("mut"), token("self")])),
+                Some(false) => parameters.insert(0, token("self")),
+                None => {},
+            }
+            let return_type = return_type.map(|type_kind| concat([token("->"), type_kind])).unwrap_or_default();
+            concat([attributes, token("fn"), token(identifier), generic_parameters, token("("), comma_separated(parameters, trailing_comma), token(")"), return_type, body])
+        })
+}
+
+/// a fn declaration without a body, like the ones in traits
+fn signature() -> impl Strategy<Value = Tokens> {
+    function(Just(token(";")))
+}
+
+/// a valid program with random trivia around every token
+fn program() -> impl Strategy<Value = String> {
+    prop::collection::vec(statement(), 0..6)
+        .prop_map(concat)
+        .prop_flat_map(|tokens| {
+            let trivia = prop::collection::vec(prop::option::of(select(&TRIVIA[..])), tokens.len() + 1);
+            (Just(tokens), trivia)
+        })
+        .prop_map(|(tokens, trivia)| {
+            let mut src = String::new();
+            for (i, trivia) in trivia.into_iter().enumerate() {
+                // tokens need something between them, the edges of the source don't
+                let edge = i == 0 || i == tokens.len();
+                src.push_str(trivia.unwrap_or(if edge { "" } else { " " }));
+                if let Some(token) = tokens.get(i) {
+                    src.push_str(token);
+                }
+            }
+            src
+        })
+}
+
+/// any sequence of tokens, mostly not a valid program
+fn token_soup() -> impl Strategy<Value = String> {
+    let token = prop_oneof![
+        select(&IDENTIFIERS[..]).prop_map(String::from),
+        select(&LITERALS[..]).prop_map(String::from),
+        select(&BINARY_OPERATORS[..]).prop_map(String::from),
+        select(&PREFIX_OPERATORS[..]).prop_map(String::from),
+        select(&PUNCTUATION[..]).prop_map(String::from),
+    ];
+    prop::collection::vec(token, 0..32).prop_map(|tokens| tokens.join(" "))
+}
+
+proptest! {
+    #[test]
+    fn generated_programs_round_trip(src in program()) {
+        let ast = generate_ast(tokenize(&src));
+        prop_assert_eq!(ast.to_source(), src);
+    }
+
+    #[test]
+    fn token_soup_round_trips(src in token_soup()) {
+        let ast = generate_ast(tokenize(&src));
+        prop_assert_eq!(ast.to_source(), src);
+    }
+}
@@ -0,0 +1,743 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use ferrousc_ast::nodes::*;
+use ferrousc_diagnostics::{codes, Diagnostic, Diagnostics, Span};
+use ferrousc_lexer::{NumberSuffix, Token, TokenKind};
+
+use crate::{canonical, node_span, pattern_bindings, span};
+
+/// type arguments nested deeper than this come from a function that instantiates itself with ever larger types
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A concrete type like i32 or List<f64>. Internal types always have their short name, so int and i32 are the same type
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Type<'src> {
+    pub name: &'src str,
+    pub arguments: Vec<Type<'src>>,
+}
+
+impl<'src> Type<'src> {
+    fn named(name: &'src str) -> Type<'src> {
+        Type{ name, arguments: vec![] }
+    }
+
+    /// 1 for a type without type arguments
+    fn depth(&self) -> usize {
+        1 + self.arguments.iter().map(Type::depth).max().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Type<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.arguments.is_empty() {
+            let arguments: Vec<String> = self.arguments.iter().map(Type::to_string).collect();
+            write!(f, "<{}>", arguments.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A generic fn, struct or enum with a concrete type for each of its type parameters.
+/// It is displayed like max<i32>, which is unique for every instance and can be used as the name of its code
+pub struct Instance<'a, 'src> {
+    pub declaration: &'a Stat<'src>,
+    /// in the order of the type parameters of the declaration
+    pub type_arguments: Vec<Type<'src>>,
+}
+
+impl<'a, 'src> Instance<'a, 'src> {
+    pub fn name(&self) -> &'src str {
+        declaration_identifier(self.declaration).map_or("", |identifier| identifier.identifier.token.value)
+    }
+
+    /// the type argument of every type parameter, by the name of the parameter
+    pub fn substitution(&self) -> HashMap<&'src str, Type<'src>> {
+        type_parameters(self.declaration).into_iter().zip(self.type_arguments.iter().cloned()).collect()
+    }
+}
+
+impl fmt::Display for Instance<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Type{ name: self.name(), arguments: self.type_arguments.clone() })
+    }
+}
+
+/// the instances of the generic functions and types a program uses, generic code is only compiled for these
+pub struct Instances<'a, 'src> {
+    pub functions: Vec<Instance<'a, 'src>>,
+    pub types: Vec<Instance<'a, 'src>>,
+    /// the index in functions of the instance each call of a generic function calls,
+    /// by the index of the instance the call is in and the offset of the callee
+    calls: HashMap<(Option<usize>, usize), usize>,
+}
+
+impl<'a, 'src> Instances<'a, 'src> {
+    /// The instance a call of a generic function calls, None for a call of anything else.
+    /// caller is the index in functions of the instance the call is in, None outside of generic functions
+    pub fn callee(&self, caller: Option<usize>, callee: &Expr<'_>) -> Option<&Instance<'a, 'src>> {
+        self.calls.get(&(caller, node_span(callee).offset)).map(|index| &self.functions[*index])
+    }
+}
+
+/// Finds the instances of the generic functions and types a program uses, starting at the code that is not generic.
+/// Generic code is checked once, with its type parameters as opaque types, then walked again for every instance of it
+/// to find the instances it needs in turn. Type arguments are inferred from the types of the arguments of a call,
+/// as far as they are known without a type checker.
+pub fn monomorphize<'a, 'src>(unit: &'a CompilationUnit<'src>, diagnostics: &mut Diagnostics) -> Instances<'a, 'src> {
+    let mut monomorphizer = Monomorphizer::new(&unit.statements);
+    for statement in &unit.statements {
+        monomorphizer.walk_statement(statement);
+    }
+    while let Some(index) = monomorphizer.queue.pop_front() {
+        monomorphizer.walk_instance(index);
+    }
+
+    diagnostics.extend(monomorphizer.diagnostics);
+    monomorphizer.instances
+}
+
+struct Monomorphizer<'a, 'src> {
+    /// every top level function, the first declaration of a name wins
+    functions: HashMap<&'src str, &'a Stat<'src>>,
+    /// every top level struct and enum
+    types: HashMap<&'src str, &'a Stat<'src>>,
+    instances: Instances<'a, 'src>,
+    /// the function instances whose code was not walked yet
+    queue: VecDeque<usize>,
+    /// the types of the type parameters of the walked code. In generic code they are opaque types with the name of the parameter
+    substitution: HashMap<&'src str, Type<'src>>,
+    /// the function instance that is walked
+    caller: Option<usize>,
+    /// false in generic code, its types are not concrete
+    record: bool,
+    /// false in instances, their code was already checked as generic code
+    report: bool,
+    /// the innermost scope is the last one. None is a variable of an unknown type
+    scopes: Vec<HashMap<&'src str, Option<Type<'src>>>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, 'src> Monomorphizer<'a, 'src> {
+    fn new(statements: &'a [Stat<'src>]) -> Monomorphizer<'a, 'src> {
+        let mut functions = HashMap::new();
+        let mut types = HashMap::new();
+        for statement in statements {
+            match statement {
+                Stat::FunctionDefinition{ identifier, .. } => {
+                    functions.entry(identifier.identifier.token.value).or_insert(statement);
+                },
+                Stat::StructDefinition{ identifier, .. } | Stat::EnumDefinition{ identifier, .. } => {
+                    types.entry(identifier.identifier.token.value).or_insert(statement);
+                },
+                _ => {},
+            }
+        }
+
+        Monomorphizer {
+            functions,
+            types,
+            instances: Instances{ functions: vec![], types: vec![], calls: HashMap::new() },
+            queue: VecDeque::new(),
+            substitution: HashMap::new(),
+            caller: None,
+            record: true,
+            report: true,
+            scopes: vec![HashMap::new()],
+            diagnostics: vec![],
+        }
+    }
+
+    fn walk_statement(&mut self, statement: &'a Stat<'src>) {
+        match statement {
+            Stat::VarDefinition{ identifier, type_id, initial_value, .. } => {
+                let initial_type = initial_value.as_ref().and_then(|initial_value| self.walk_expression(&initial_value.expression));
+                let declared_type = match type_id {
+                    Some(type_id) => {
+                        self.check_type(&type_id.type_kind);
+                        self.resolve(&type_id.type_kind)
+                    },
+                    None => initial_type,
+                };
+                self.declare(identifier.identifier.token.value, declared_type);
+            },
+            Stat::Expr{ expr, .. } => {
+                self.walk_expression(expr);
+            },
+            Stat::Block{ statements, .. } => {
+                self.scopes.push(HashMap::new());
+                for statement in statements {
+                    self.walk_statement(statement);
+                }
+                self.scopes.pop();
+            },
+            Stat::For{ identifier, range, statement, .. } => {
+                self.walk_expression(range);
+                self.scopes.push(HashMap::new());
+                self.declare(identifier.identifier.token.value, None);
+                self.walk_statement(statement);
+                self.scopes.pop();
+            },
+            Stat::While{ expression, statement, .. } => {
+                self.walk_expression(expression);
+                self.walk_statement(statement);
+            },
+            Stat::If{ expression, statement, else_statement, .. } => {
+                self.walk_expression(expression);
+                self.walk_statement(statement);
+                if let Some(else_statement) = else_statement {
+                    self.walk_statement(else_statement);
+                }
+            },
+            Stat::Else{ statement, .. } => self.walk_statement(statement),
+            Stat::Return{ expression: Some(expression), .. } => {
+                self.walk_expression(expression);
+            },
+            Stat::FunctionDefinition{..} => self.walk_function(statement, None),
+            Stat::TraitDefinition{ functions, .. } => {
+                for function in functions {
+                    self.walk_function(function, None);
+                }
+            },
+            Stat::ImplBlock{ type_kind, functions, .. } => {
+                self.check_type(type_kind);
+                let self_type = self.resolve(type_kind);
+                for function in functions {
+                    self.walk_function(function, self_type.clone());
+                }
+            },
+            Stat::StructDefinition{ generic_parameters, fields, .. } => {
+                let outer = self.enter_generic(generic_parameters.as_ref());
+                for field in fields {
+                    self.check_type(&field.type_id.type_kind);
+                }
+                self.leave_generic(outer);
+            },
+            Stat::EnumDefinition{ generic_parameters, variants, .. } => {
+                let outer = self.enter_generic(generic_parameters.as_ref());
+                for variant in variants {
+                    match &variant.payload {
+                        Some(VariantPayload::Tuple{ fields, .. }) => fields.iter().for_each(|field| self.check_type(&field.type_kind)),
+                        Some(VariantPayload::Struct{ fields, .. }) => fields.iter().for_each(|field| self.check_type(&field.type_id.type_kind)),
+                        None => {},
+                    }
+                }
+                self.leave_generic(outer);
+            },
+            Stat::Return{ expression: None, .. }
+            | Stat::Break{..}
+            | Stat::Error{..} => {},
+        }
+    }
+
+    /// the signature and body of a function, as generic code if it has type parameters
+    fn walk_function(&mut self, statement: &'a Stat<'src>, self_type: Option<Type<'src>>) {
+        let (generic_parameters, parameter_list, return_type, body) = match statement {
+            Stat::FunctionDefinition{ generic_parameters, parameter_list, return_type, body, .. } => (generic_parameters, parameter_list, return_type, body),
+            _ => return,
+        };
+
+        let outer = self.enter_generic(generic_parameters.as_ref());
+        for parameter in &parameter_list.parameters {
+            self.check_type(&parameter.type_id.type_kind);
+        }
+        if let Some(return_type) = return_type {
+            self.check_type(&return_type.type_kind);
+        }
+
+        self.scopes.push(HashMap::new());
+        if parameter_list.receiver.is_some() {
+            self.declare("self", self_type);
+        }
+        self.walk_parameters_and_body(parameter_list, body);
+        self.scopes.pop();
+        self.leave_generic(outer);
+    }
+
+    /// the code of a generic function again, with the type arguments of one of its instances
+    fn walk_instance(&mut self, index: usize) {
+        let instance = &self.instances.functions[index];
+        let (parameter_list, body) = match instance.declaration {
+            Stat::FunctionDefinition{ parameter_list, body, .. } => (parameter_list, body),
+            _ => return,
+        };
+
+        self.substitution = instance.substitution();
+        self.caller = Some(index);
+        self.record = true;
+        self.report = false;
+
+        self.scopes.push(HashMap::new());
+        self.walk_parameters_and_body(parameter_list, body);
+        self.scopes.pop();
+    }
+
+    fn walk_parameters_and_body(&mut self, parameter_list: &'a ParameterList<'src>, body: &'a FunctionBody<'src>) {
+        for parameter in &parameter_list.parameters {
+            let parameter_type = self.resolve(&parameter.type_id.type_kind);
+            self.declare(parameter.identifier.identifier.token.value, parameter_type);
+        }
+
+        match body {
+            FunctionBody::BlockStatement{ block } => self.walk_statement(block),
+            FunctionBody::ExpressionBody{ statement, .. } => self.walk_statement(statement),
+            FunctionBody::Signature{..} => {},
+        }
+    }
+
+    /// Makes the type parameters of a declaration opaque types, its code is generic.
+    /// Returns the substitution and record from before, to restore them with leave_generic
+    fn enter_generic(&mut self, generic_parameters: Option<&'a GenericParameterList<'src>>) -> (HashMap<&'src str, Type<'src>>, bool) {
+        let outer = (self.substitution.clone(), self.record);
+        if let Some(generic_parameters) = generic_parameters {
+            for parameter in &generic_parameters.parameters {
+                let name = parameter.identifier.identifier.token.value;
+                self.substitution.insert(name, Type::named(name));
+            }
+            self.record = false;
+        }
+
+        outer
+    }
+
+    fn leave_generic(&mut self, (substitution, record): (HashMap<&'src str, Type<'src>>, bool)) {
+        self.substitution = substitution;
+        self.record = record;
+    }
+
+    /// the type of the expression, as far as it is known
+    fn walk_expression(&mut self, expr: &'a Expr<'src>) -> Option<Type<'src>> {
+        match expr {
+            Expr::Literal{ type_kind: Some(type_kind), .. } => {
+                self.check_type(type_kind);
+                self.resolve(type_kind)
+            },
+            Expr::Literal{ kind, type_kind: None } => match kind {
+                LiteralKind::Number{ number_literal } => number_type(&number_literal.token).map(Type::named),
+                LiteralKind::String{..} => Some(Type::named("string")),
+                LiteralKind::Char{..} => Some(Type::named("char")),
+                LiteralKind::Bool{..} => Some(Type::named("bool")),
+            },
+            Expr::Decorated{ expr, .. } => self.walk_expression(expr),
+            Expr::IdentifierUsage{ identifier } => self.lookup(identifier.identifier.token.value).cloned().flatten(),
+            Expr::Call{ callee, argument_list } => self.walk_call(callee, argument_list),
+            Expr::Turbofish{ expr, generic_arguments, .. } => {
+                self.walk_expression(expr);
+                for argument in &generic_arguments.arguments {
+                    self.check_type(&argument.type_kind);
+                }
+                None
+            },
+            Expr::StructLiteral{ identifier, fields, .. } => self.walk_struct_literal(identifier, fields),
+            Expr::FieldAccess{ expr, .. } | Expr::Unary{ operand: expr, .. } => {
+                self.walk_expression(expr);
+                None
+            },
+            Expr::Index{ lhs, expr: rhs, .. } | Expr::Binary{ lhs, rhs, .. } => {
+                self.walk_expression(lhs);
+                self.walk_expression(rhs);
+                None
+            },
+            Expr::Ternary{ lhs, mhs, rhs, .. } => {
+                self.walk_expression(lhs);
+                self.walk_expression(mhs);
+                self.walk_expression(rhs);
+                None
+            },
+            Expr::ArrayInitializer{ items, .. } => {
+                for item in items {
+                    self.walk_expression(&item.expr);
+                }
+                None
+            },
+            Expr::Interpolated{ parts, .. } => {
+                for part in parts {
+                    if let InterpolatedPart::Hole{ expr, .. } = part {
+                        self.walk_expression(expr);
+                    }
+                }
+                None
+            },
+            Expr::Match{ expr, body, .. } => {
+                self.walk_expression(expr);
+                for arm in &body.arms {
+                    self.scopes.push(HashMap::new());
+                    for name in pattern_bindings(&arm.pattern) {
+                        self.declare(name, None);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.walk_expression(&guard.expr);
+                    }
+                    self.walk_expression(&arm.expr);
+                    self.scopes.pop();
+                }
+                None
+            },
+            Expr::Error{..} => None,
+        }
+    }
+
+    /// the return type of the call. Calls of generic functions by their name are recorded with their instance
+    fn walk_call(&mut self, callee: &'a Expr<'src>, argument_list: &'a ArgumentList<'src>) -> Option<Type<'src>> {
+        let argument_types: Vec<Option<Type>> = argument_list.arguments.iter().map(|argument| self.walk_expression(&argument.expr)).collect();
+
+        let (identifier, generic_arguments) = match callee {
+            Expr::IdentifierUsage{ identifier } => (identifier, None),
+            Expr::Turbofish{ expr, generic_arguments, .. } => {
+                self.walk_expression(callee);
+                match expr.as_ref() {
+                    Expr::IdentifierUsage{ identifier } => (identifier, Some(generic_arguments)),
+                    _ => return None,
+                }
+            },
+            _ => {
+                self.walk_expression(callee);
+                return None;
+            },
+        };
+
+        // a variable with the same name is called instead
+        let name = identifier.identifier.token.value;
+        let function = match self.functions.get(name) {
+            Some(function) if self.lookup(name).is_none() => *function,
+            _ => return None,
+        };
+        let (declared, parameter_list, return_type) = match function {
+            Stat::FunctionDefinition{ identifier, parameter_list, return_type, .. } => (identifier, parameter_list, return_type),
+            _ => return None,
+        };
+        let type_parameters = type_parameters(function);
+
+        let type_arguments = match generic_arguments {
+            Some(generic_arguments) if generic_arguments.arguments.len() != type_parameters.len() => {
+                if self.report {
+                    self.diagnostics.push(wrong_type_argument_count(name, &declared.identifier, type_parameters.len(), generic_arguments.arguments.len(), node_span(generic_arguments)));
+                }
+                return None;
+            },
+            Some(generic_arguments) => generic_arguments.arguments.iter().map(|argument| self.resolve(&argument.type_kind)).collect::<Option<Vec<_>>>()?,
+            None if type_parameters.is_empty() => vec![],
+            None => self.infer(identifier, &type_parameters, &parameter_list.parameters, &argument_list.arguments, &argument_types)?,
+        };
+
+        let substitution: HashMap<&str, Type> = type_parameters.iter().copied().zip(type_arguments.iter().cloned()).collect();
+        let return_type = return_type.as_ref().and_then(|return_type| self.resolve_with(&return_type.type_kind, &substitution));
+
+        if !type_parameters.is_empty() && self.record {
+            if let Some(index) = self.instantiate(function, type_arguments, identifier) {
+                self.instances.calls.insert((self.caller, identifier.identifier.token.offset), index);
+            }
+            if let Some(return_type) = &return_type {
+                self.record_type(return_type);
+            }
+        }
+
+        return_type
+    }
+
+    /// the type arguments of a call without turbofish, from the types of its arguments
+    fn infer(&mut self, identifier: &Identifier<'src>, type_parameters: &[&'src str], parameters: &'a [Parameter<'src>], arguments: &'a [Argument<'src>], argument_types: &[Option<Type<'src>>]) -> Option<Vec<Type<'src>>> {
+        let name = identifier.identifier.token.value;
+        let mut inferred = HashMap::new();
+        for ((parameter, argument), argument_type) in parameters.iter().zip(arguments).zip(argument_types) {
+            let argument_type = match argument_type {
+                Some(argument_type) => argument_type,
+                None => continue,
+            };
+            if let Err(conflict) = unify(&parameter.type_id.type_kind, argument_type, type_parameters, &mut inferred, node_span(&argument.expr)) {
+                if self.report {
+                    self.diagnostics.push(conflict.diagnostic(name));
+                }
+                return None;
+            }
+        }
+
+        let mut type_arguments = vec![];
+        for type_parameter in type_parameters {
+            match inferred.remove(type_parameter) {
+                Some((type_argument, _)) => type_arguments.push(type_argument),
+                None => {
+                    // with arguments of unknown types it is only unknown for now
+                    let is_inferable = parameters.iter().any(|parameter| mentions(&parameter.type_id.type_kind, type_parameter));
+                    if !is_inferable && self.report {
+                        self.diagnostics.push(
+                            Diagnostic::error(codes::CANNOT_INFER_TYPE_ARGUMENT, format!("cannot infer type parameter `{}` of `{}`", type_parameter, name))
+                                .with_primary_label(span(&identifier.identifier), format!("cannot infer `{}`", type_parameter))
+                                .with_help(format!("give the type arguments with a turbofish, like `{}::<{}>`", name, type_parameters.join(", "))),
+                        );
+                    }
+                    return None;
+                },
+            }
+        }
+
+        Some(type_arguments)
+    }
+
+    /// the type of a struct literal, with the type arguments of a generic struct inferred from its fields
+    fn walk_struct_literal(&mut self, identifier: &Identifier<'src>, fields: &'a [FieldInitializer<'src>]) -> Option<Type<'src>> {
+        let field_types: Vec<Option<Type>> = fields.iter().map(|field| self.walk_expression(&field.expr)).collect();
+
+        let name = identifier.identifier.token.value;
+        let declaration = *self.types.get(name)?;
+        let definitions = match declaration {
+            Stat::StructDefinition{ fields, .. } => fields,
+            _ => return None,
+        };
+        let type_parameters = type_parameters(declaration);
+        if type_parameters.is_empty() {
+            return Some(Type::named(name));
+        }
+
+        let mut inferred = HashMap::new();
+        for (field, field_type) in fields.iter().zip(&field_types) {
+            let definition = definitions.iter().find(|definition| definition.identifier.identifier.token.value == field.identifier.identifier.token.value);
+            if let (Some(definition), Some(field_type)) = (definition, field_type) {
+                if let Err(conflict) = unify(&definition.type_id.type_kind, field_type, &type_parameters, &mut inferred, node_span(&field.expr)) {
+                    if self.report {
+                        self.diagnostics.push(conflict.diagnostic(name));
+                    }
+                    return None;
+                }
+            }
+        }
+
+        let arguments = type_parameters.iter().map(|type_parameter| inferred.remove(type_parameter).map(|(type_argument, _)| type_argument)).collect::<Option<Vec<_>>>()?;
+        let struct_type = Type{ name, arguments };
+        if self.record {
+            self.record_type(&struct_type);
+        }
+
+        Some(struct_type)
+    }
+
+    /// the index of the instance of the function with the type arguments, it is walked later if it is new
+    fn instantiate(&mut self, function: &'a Stat<'src>, type_arguments: Vec<Type<'src>>, identifier: &Identifier<'src>) -> Option<usize> {
+        let name = identifier.identifier.token.value;
+        if type_arguments.iter().any(|type_argument| type_argument.depth() > MAX_TYPE_DEPTH) {
+            // instances are walked without reporting, but this can only be found in them
+            self.diagnostics.push(
+                Diagnostic::error(codes::RECURSIVE_INSTANTIATION, format!("instantiating `{}` never ends", name))
+                    .with_primary_label(span(&identifier.identifier), format!("every instance of `{}` needs another one with larger types", name))
+                    .with_note(format!("type arguments can be nested at most {} levels deep", MAX_TYPE_DEPTH)),
+            );
+            return None;
+        }
+
+        let existing = self.instances.functions.iter().position(|instance| std::ptr::eq(instance.declaration, function) && instance.type_arguments == type_arguments);
+        if existing.is_some() {
+            return existing;
+        }
+
+        for type_argument in &type_arguments {
+            self.record_type(type_argument);
+        }
+        self.instances.functions.push(Instance{ declaration: function, type_arguments });
+        let index = self.instances.functions.len() - 1;
+        self.queue.push_back(index);
+        Some(index)
+    }
+
+    /// Reports type arguments that don't match the type parameters of their type,
+    /// and records the instances of generic types in code that is not generic
+    fn check_type(&mut self, type_kind: &TypeKind<'src>) {
+        let (identifier, generic_arguments) = match type_kind {
+            TypeKind::UserDefined{ identifier, generic_arguments } => (identifier, generic_arguments),
+            TypeKind::Internal{..} | TypeKind::ImplTrait{..} => return,
+        };
+
+        for argument in generic_arguments.iter().flat_map(|generic_arguments| &generic_arguments.arguments) {
+            self.check_type(&argument.type_kind);
+        }
+
+        let name = identifier.identifier.token.value;
+        let declaration = match self.types.get(name) {
+            Some(declaration) if !self.substitution.contains_key(name) => *declaration,
+            _ => return,
+        };
+        let expected = type_parameters(declaration).len();
+        let found = generic_arguments.as_ref().map_or(0, |generic_arguments| generic_arguments.arguments.len());
+        if expected != found {
+            if self.report {
+                let at = match generic_arguments {
+                    Some(generic_arguments) => node_span(generic_arguments),
+                    None => span(&identifier.identifier),
+                };
+                let declared = declaration_identifier(declaration).unwrap();
+                self.diagnostics.push(wrong_type_argument_count(name, &declared.identifier, expected, found, at));
+            }
+            return;
+        }
+
+        if self.record {
+            if let Some(resolved) = self.resolve(type_kind) {
+                self.record_type(&resolved);
+            }
+        }
+    }
+
+    /// adds the instances of the generic types in the type, including the type itself
+    fn record_type(&mut self, resolved: &Type<'src>) {
+        for argument in &resolved.arguments {
+            self.record_type(argument);
+        }
+
+        if resolved.arguments.is_empty() {
+            return;
+        }
+        if let Some(declaration) = self.types.get(resolved.name).copied() {
+            if !self.instances.types.iter().any(|instance| std::ptr::eq(instance.declaration, declaration) && instance.type_arguments == resolved.arguments) {
+                self.instances.types.push(Instance{ declaration, type_arguments: resolved.arguments.clone() });
+            }
+        }
+    }
+
+    fn resolve(&self, type_kind: &TypeKind<'src>) -> Option<Type<'src>> {
+        self.resolve_with(type_kind, &self.substitution)
+    }
+
+    /// the type with the type parameters replaced. None for an impl Trait, or a type with the wrong number of type arguments
+    fn resolve_with(&self, type_kind: &TypeKind<'src>, substitution: &HashMap<&'src str, Type<'src>>) -> Option<Type<'src>> {
+        match type_kind {
+            TypeKind::Internal{ identifier } => Some(Type::named(canonical(identifier.identifier.token.value))),
+            TypeKind::UserDefined{ identifier, generic_arguments } => {
+                let name = identifier.identifier.token.value;
+                if name.is_empty() {
+                    return None;
+                }
+                if let Some(type_argument) = substitution.get(name) {
+                    return Some(type_argument.clone());
+                }
+
+                let arguments = generic_arguments.iter()
+                    .flat_map(|generic_arguments| &generic_arguments.arguments)
+                    .map(|argument| self.resolve_with(&argument.type_kind, substitution))
+                    .collect::<Option<Vec<_>>>()?;
+                match self.types.get(name) {
+                    Some(declaration) if type_parameters(declaration).len() != arguments.len() => None,
+                    _ => Some(Type{ name, arguments }),
+                }
+            },
+            TypeKind::ImplTrait{..} => None,
+        }
+    }
+
+    fn declare(&mut self, name: &'src str, declared_type: Option<Type<'src>>) {
+        self.scopes.last_mut().unwrap().insert(name, declared_type);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Option<Type<'src>>> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+/// two arguments that need different types for the same type parameter
+struct Conflict<'src> {
+    type_parameter: &'src str,
+    previous: (Type<'src>, Span),
+    actual: (Type<'src>, Span),
+}
+
+impl Conflict<'_> {
+    fn diagnostic(&self, name: &str) -> Diagnostic {
+        Diagnostic::error(codes::CONFLICTING_TYPE_ARGUMENT, format!("conflicting types for type parameter `{}` of `{}`", self.type_parameter, name))
+            .with_primary_label(self.actual.1, format!("`{}` is `{}` here", self.type_parameter, self.actual.0))
+            .with_secondary_label(self.previous.1, format!("`{}` is `{}` here", self.type_parameter, self.previous.0))
+    }
+}
+
+/// Infers the type parameters in type_kind by matching it against the actual type, like T in List<T> against List<i32>.
+/// Parts that don't match are left to the type checker
+fn unify<'src>(type_kind: &TypeKind<'src>, actual: &Type<'src>, type_parameters: &[&'src str], inferred: &mut HashMap<&'src str, (Type<'src>, Span)>, at: Span) -> Result<(), Box<Conflict<'src>>> {
+    let (identifier, generic_arguments) = match type_kind {
+        TypeKind::UserDefined{ identifier, generic_arguments } => (identifier, generic_arguments),
+        TypeKind::Internal{..} | TypeKind::ImplTrait{..} => return Ok(()),
+    };
+    let name = identifier.identifier.token.value;
+
+    match generic_arguments {
+        None if type_parameters.contains(&name) => match inferred.get(name) {
+            Some(previous) if previous.0 != *actual => Err(Box::new(Conflict{ type_parameter: name, previous: previous.clone(), actual: (actual.clone(), at) })),
+            Some(_) => Ok(()),
+            None => {
+                inferred.insert(name, (actual.clone(), at));
+                Ok(())
+            },
+        },
+        Some(generic_arguments) if name == actual.name && generic_arguments.arguments.len() == actual.arguments.len() => {
+            for (argument, actual) in generic_arguments.arguments.iter().zip(&actual.arguments) {
+                unify(&argument.type_kind, actual, type_parameters, inferred, at)?;
+            }
+            Ok(())
+        },
+        _ => Ok(()),
+    }
+}
+
+/// whether the type parameter is part of the type, like T in List<T>
+fn mentions(type_kind: &TypeKind<'_>, type_parameter: &str) -> bool {
+    match type_kind {
+        TypeKind::UserDefined{ identifier, generic_arguments } => {
+            identifier.identifier.token.value == type_parameter
+                || generic_arguments.iter().flat_map(|generic_arguments| &generic_arguments.arguments).any(|argument| mentions(&argument.type_kind, type_parameter))
+        },
+        TypeKind::Internal{..} | TypeKind::ImplTrait{..} => false,
+    }
+}
+
+fn wrong_type_argument_count(name: &str, declared: &SyntaxToken<'_>, expected: usize, found: usize, at: Span) -> Diagnostic {
+    let expected = match expected {
+        1 => "1 type argument".to_string(),
+        expected => format!("{} type arguments", expected),
+    };
+    Diagnostic::error(codes::WRONG_TYPE_ARGUMENT_COUNT, format!("`{}` expects {}, found {}", name, expected, found))
+        .with_primary_label(at, format!("expected {}", expected))
+        .with_secondary_label(span(declared), "declared here")
+}
+
+fn declaration_identifier<'a, 'src>(statement: &'a Stat<'src>) -> Option<&'a Identifier<'src>> {
+    match statement {
+        Stat::FunctionDefinition{ identifier, .. }
+        | Stat::StructDefinition{ identifier, .. }
+        | Stat::EnumDefinition{ identifier, .. } => Some(identifier),
+        _ => None,
+    }
+}
+
+/// the names of the type parameters of a fn, struct or enum, empty if it is not generic
+fn type_parameters<'src>(statement: &Stat<'src>) -> Vec<&'src str> {
+    let generic_parameters = match statement {
+        Stat::FunctionDefinition{ generic_parameters, .. }
+        | Stat::StructDefinition{ generic_parameters, .. }
+        | Stat::EnumDefinition{ generic_parameters, .. } => generic_parameters,
+        _ => return vec![],
+    };
+
+    generic_parameters.iter()
+        .flat_map(|generic_parameters| &generic_parameters.parameters)
+        .map(|parameter| parameter.identifier.identifier.token.value)
+        .collect()
+}
+
+/// i32 or f64 without a suffix, depending on whether the number has a fraction or exponent
+fn number_type(token: &Token<'_>) -> Option<&'static str> {
+    match token.kind {
+        TokenKind::NumberLiteral{ suffix: Some(suffix), .. } => match suffix {
+            NumberSuffix::I8 => Some("i8"),
+            NumberSuffix::I16 => Some("i16"),
+            NumberSuffix::I32 => Some("i32"),
+            NumberSuffix::I64 => Some("i64"),
+            NumberSuffix::U8 => Some("u8"),
+            NumberSuffix::U16 => Some("u16"),
+            NumberSuffix::U32 => Some("u32"),
+            NumberSuffix::U64 => Some("u64"),
+            NumberSuffix::F32 => Some("f32"),
+            NumberSuffix::F64 => Some("f64"),
+            NumberSuffix::Invalid => None,
+        },
+        TokenKind::NumberLiteral{ has_exponent, .. } if has_exponent || token.value.contains('.') => Some("f64"),
+        TokenKind::NumberLiteral{..} => Some("i32"),
+        _ => None,
+    }
+}
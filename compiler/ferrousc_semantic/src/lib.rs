@@ -0,0 +1,63 @@
+mod generics;
+mod traits;
+
+pub use generics::{monomorphize, Instance, Instances, Type};
+
+use ferrousc_ast::nodes::{CompilationUnit, MatchPattern, PatternPayload, SyntaxToken};
+use ferrousc_ast::source::node_span;
+use ferrousc_diagnostics::{Diagnostics, Span};
+
+/// the internal types with another name, every internal type is known by the second one
+const TYPE_ALIASES: [(&str, &str); 10] = [
+    ("sbyte", "i8"),
+    ("short", "i16"),
+    ("int", "i32"),
+    ("long", "i64"),
+    ("byte", "u8"),
+    ("ushort", "u16"),
+    ("uint", "u32"),
+    ("ulong", "u64"),
+    ("float", "f32"),
+    ("double", "f64"),
+];
+
+/// Reports what is wrong with a program that parses, like an impl that misses a function of its trait.
+/// Error nodes and missing tokens are skipped, the parser already reported them.
+pub fn check(unit: &CompilationUnit<'_>, diagnostics: &mut Diagnostics) {
+    traits::check_traits(unit, diagnostics);
+    monomorphize(unit, diagnostics);
+}
+
+/// the name an internal type is known by, other names are returned as they are
+pub(crate) fn canonical(name: &str) -> &str {
+    TYPE_ALIASES.iter().find(|(alias, _)| *alias == name).map_or(name, |(_, canonical)| canonical)
+}
+
+pub(crate) fn span(token: &SyntaxToken<'_>) -> Span {
+    Span::new(token.token.offset, token.token.len)
+}
+
+/// the names of the variables a pattern binds
+pub(crate) fn pattern_bindings<'src>(pattern: &MatchPattern<'src>) -> Vec<&'src str> {
+    match pattern {
+        MatchPattern::Identifier(identifier) => vec![identifier.identifier.token.value],
+        MatchPattern::Or{ lhs, rhs, .. } => {
+            let mut bindings = pattern_bindings(lhs);
+            bindings.extend(pattern_bindings(rhs));
+            bindings
+        },
+        MatchPattern::Variant{ payload: Some(PatternPayload::Tuple{ patterns, .. }), .. } => {
+            patterns.iter().flat_map(|item| pattern_bindings(&item.pattern)).collect()
+        },
+        MatchPattern::Variant{ payload: Some(PatternPayload::Struct{ fields, .. }), .. } => {
+            fields.iter().flat_map(|field| match &field.value {
+                Some(value) => pattern_bindings(&value.pattern),
+                None => vec![field.identifier.identifier.token.value],
+            }).collect()
+        },
+        MatchPattern::Variant{ payload: None, .. }
+        | MatchPattern::Literal(_)
+        | MatchPattern::Wildcard(_)
+        | MatchPattern::Range{..} => vec![],
+    }
+}
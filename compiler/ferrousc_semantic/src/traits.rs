@@ -0,0 +1,571 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use ferrousc_ast::nodes::*;
+use ferrousc_diagnostics::{codes, Diagnostic, Diagnostics, Span};
+
+use crate::{canonical, node_span, pattern_bindings, span};
+
+/// Checks that every impl of a trait implements the functions the trait declares without a body, with the same signature,
+/// and that the arguments for impl Trait parameters and bounded type parameters implement their traits.
+/// Traits, impls, functions and generic types are only looked up at the top level.
+pub(crate) fn check_traits<'a, 'src>(unit: &'a CompilationUnit<'src>, diagnostics: &mut Diagnostics) {
+    let mut checker = Checker::new(&unit.statements);
+    for statement in &unit.statements {
+        checker.check_statement(statement);
+    }
+    diagnostics.extend(checker.diagnostics);
+}
+
+/// the type of an expression, as far as it is known without inference
+#[derive(Clone, Debug, PartialEq)]
+enum KnownType<'src> {
+    /// Circle or i32
+    Named(&'src str),
+    /// impl Shape + Named, only the traits are known
+    Bounded(Vec<&'src str>),
+}
+
+impl fmt::Display for KnownType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KnownType::Named(name) => write!(f, "{}", name),
+            KnownType::Bounded(traits) => write!(f, "impl {}", traits.join(" + ")),
+        }
+    }
+}
+
+/// what an impl has to repeat of a function of its trait, the names of the parameters and type parameters don't matter
+struct Signature<'a, 'src> {
+    name: &'src str,
+    /// the type parameters with their bounds
+    type_parameters: Vec<(&'src str, Vec<&'src str>)>,
+    receiver: bool,
+    parameters: Vec<&'a TypeKind<'src>>,
+    return_type: Option<&'a TypeKind<'src>>,
+}
+
+impl Signature<'_, '_> {
+    /// the signature with every type parameter written by its position, so fn f<T>(T) and fn f<U>(U) are the same
+    fn positional(&self) -> (Vec<Vec<&str>>, bool, Vec<String>, Option<String>) {
+        let names: Vec<&str> = self.type_parameters.iter().map(|(name, _)| *name).collect();
+        let bounds = self.type_parameters.iter().map(|(_, bounds)| {
+            let mut bounds = bounds.clone();
+            bounds.sort_unstable();
+            bounds
+        }).collect();
+        let parameters = self.parameters.iter().map(|type_kind| type_name(type_kind, &names)).collect();
+        (bounds, self.receiver, parameters, self.return_type.map(|return_type| type_name(return_type, &names)))
+    }
+}
+
+impl PartialEq for Signature<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.positional() == other.positional()
+    }
+}
+
+impl fmt::Display for Signature<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fn {}", self.name)?;
+        if !self.type_parameters.is_empty() {
+            let type_parameters: Vec<String> = self.type_parameters.iter().map(|(name, bounds)| {
+                if bounds.is_empty() { name.to_string() } else { format!("{}: {}", name, bounds.join(" + ")) }
+            }).collect();
+            write!(f, "<{}>", type_parameters.join(", "))?;
+        }
+        let receiver = if self.receiver { Some("self".to_string()) } else { None };
+        let parameters: Vec<String> = receiver.into_iter().chain(self.parameters.iter().map(|type_kind| type_name(type_kind, &[]))).collect();
+        write!(f, "({})", parameters.join(", "))?;
+        if let Some(return_type) = self.return_type {
+            write!(f, " -> {}", type_name(return_type, &[]))?;
+        }
+        Ok(())
+    }
+}
+
+/// a function of a trait or of an impl of one
+struct Function<'a, 'src> {
+    identifier: &'a SyntaxToken<'src>,
+    signature: Signature<'a, 'src>,
+    has_body: bool,
+}
+
+struct Trait<'a, 'src> {
+    identifier: &'a SyntaxToken<'src>,
+    functions: Vec<Function<'a, 'src>>,
+}
+
+struct Checker<'a, 'src> {
+    traits: HashMap<&'src str, Trait<'a, 'src>>,
+    /// the type and trait of every impl Trait for Type
+    impls: HashSet<(&'src str, &'src str)>,
+    /// the type parameters and parameters of every function, to check the arguments of calls against their bounds
+    functions: HashMap<&'src str, (Option<&'a GenericParameterList<'src>>, &'a [Parameter<'src>])>,
+    /// the type parameters of every generic struct and enum
+    generic_types: HashMap<&'src str, &'a GenericParameterList<'src>>,
+    /// the bounds of the type parameters of the function that is checked
+    type_parameters: HashMap<&'src str, Vec<&'src str>>,
+    /// the innermost scope is the last one. None is a variable of an unknown type
+    scopes: Vec<HashMap<&'src str, Option<KnownType<'src>>>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, 'src> Checker<'a, 'src> {
+    fn new(statements: &'a [Stat<'src>]) -> Checker<'a, 'src> {
+        let mut traits = HashMap::new();
+        let mut impls = HashSet::new();
+        let mut functions = HashMap::new();
+        let mut generic_types = HashMap::new();
+
+        // the first declaration of a name wins
+        for statement in statements {
+            match statement {
+                Stat::TraitDefinition{ identifier, functions, .. } if !identifier.identifier.token.value.is_empty() => {
+                    traits.entry(identifier.identifier.token.value).or_insert_with(|| Trait {
+                        identifier: &identifier.identifier,
+                        functions: functions.iter().filter_map(function).collect(),
+                    });
+                },
+                Stat::ImplBlock{ trait_for: Some(trait_for), type_kind, .. } => {
+                    if let Some(KnownType::Named(type_name)) = known_type(type_kind) {
+                        impls.insert((type_name, trait_for.identifier.identifier.token.value));
+                    }
+                },
+                Stat::FunctionDefinition{ identifier, generic_parameters, parameter_list, .. } => {
+                    functions.entry(identifier.identifier.token.value).or_insert((generic_parameters.as_ref(), &parameter_list.parameters[..]));
+                },
+                Stat::StructDefinition{ identifier, generic_parameters: Some(generic_parameters), .. }
+                | Stat::EnumDefinition{ identifier, generic_parameters: Some(generic_parameters), .. } => {
+                    generic_types.entry(identifier.identifier.token.value).or_insert(generic_parameters);
+                },
+                _ => {},
+            }
+        }
+
+        Checker { traits, impls, functions, generic_types, type_parameters: HashMap::new(), scopes: vec![HashMap::new()], diagnostics: vec![] }
+    }
+
+    fn check_statement(&mut self, statement: &'a Stat<'src>) {
+        match statement {
+            Stat::VarDefinition{ identifier, type_id, initial_value, .. } => {
+                if let Some(initial_value) = initial_value {
+                    self.check_expression(&initial_value.expression);
+                }
+                let known_type = match type_id {
+                    Some(type_id) => {
+                        self.check_bounds(&type_id.type_kind);
+                        known_type(&type_id.type_kind)
+                    },
+                    None => initial_value.as_ref().and_then(|initial_value| self.type_of(&initial_value.expression)),
+                };
+                self.declare(identifier.identifier.token.value, known_type);
+            },
+            Stat::Expr{ expr, .. } => self.check_expression(expr),
+            Stat::Block{ statements, .. } => {
+                self.scopes.push(HashMap::new());
+                for statement in statements {
+                    self.check_statement(statement);
+                }
+                self.scopes.pop();
+            },
+            Stat::For{ identifier, range, statement, .. } => {
+                self.check_expression(range);
+                self.scopes.push(HashMap::new());
+                self.declare(identifier.identifier.token.value, None);
+                self.check_statement(statement);
+                self.scopes.pop();
+            },
+            Stat::While{ expression, statement, .. } => {
+                self.check_expression(expression);
+                self.check_statement(statement);
+            },
+            Stat::If{ expression, statement, else_statement, .. } => {
+                self.check_expression(expression);
+                self.check_statement(statement);
+                if let Some(else_statement) = else_statement {
+                    self.check_statement(else_statement);
+                }
+            },
+            Stat::Else{ statement, .. } => self.check_statement(statement),
+            Stat::Return{ expression: Some(expression), .. } => self.check_expression(expression),
+            Stat::FunctionDefinition{..} => self.check_function(statement, None, false),
+            Stat::TraitDefinition{ identifier, functions, .. } => {
+                let self_type = KnownType::Bounded(vec![identifier.identifier.token.value]);
+                for function in functions {
+                    self.check_function(function, Some(self_type.clone()), true);
+                }
+            },
+            Stat::ImplBlock{ trait_for, type_kind, functions, .. } => {
+                if let Some(trait_for) = trait_for {
+                    self.check_impl_of_trait(trait_for, type_kind, functions);
+                }
+                let self_type = known_type(type_kind);
+                for function in functions {
+                    self.check_function(function, self_type.clone(), false);
+                }
+            },
+            Stat::StructDefinition{ generic_parameters, fields, .. } => {
+                let type_parameters = self.enter_type_parameters(generic_parameters.as_ref());
+                for field in fields {
+                    self.check_bounds(&field.type_id.type_kind);
+                }
+                self.type_parameters = type_parameters;
+            },
+            Stat::EnumDefinition{ generic_parameters, variants, .. } => {
+                let type_parameters = self.enter_type_parameters(generic_parameters.as_ref());
+                for variant in variants {
+                    match &variant.payload {
+                        Some(VariantPayload::Tuple{ fields, .. }) => fields.iter().for_each(|field| self.check_bounds(&field.type_kind)),
+                        Some(VariantPayload::Struct{ fields, .. }) => fields.iter().for_each(|field| self.check_bounds(&field.type_id.type_kind)),
+                        None => {},
+                    }
+                }
+                self.type_parameters = type_parameters;
+            },
+            Stat::Return{ expression: None, .. }
+            | Stat::Break{..}
+            | Stat::Error{..} => {},
+        }
+    }
+
+    /// the body of a function, with self of type self_type if it has a receiver. Only functions in traits can leave out their body
+    fn check_function(&mut self, statement: &'a Stat<'src>, self_type: Option<KnownType<'src>>, in_trait: bool) {
+        let (identifier, generic_parameters, parameter_list, return_type, body) = match statement {
+            Stat::FunctionDefinition{ identifier, generic_parameters, parameter_list, return_type, body, .. } => (identifier, generic_parameters, parameter_list, return_type, body),
+            _ => return,
+        };
+
+        let type_parameters = self.enter_type_parameters(generic_parameters.as_ref());
+
+        for parameter in &parameter_list.parameters {
+            self.check_bounds(&parameter.type_id.type_kind);
+        }
+        if let Some(return_type) = return_type {
+            self.check_bounds(&return_type.type_kind);
+        }
+
+        self.scopes.push(HashMap::new());
+        if parameter_list.receiver.is_some() {
+            self.declare("self", self_type);
+        }
+        for parameter in &parameter_list.parameters {
+            self.declare(parameter.identifier.identifier.token.value, known_type(&parameter.type_id.type_kind));
+        }
+
+        match body.as_ref() {
+            FunctionBody::BlockStatement{ block } => self.check_statement(block),
+            FunctionBody::ExpressionBody{ statement, .. } => self.check_statement(statement),
+            FunctionBody::Signature{ semicolon_token } if !in_trait => self.diagnostics.push(
+                Diagnostic::error(codes::MISSING_FUNCTION_BODY, format!("`{}` has no body", identifier.identifier.token.value))
+                    .with_primary_label(span(&identifier.identifier), "")
+                    .with_note("only functions of traits can leave out their body")
+                    .with_suggestion(span(semicolon_token), " {}", "add a body"),
+            ),
+            FunctionBody::Signature{..} => {},
+        }
+        self.scopes.pop();
+        self.type_parameters = type_parameters;
+    }
+
+    /// Adds the type parameters of a declaration with their bounds, and checks that the bounds are traits.
+    /// Returns the type parameters from before, to restore them at the end of the declaration
+    fn enter_type_parameters(&mut self, generic_parameters: Option<&'a GenericParameterList<'src>>) -> HashMap<&'src str, Vec<&'src str>> {
+        let mut type_parameters = self.type_parameters.clone();
+        for parameter in generic_parameters.iter().flat_map(|generic_parameters| &generic_parameters.parameters) {
+            let bounds = parameter.bounds.iter().flat_map(|bounds| &bounds.bounds);
+            for bound in bounds.clone() {
+                self.check_trait(&bound.identifier.identifier);
+            }
+            type_parameters.insert(parameter.identifier.identifier.token.value, bounds.map(|bound| bound.identifier.identifier.token.value).collect());
+        }
+
+        std::mem::replace(&mut self.type_parameters, type_parameters)
+    }
+
+    fn check_impl_of_trait(&mut self, trait_for: &'a TraitFor<'src>, type_kind: &'a TypeKind<'src>, functions: &'a [Stat<'src>]) {
+        let trait_name = trait_for.identifier.identifier.token.value;
+        if trait_name.is_empty() {
+            return;
+        }
+        let declaration = match self.traits.get(trait_name) {
+            Some(declaration) => declaration,
+            None => {
+                self.diagnostics.push(unknown_trait(&trait_for.identifier.identifier));
+                return;
+            },
+        };
+
+        let mut diagnostics = vec![];
+        let implemented: Vec<Function> = functions.iter().filter_map(function).collect();
+        for function in &implemented {
+            match declaration.functions.iter().find(|declared| declared.signature.name == function.signature.name) {
+                Some(declared) if declared.signature != function.signature => diagnostics.push(
+                    Diagnostic::error(codes::TRAIT_FUNCTION_MISMATCH, format!("`{}` does not match its declaration in trait `{}`", function.signature.name, trait_name))
+                        .with_primary_label(span(function.identifier), format!("expected `{}`", declared.signature))
+                        .with_secondary_label(span(declared.identifier), "declared here")
+                        .with_note(format!("found `{}`", function.signature)),
+                ),
+                Some(_) => {},
+                None => diagnostics.push(
+                    Diagnostic::error(codes::NOT_A_TRAIT_FUNCTION, format!("`{}` is not a function of trait `{}`", function.signature.name, trait_name))
+                        .with_primary_label(span(function.identifier), "")
+                        .with_secondary_label(span(declaration.identifier), "trait declared here")
+                        .with_help("move it into an impl block without a trait"),
+                ),
+            }
+        }
+
+        for declared in declaration.functions.iter().filter(|declared| !declared.has_body) {
+            if !implemented.iter().any(|function| function.signature.name == declared.signature.name) {
+                diagnostics.push(
+                    Diagnostic::error(codes::MISSING_TRAIT_FUNCTION, format!("missing `{}` in impl of `{}` for `{}`", declared.signature.name, trait_name, type_name(type_kind, &[])))
+                        .with_primary_label(span(&trait_for.identifier.identifier), format!("missing `{}`", declared.signature.name))
+                        .with_secondary_label(span(declared.identifier), "declared here")
+                        .with_help(format!("implement `{}`", declared.signature)),
+                );
+            }
+        }
+
+        self.diagnostics.extend(diagnostics);
+    }
+
+    /// every trait in an impl Trait has to be declared, and the type arguments of generic types have to implement their bounds
+    fn check_bounds(&mut self, type_kind: &'a TypeKind<'src>) {
+        match type_kind {
+            TypeKind::ImplTrait{ bounds, .. } => {
+                for bound in bounds {
+                    self.check_trait(&bound.identifier.identifier);
+                }
+            },
+            TypeKind::UserDefined{ identifier, generic_arguments: Some(generic_arguments) } => {
+                for argument in &generic_arguments.arguments {
+                    self.check_bounds(&argument.type_kind);
+                }
+                if let Some(generic_parameters) = self.generic_types.get(identifier.identifier.token.value) {
+                    self.check_type_arguments(generic_parameters, generic_arguments);
+                }
+            },
+            TypeKind::UserDefined{..} | TypeKind::Internal{..} => {},
+        }
+    }
+
+    fn check_trait(&mut self, identifier: &SyntaxToken<'src>) {
+        let name = identifier.token.value;
+        if !name.is_empty() && !self.traits.contains_key(name) {
+            self.diagnostics.push(unknown_trait(identifier));
+        }
+    }
+
+    /// explicit type arguments, like the int in List<int> or max::<int>, have to implement the bounds of their parameter
+    fn check_type_arguments(&mut self, generic_parameters: &'a GenericParameterList<'src>, generic_arguments: &'a GenericArgumentList<'src>) {
+        for (argument, parameter) in generic_arguments.arguments.iter().zip(&generic_parameters.parameters) {
+            if let Some(argument_type) = known_type(&argument.type_kind) {
+                for bound in parameter.bounds.iter().flat_map(|bounds| &bounds.bounds) {
+                    self.check_bound(&argument_type, node_span(&argument.type_kind), bound);
+                }
+            }
+        }
+    }
+
+    /// unknown traits are reported at the bound
+    fn check_bound(&mut self, known_type: &KnownType<'src>, at: Span, bound: &TraitBound<'src>) {
+        let trait_name = bound.identifier.identifier.token.value;
+        if self.traits.contains_key(trait_name) && !self.implements(known_type, trait_name) {
+            self.diagnostics.push(
+                Diagnostic::error(codes::UNSATISFIED_TRAIT_BOUND, format!("`{}` does not implement trait `{}`", known_type, trait_name))
+                    .with_primary_label(at, format!("`{}` does not implement `{}`", known_type, trait_name))
+                    .with_secondary_label(span(&bound.identifier.identifier), "required by this bound"),
+            );
+        }
+    }
+
+    fn check_expression(&mut self, expr: &'a Expr<'src>) {
+        match expr {
+            Expr::Decorated{ expr, .. }
+            | Expr::FieldAccess{ expr, .. }
+            | Expr::Unary{ operand: expr, .. } => self.check_expression(expr),
+            Expr::Turbofish{ expr, generic_arguments, .. } => {
+                self.check_expression(expr);
+                for argument in &generic_arguments.arguments {
+                    self.check_bounds(&argument.type_kind);
+                }
+            },
+            Expr::Index{ lhs, expr: rhs, .. } | Expr::Binary{ lhs, rhs, .. } => {
+                self.check_expression(lhs);
+                self.check_expression(rhs);
+            },
+            Expr::Ternary{ lhs, mhs, rhs, .. } => {
+                self.check_expression(lhs);
+                self.check_expression(mhs);
+                self.check_expression(rhs);
+            },
+            Expr::ArrayInitializer{ items, .. } => {
+                for item in items {
+                    self.check_expression(&item.expr);
+                }
+            },
+            Expr::Interpolated{ parts, .. } => {
+                for part in parts {
+                    if let InterpolatedPart::Hole{ expr, .. } = part {
+                        self.check_expression(expr);
+                    }
+                }
+            },
+            Expr::StructLiteral{ fields, .. } => {
+                for field in fields {
+                    self.check_expression(&field.expr);
+                }
+            },
+            Expr::Match{ expr, body, .. } => {
+                self.check_expression(expr);
+                for arm in &body.arms {
+                    self.scopes.push(HashMap::new());
+                    for name in pattern_bindings(&arm.pattern) {
+                        self.declare(name, None);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.check_expression(&guard.expr);
+                    }
+                    self.check_expression(&arm.expr);
+                    self.scopes.pop();
+                }
+            },
+            Expr::Call{ callee, argument_list } => {
+                self.check_expression(callee);
+                for argument in &argument_list.arguments {
+                    self.check_expression(&argument.expr);
+                }
+                match callee.as_ref() {
+                    Expr::IdentifierUsage{ identifier } => self.check_arguments(identifier.identifier.token.value, None, &argument_list.arguments),
+                    Expr::Turbofish{ expr, generic_arguments, .. } => {
+                        if let Expr::IdentifierUsage{ identifier } = expr.as_ref() {
+                            self.check_arguments(identifier.identifier.token.value, Some(generic_arguments), &argument_list.arguments);
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Expr::Literal{..} | Expr::IdentifierUsage{..} | Expr::Error{..} => {},
+        }
+    }
+
+    /// The arguments of a call have to implement the traits of impl Trait parameters and of bounded type parameters.
+    /// With explicit type arguments, the type arguments are checked against the bounds instead of the arguments
+    fn check_arguments(&mut self, name: &'src str, generic_arguments: Option<&'a GenericArgumentList<'src>>, arguments: &'a [Argument<'src>]) {
+        // a variable with the same name is called instead
+        let (generic_parameters, parameters) = match self.functions.get(name) {
+            Some(function) if self.lookup(name).is_none() => *function,
+            _ => return,
+        };
+
+        if let (Some(generic_parameters), Some(generic_arguments)) = (generic_parameters, generic_arguments) {
+            self.check_type_arguments(generic_parameters, generic_arguments);
+        }
+
+        for (argument, parameter) in arguments.iter().zip(parameters) {
+            let bounds: Vec<&TraitBound> = match &parameter.type_id.type_kind {
+                TypeKind::ImplTrait{ bounds, .. } => bounds.iter().collect(),
+                TypeKind::UserDefined{ identifier, generic_arguments: None } if generic_arguments.is_none() => generic_parameters.iter()
+                    .flat_map(|generic_parameters| &generic_parameters.parameters)
+                    .filter(|generic_parameter| generic_parameter.identifier.identifier.token.value == identifier.identifier.token.value)
+                    .flat_map(|generic_parameter| generic_parameter.bounds.iter().flat_map(|bounds| &bounds.bounds))
+                    .collect(),
+                _ => continue,
+            };
+            let argument_type = match self.type_of(&argument.expr) {
+                Some(argument_type) => argument_type,
+                None => continue,
+            };
+
+            for bound in bounds {
+                self.check_bound(&argument_type, node_span(&argument.expr), bound);
+            }
+        }
+    }
+
+    fn implements(&self, known_type: &KnownType<'src>, trait_name: &'src str) -> bool {
+        match known_type {
+            KnownType::Named(type_name) => match self.type_parameters.get(type_name) {
+                Some(bounds) => bounds.contains(&trait_name),
+                None => self.impls.contains(&(*type_name, trait_name)),
+            },
+            KnownType::Bounded(traits) => traits.contains(&trait_name),
+        }
+    }
+
+    fn type_of(&self, expr: &Expr<'src>) -> Option<KnownType<'src>> {
+        match expr {
+            Expr::StructLiteral{ identifier, .. } => Some(KnownType::Named(identifier.identifier.token.value)),
+            Expr::IdentifierUsage{ identifier } => self.lookup(identifier.identifier.token.value).cloned().flatten(),
+            Expr::Decorated{ expr, .. } => self.type_of(expr),
+            _ => None,
+        }
+    }
+
+    fn declare(&mut self, name: &'src str, known_type: Option<KnownType<'src>>) {
+        self.scopes.last_mut().unwrap().insert(name, known_type);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Option<KnownType<'src>>> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+fn function<'a, 'src>(statement: &'a Stat<'src>) -> Option<Function<'a, 'src>> {
+    match statement {
+        Stat::FunctionDefinition{ identifier, generic_parameters, parameter_list, return_type, body, .. } => Some(Function {
+            identifier: &identifier.identifier,
+            signature: Signature {
+                name: identifier.identifier.token.value,
+                type_parameters: generic_parameters.iter().flat_map(|generic_parameters| &generic_parameters.parameters).map(|parameter| (
+                    parameter.identifier.identifier.token.value,
+                    parameter.bounds.iter().flat_map(|bounds| &bounds.bounds).map(|bound| bound.identifier.identifier.token.value).collect(),
+                )).collect(),
+                receiver: parameter_list.receiver.is_some(),
+                parameters: parameter_list.parameters.iter().map(|parameter| &parameter.type_id.type_kind).collect(),
+                return_type: return_type.as_ref().map(|return_type| &return_type.type_kind),
+            },
+            has_body: !matches!(body.as_ref(), FunctionBody::Signature{..}),
+        }),
+        _ => None,
+    }
+}
+
+fn known_type<'src>(type_kind: &TypeKind<'src>) -> Option<KnownType<'src>> {
+    match type_kind {
+        TypeKind::UserDefined{ identifier, .. } | TypeKind::Internal{ identifier } if !identifier.identifier.token.value.is_empty() => {
+            Some(KnownType::Named(canonical(identifier.identifier.token.value)))
+        },
+        TypeKind::ImplTrait{ bounds, .. } => Some(KnownType::Bounded(bounds.iter().map(|bound| bound.identifier.identifier.token.value).collect())),
+        _ => None,
+    }
+}
+
+/// The type with internal types by their short name, for messages and to compare signatures.
+/// The type parameters in `type_parameters` are written by their position, which no other type can be named
+fn type_name(type_kind: &TypeKind<'_>, type_parameters: &[&str]) -> String {
+    match type_kind {
+        TypeKind::UserDefined{ identifier, generic_arguments: Some(generic_arguments) } => {
+            let arguments: Vec<String> = generic_arguments.arguments.iter().map(|argument| type_name(&argument.type_kind, type_parameters)).collect();
+            return format!("{}<{}>", identifier.identifier.token.value, arguments.join(", "));
+        },
+        TypeKind::UserDefined{ identifier, generic_arguments: None } => {
+            if let Some(position) = type_parameters.iter().position(|name| *name == identifier.identifier.token.value) {
+                return position.to_string();
+            }
+        },
+        _ => {},
+    }
+
+    match known_type(type_kind) {
+        Some(known_type) => known_type.to_string(),
+        None => String::new(),
+    }
+}
+
+fn unknown_trait(identifier: &SyntaxToken<'_>) -> Diagnostic {
+    Diagnostic::error(codes::UNKNOWN_TRAIT, format!("cannot find trait `{}`", identifier.token.value))
+        .with_primary_label(span(identifier), "not a trait")
+}
+
@@ -0,0 +1,132 @@
+use expect_test::{expect, Expect};
+use ferrousc_diagnostics::Diagnostics;
+use ferrousc_lexer::tokenize;
+use ferrousc_parser::generate_ast;
+
+fn check(src: &str, expect: Expect) {
+    let ast = generate_ast(tokenize(src));
+    let mut diagnostics = Diagnostics::new();
+    let instances = ferrousc_semantic::monomorphize(&ast, &mut diagnostics);
+    diagnostics.sort();
+
+    let mut actual = String::new();
+    for instance in &instances.functions {
+        actual.push_str(&format!("fn {}\n", instance));
+    }
+    for instance in &instances.types {
+        actual.push_str(&format!("type {}\n", instance));
+    }
+    actual.push_str(&diagnostics.render(src, "main.fe"));
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn type_arguments_are_inferred_from_arguments() {
+    check(
+        "fn max<T>(a: T, b: T) -> T {}\nfn pair<A, B>(a: A, b: B) {}\nstruct Point { x: int }\nfn main() {\n    max(1, 2);\n    max(1.5, 2.5);\n    max(\"a\", \"b\");\n    let a: long = 1;\n    max(a, a);\n    max(Point { x: 1 }, Point { x: 2 });\n    pair(true, 'c');\n    max(1, 2);\n}\n",
+        expect![[r#"
+            fn max<i32>
+            fn max<f64>
+            fn max<string>
+            fn max<i64>
+            fn max<Point>
+            fn pair<bool, char>
+        "#]],
+    );
+}
+
+#[test]
+fn turbofish_gives_type_arguments() {
+    check(
+        "fn zero<T>() -> T {}\nstruct List<T> { head: T }\nfn main() {\n    zero::<int>();\n    zero::<List<double>>();\n    let l: List<bool> = zero::<List<bool>>();\n    zero::<i32>();\n}\n",
+        expect![[r#"
+            fn zero<i32>
+            fn zero<List<f64>>
+            fn zero<List<bool>>
+            type List<f64>
+            type List<bool>
+        "#]],
+    );
+}
+
+#[test]
+fn generic_code_instantiates_more_code() {
+    check(
+        "struct List<T> { head: T }\nenum Option<T> { Some(T), None }\nfn wrap<T>(a: T) -> List<T> {\n    let inner: Option<T> = make();\n    first(a);\n}\nfn first<U>(a: U) {\n    let l = List { head: a };\n}\nfn main() {\n    wrap(1);\n    wrap(true);\n}\n",
+        expect![[r#"
+            fn wrap<i32>
+            fn wrap<bool>
+            fn first<i32>
+            fn first<bool>
+            type List<i32>
+            type List<bool>
+            type Option<i32>
+            type Option<bool>
+        "#]],
+    );
+}
+
+#[test]
+fn type_argument_errors() {
+    check(
+        "fn max<T>(a: T, b: T) -> T {}\nfn zero<T>() -> T {}\nstruct List<T> { head: T }\nfn main() {\n    max(1, true);\n    zero();\n    max::<int, bool>(1, 2);\n    let l: List<int, int> = make();\n    let p: List = make();\n}\n",
+        expect![[r#"
+            error[E0208]: conflicting types for type parameter `T` of `max`
+             --> main.fe:5:12
+              |
+            5 |     max(1, true);
+              |         - `T` is `i32` here
+              |            ^^^^ `T` is `bool` here
+
+            error[E0207]: cannot infer type parameter `T` of `zero`
+             --> main.fe:6:5
+              |
+            6 |     zero();
+              |     ^^^^ cannot infer `T`
+              = help: give the type arguments with a turbofish, like `zero::<T>`
+
+            error[E0206]: `max` expects 1 type argument, found 2
+             --> main.fe:7:10
+              |
+            1 | fn max<T>(a: T, b: T) -> T {}
+              |    --- declared here
+            7 |     max::<int, bool>(1, 2);
+              |          ^^^^^^^^^^^ expected 1 type argument
+
+            error[E0206]: `List` expects 1 type argument, found 2
+             --> main.fe:8:16
+              |
+            3 | struct List<T> { head: T }
+              |        ---- declared here
+            8 |     let l: List<int, int> = make();
+              |                ^^^^^^^^^^ expected 1 type argument
+
+            error[E0206]: `List` expects 1 type argument, found 0
+             --> main.fe:9:12
+              |
+            3 | struct List<T> { head: T }
+              |        ---- declared here
+            9 |     let p: List = make();
+              |            ^^^^ expected 1 type argument
+        "#]],
+    );
+}
+
+#[test]
+fn recursive_instantiation_is_limited() {
+    let src = "struct List<T> { head: T }\nfn grow<T>(a: T) {\n    grow(List { head: a });\n}\nfn main() {\n    grow(1);\n}\n";
+    let ast = generate_ast(tokenize(src));
+    let mut diagnostics = Diagnostics::new();
+    let instances = ferrousc_semantic::monomorphize(&ast, &mut diagnostics);
+
+    // grow<i32> up to grow<List<...<i32>...>> with 31 lists
+    assert_eq!(instances.functions.len(), 32);
+    expect![[r#"
+        error[E0209]: instantiating `grow` never ends
+         --> main.fe:3:5
+          |
+        3 |     grow(List { head: a });
+          |     ^^^^ every instance of `grow` needs another one with larger types
+          = note: type arguments can be nested at most 32 levels deep
+    "#]].assert_eq(&diagnostics.render(src, "main.fe"));
+}
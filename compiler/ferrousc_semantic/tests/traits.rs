@@ -0,0 +1,189 @@
+use expect_test::{expect, Expect};
+use ferrousc_diagnostics::Diagnostics;
+use ferrousc_lexer::tokenize;
+use ferrousc_parser::generate_ast;
+
+fn check(src: &str, expect: Expect) {
+    let ast = generate_ast(tokenize(src));
+    let mut diagnostics = Diagnostics::new();
+    ferrousc_semantic::check(&ast, &mut diagnostics);
+    diagnostics.sort();
+
+    expect.assert_eq(&diagnostics.render(src, "main.fe"));
+}
+
+const SHAPE: &str = "trait Shape {\n    fn area(self) -> f64;\n    fn name(self) -> string => \"shape\";\n}\nstruct Circle { r: f64 }\nstruct Square { a: f64 }\n";
+
+#[test]
+fn impls_implement_every_required_function() {
+    check(&format!("{}impl Shape for Circle {{\n    fn area(mut self) -> f64 => self.r;\n}}\n", SHAPE), expect![[""]]);
+    check(&format!("{}impl Shape for Circle {{\n    fn name(self) -> string => \"circle\";\n}}\n", SHAPE), expect![[r#"
+        error[E0200]: missing `area` in impl of `Shape` for `Circle`
+         --> main.fe:7:6
+          |
+        2 |     fn area(self) -> f64;
+          |        ---- declared here
+        7 | impl Shape for Circle {
+          |      ^^^^^ missing `area`
+          = help: implement `fn area(self) -> f64`
+    "#]]);
+}
+
+#[test]
+fn impls_match_the_trait() {
+    check(
+        &format!("{}impl Shape for Circle {{\n    fn area(self, scale: f64) -> f64 => self.r;\n    fn radius(self) -> f64 => self.r;\n}}\nimpl Drawable for Circle {{}}\n", SHAPE),
+        expect![[r#"
+            error[E0201]: `area` does not match its declaration in trait `Shape`
+             --> main.fe:8:8
+              |
+            2 |     fn area(self) -> f64;
+              |        ---- declared here
+            8 |     fn area(self, scale: f64) -> f64 => self.r;
+              |        ^^^^ expected `fn area(self) -> f64`
+              = note: found `fn area(self, f64) -> f64`
+
+            error[E0202]: `radius` is not a function of trait `Shape`
+             --> main.fe:9:8
+              |
+            1 | trait Shape {
+              |       ----- trait declared here
+            9 |     fn radius(self) -> f64 => self.r;
+              |        ^^^^^^
+              = help: move it into an impl block without a trait
+
+            error[E0203]: cannot find trait `Drawable`
+              --> main.fe:11:6
+               |
+            11 | impl Drawable for Circle {}
+               |      ^^^^^^^^ not a trait
+        "#]],
+    );
+}
+
+#[test]
+fn bounds_on_parameters() {
+    check(
+        &format!(
+            "{}impl Shape for Circle {{ fn area(self) -> f64 => 0; }}\nfn total(a: impl Shape, b: impl Shape + Named) {{}}\nfn print(s: impl Shape) {{\n    let c = Circle {{ r: 1 }};\n    let q: Square = make();\n    total(c, s);\n    total(q, Circle {{ r: 2 }});\n    total(s, (q));\n}}\n",
+            SHAPE
+        ),
+        expect![[r#"
+            error[E0203]: cannot find trait `Named`
+             --> main.fe:8:41
+              |
+            8 | fn total(a: impl Shape, b: impl Shape + Named) {}
+              |                                         ^^^^^ not a trait
+
+            error[E0205]: `Square` does not implement trait `Shape`
+              --> main.fe:13:11
+               |
+             8 | fn total(a: impl Shape, b: impl Shape + Named) {}
+               |                  ----- required by this bound
+            13 |     total(q, Circle { r: 2 });
+               |           ^ `Square` does not implement `Shape`
+
+            error[E0205]: `Square` does not implement trait `Shape`
+              --> main.fe:14:14
+               |
+             8 | fn total(a: impl Shape, b: impl Shape + Named) {}
+               |                                 ----- required by this bound
+            14 |     total(s, (q));
+               |              ^^^ `Square` does not implement `Shape`
+        "#]],
+    );
+}
+
+#[test]
+fn only_trait_functions_leave_out_their_body() {
+    check("fn f();\nimpl Circle { fn g(self); }\ntrait T { fn h(); }", expect![[r#"
+        error[E0204]: `f` has no body
+         --> main.fe:1:4
+          |
+        1 | fn f();
+          |    ^
+          = note: only functions of traits can leave out their body
+        help: add a body
+          |
+        1 | fn f() {}
+
+        error[E0204]: `g` has no body
+         --> main.fe:2:18
+          |
+        2 | impl Circle { fn g(self); }
+          |                  ^
+          = note: only functions of traits can leave out their body
+        help: add a body
+          |
+        2 | impl Circle { fn g(self) {} }
+    "#]]);
+}
+
+#[test]
+fn bounds_on_type_parameters() {
+    check(
+        &format!(
+            "{}impl Shape for Circle {{ fn area(self) -> f64 => 0; }}\nstruct Bag<T: Shape> {{ item: T }}\nfn larger<T: Shape + Named, U>(a: T, b: T, c: U) -> Bag<U> {{}}\nfn print<S: Shape>(s: S) {{\n    let q: Square = make();\n    larger(s, s, q);\n    larger::<Square, int>(q, q, 1);\n    let b: Bag<Circle> = make();\n    let d: Bag<S> = make();\n    let e: Bag<Square> = make();\n}}\n",
+            SHAPE
+        ),
+        expect![[r#"
+            error[E0203]: cannot find trait `Named`
+             --> main.fe:9:22
+              |
+            9 | fn larger<T: Shape + Named, U>(a: T, b: T, c: U) -> Bag<U> {}
+              |                      ^^^^^ not a trait
+
+            error[E0205]: `U` does not implement trait `Shape`
+             --> main.fe:9:57
+              |
+            8 | struct Bag<T: Shape> { item: T }
+              |               ----- required by this bound
+            9 | fn larger<T: Shape + Named, U>(a: T, b: T, c: U) -> Bag<U> {}
+              |                                                         ^ `U` does not implement `Shape`
+
+            error[E0205]: `Square` does not implement trait `Shape`
+              --> main.fe:13:14
+               |
+             9 | fn larger<T: Shape + Named, U>(a: T, b: T, c: U) -> Bag<U> {}
+               |              ----- required by this bound
+            13 |     larger::<Square, int>(q, q, 1);
+               |              ^^^^^^ `Square` does not implement `Shape`
+
+            error[E0205]: `Square` does not implement trait `Shape`
+              --> main.fe:16:16
+               |
+             8 | struct Bag<T: Shape> { item: T }
+               |               ----- required by this bound
+            16 |     let e: Bag<Square> = make();
+               |                ^^^^^^ `Square` does not implement `Shape`
+        "#]],
+    );
+}
+
+#[test]
+fn aliases_are_the_same_type() {
+    check(
+        "trait S { fn area(self) -> double; }\nstruct C { r: f64 }\nimpl S for C { fn area(self) -> f64 => self.r; }\ntrait Ord {}\nimpl Ord for int {}\nfn max<T: Ord>(a: T, b: T) -> T => a;\nfn main() {\n    let x: i32 = 1;\n    max(x, x);\n    max::<i32>(x, x);\n}\n",
+        expect![[""]],
+    );
+}
+
+#[test]
+fn type_parameters_are_compared_by_position() {
+    check(
+        &format!(
+            "{}trait S {{\n    fn f<T>(self, a: T);\n    fn g<T, U: Shape>(self, a: List<T>, b: U) -> T;\n    fn h<T: Shape>(self, a: T);\n}}\nimpl S for Circle {{\n    fn f<U>(self, a: U) {{}}\n    fn g<A, B: Shape>(self, a: List<A>, b: B) -> A => a.first();\n    fn h<T>(self, a: T) {{}}\n}}\n",
+            SHAPE
+        ),
+        expect![[r#"
+            error[E0201]: `h` does not match its declaration in trait `S`
+              --> main.fe:15:8
+               |
+            10 |     fn h<T: Shape>(self, a: T);
+               |        - declared here
+            15 |     fn h<T>(self, a: T) {}
+               |        ^ expected `fn h<T: Shape>(self, T)`
+               = note: found `fn h<T>(self, T)`
+        "#]],
+    );
+}